@@ -71,8 +71,8 @@ fn main() -> image::ImageResult<()> {
 
     let light_source = Light::new_point_light(Point(-10.0, 10.0, -10.0), Color::white());
 
-    let world = World {
-        objects: vec![
+    let world = World::new(
+        vec![
             floor,
             left_wall,
             right_wall,
@@ -80,15 +80,15 @@ fn main() -> image::ImageResult<()> {
             middle_sphere,
             right_sphere,
         ],
-        lights: vec![light_source],
-    };
+        vec![light_source],
+    );
 
     let cam = Camera::new(800, 750, PI / 3.0).with_transform(&Matrix::view_transform(
         Point(0.0, 1.5, -5.0),
         Point(0.0, 1.0, 0.0),
         Vec3(0.0, 1.0, 0.0),
     ));
-    let canvas = cam.render(&world, 5).unwrap();
+    let canvas = cam.render_default_depth(&world).unwrap();
 
     canvas.export("img/chapter7.png")
 }
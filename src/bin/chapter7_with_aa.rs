@@ -71,8 +71,8 @@ fn main() -> image::ImageResult<()> {
 
     let light_source = Light::new_point_light(Point(-10.0, 10.0, -10.0), Color::white());
 
-    let world = World {
-        objects: vec![
+    let world = World::new(
+        vec![
             floor,
             left_wall,
             right_wall,
@@ -80,8 +80,8 @@ fn main() -> image::ImageResult<()> {
             middle_sphere,
             right_sphere,
         ],
-        lights: vec![light_source],
-    };
+        vec![light_source],
+    );
 
     let cam = Camera::new(800, 750, PI / 3.0)
         .with_transform(&Matrix::view_transform(
@@ -91,6 +91,6 @@ fn main() -> image::ImageResult<()> {
         ))
         .with_antialiasing(20);
 
-    let canvas = cam.render(&world, 5).unwrap();
+    let canvas = cam.render_default_depth(&world).unwrap();
     canvas.export("img/chapter7_aa.png")
 }
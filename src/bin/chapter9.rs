@@ -51,10 +51,10 @@ fn main() -> image::ImageResult<()> {
 
     let light_source = Light::new_point_light(Point(-10.0, 10.0, -10.0), Color::white());
 
-    let world = World {
-        objects: vec![floor, left_sphere, middle_sphere, right_sphere],
-        lights: vec![light_source],
-    };
+    let world = World::new(
+        vec![floor, left_sphere, middle_sphere, right_sphere],
+        vec![light_source],
+    );
 
     let cam = Camera::new(800, 750, PI / 3.0)
         .with_transform(&Matrix::view_transform(
@@ -64,6 +64,6 @@ fn main() -> image::ImageResult<()> {
         ))
         .with_antialiasing(10);
 
-    let canvas = cam.render(&world, 5).unwrap();
+    let canvas = cam.render_default_depth(&world).unwrap();
     canvas.export("img/chapter9.png")
 }
@@ -22,6 +22,7 @@ fn main() -> image::ImageResult<()> {
     let sphere = Sphere {
         material: sphere_mat,
         transform: rtc::math::Matrix::scaling(2.5, 2.5, 2.5),
+        name: None,
     };
 
     let light_pos = Point(-10.0, 10.0, -10.0);
@@ -46,7 +47,7 @@ fn main() -> image::ImageResult<()> {
                     let r = Ray::new(ray_origin, (position - ray_origin).normalize());
                     let xs = sphere.as_shape().intersect(r);
 
-                    if let Some(mut intersections) = xs {
+                    if let Some(intersections) = xs {
                         if let Some(hit) = intersections.hit() {
                             let hit_point = r.position(hit.t);
                             let normal = hit.object.normal_at(hit_point).unwrap();
@@ -77,5 +78,8 @@ fn get_material(shape: &Shape) -> Material {
     match *shape {
         Shape::Sphere(ref sphere) => sphere.material.clone(),
         Shape::Plane(ref plane) => plane.material.clone(),
+        Shape::Quad(ref quad) => quad.material.clone(),
+        Shape::Portal(_) => Material::default(),
+        Shape::Custom(ref custom) => custom.material(),
     }
 }
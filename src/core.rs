@@ -2,16 +2,21 @@
 //!
 //! Allows for the creation of "intersectable" objects/shapes, organizing them into meaningful
 //! lists, and determining collisions between rays and those objects.
-use crate::math::{Point, Vec3};
+use std::fmt::Debug;
+
+use crate::math::{Matrix, Point, Vec3};
 use crate::shape::Shape;
 
+pub mod animation;
 pub mod antialias;
 pub mod camera;
 pub mod light;
 pub mod material;
+pub mod materials;
 pub mod pattern;
 pub mod precompute;
 pub mod ray;
+pub mod stats;
 pub mod world;
 
 pub use crate::core::camera::Camera;
@@ -23,8 +28,9 @@ pub use crate::core::world::World;
 
 pub const EPS: f64 = 0.00001;
 
-/// A trait for defining which objects are able to be hit by rays.
-pub trait Intersectable {
+/// A trait for defining which objects are able to be hit by rays. Implement it for your own
+/// geometry and wrap it in [Shape::Custom] to render it without changing this crate.
+pub trait Intersectable: Debug {
     /// Intersects the object with the specified ray. Stores each intersection in a growable list.
     /// Returns `None` if no hits were found.
     fn intersect(&self, r: ray::Ray) -> Option<IntersectionList>;
@@ -32,6 +38,23 @@ pub trait Intersectable {
     /// Computes the normal vector at the given point in world-space coordinates. Returns `None` if
     /// the normal can't be computed. This happens when the inverse transform matrix doesn't exist.
     fn normal_at(&self, world_pt: Point) -> Option<Vec3>;
+
+    /// The material the object is shaded with. Defaults to [Material::default].
+    fn material(&self) -> Material {
+        Material::default()
+    }
+
+    /// The object's transform, which patterns on it are positioned relative to. Defaults to the
+    /// identity.
+    fn transform(&self) -> Matrix<4> {
+        Matrix::identity()
+    }
+
+    /// The world-space, axis-aligned bounding box of the object as its `(min, max)` corners, or
+    /// `None` if it's unbounded (the default).
+    fn bounds(&self) -> Option<(Point, Point)> {
+        None
+    }
 }
 
 /// Stores data from intersections; specifically, the times `t` of the intersection(s) and the
@@ -67,19 +90,63 @@ impl IntersectionList {
         Self { data: list }
     }
 
-    /// Sorts the `IntersectionList` data and finds the minimum positive `t` value. Right now it
-    /// filters to ensure `t` is positive and that `t` is neither [INF](f64::INFINITY) nor
-    /// [NaN](f64::NAN). Infinity may be useful in the future? So this may need to be adjusted.
-    /// (Note to self...)
-    pub fn hit(&mut self) -> Option<&Intersection> {
-        self.data
-            .sort_unstable_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
-
+    /// Finds the intersection with the minimum positive `t` value. Right now it filters to ensure
+    /// `t` is positive and that `t` is neither [INF](f64::INFINITY) nor [NaN](f64::NAN). Infinity
+    /// may be useful in the future? So this may need to be adjusted. (Note to self...)
+    ///
+    /// This doesn't depend on the order of the data, so nothing needs to be sorted first.
+    pub fn hit(&self) -> Option<&Intersection> {
         self.data
             .iter()
             .filter(|x| x.t.is_finite() && x.t.is_sign_positive())
             .min_by(|x, y| x.t.partial_cmp(&y.t).unwrap())
     }
+
+    /// Every intersection with a positive (and finite) `t`, from nearest to farthest. The first one
+    /// is the [hit](IntersectionList::hit).
+    pub fn hits(&self) -> impl Iterator<Item = &Intersection> {
+        let mut hits = self
+            .data
+            .iter()
+            .filter(|x| x.t.is_finite() && x.t.is_sign_positive())
+            .collect::<Vec<_>>();
+        hits.sort_by(|x, y| x.t.partial_cmp(&y.t).unwrap());
+
+        hits.into_iter()
+    }
+
+    /// Every intersection with the given shape, in list order.
+    pub fn with_object<'a>(&'a self, shape: &'a Shape) -> impl Iterator<Item = &'a Intersection> {
+        self.data.iter().filter(move |x| x.object == *shape)
+    }
+
+    /// Moves every intersection from `other` into this list, keeping the list sorted by `t`. Meant
+    /// for combining the lists of several shapes, e.g. the children of a group.
+    pub fn extend(&mut self, mut other: IntersectionList) {
+        self.data.append(&mut other.data);
+        // a stable sort spots the two already-sorted runs, so this is a linear-time merge
+        self.data.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
+    }
+
+    /// Combines two lists into one sorted by `t`. See [IntersectionList::extend].
+    pub fn merge(mut self, other: IntersectionList) -> Self {
+        self.extend(other);
+        self
+    }
+
+    /// The number of intersections in the list.
+    pub fn count(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Sorts the data by `t` in place before finding the hit, like `hit` used to.
+    #[deprecated(note = "use `hit`, which doesn't need to sort or borrow mutably")]
+    pub fn sort_and_hit(&mut self) -> Option<&Intersection> {
+        self.data
+            .sort_unstable_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
+
+        self.hit()
+    }
 }
 
 impl std::ops::Index<usize> for IntersectionList {
@@ -0,0 +1,132 @@
+//! Rendering a scene as a sequence of frames.
+//!
+//! A closure gets a chance to move things around before each frame is rendered, and the resulting
+//! canvases can be written out as numbered images for assembling into a GIF or video.
+use super::{Camera, World};
+use crate::{io::error::RenderError, visuals::Canvas};
+
+use std::path::Path;
+
+/// Renders `frames` images of the world. Before frame `i` is rendered, `update(i, camera, world)`
+/// is called so it can move the camera or change the world; changes carry over to later frames.
+/// Each frame uses the world's [reflection depth](World::reflection_depth).
+///
+/// # Example
+/// ```ignore
+/// let frames = render_sequence(&mut cam, &mut world, 60, |i, _, world| {
+///     let angle = i as f64 / 60.0 * 2.0 * PI;
+///     world.objects[0] = Sphere::default()
+///         .with_transform(&Matrix::translation(angle.cos(), 0.0, angle.sin()))
+///         .as_shape();
+/// })
+/// .unwrap();
+/// export_frames(&frames, "frames").unwrap();
+/// ```
+pub fn render_sequence<F>(
+    camera: &mut Camera,
+    world: &mut World,
+    frames: usize,
+    mut update: F,
+) -> Result<Vec<Canvas>, RenderError>
+where
+    F: FnMut(usize, &mut Camera, &mut World),
+{
+    (0..frames)
+        .map(|i| {
+            update(i, camera, world);
+            camera.render_default_depth(world)
+        })
+        .collect()
+}
+
+/// Exports each frame as a PNG into `dir`, named `frame_0000.png`, `frame_0001.png`, and so on.
+/// The directory must already exist.
+pub fn export_frames<P: AsRef<Path>>(frames: &[Canvas], dir: P) -> image::ImageResult<()> {
+    for (i, frame) in frames.iter().enumerate() {
+        let path = dir.as_ref().join(format!("frame_{:04}.png", i));
+        frame.export(&path.to_string_lossy())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod animation_tests {
+    use super::*;
+    use crate::{
+        core::Light,
+        math::{Matrix, Point, Vec3},
+        shape::Sphere,
+        visuals::Color,
+    };
+
+    /// The average column of every pixel that isn't background.
+    fn lit_column(canvas: &Canvas) -> f64 {
+        let (mut sum, mut count) = (0.0, 0.0);
+        for y in 0..canvas.height {
+            for x in 0..canvas.width {
+                if canvas.read_pixel(x, y) != Color::black() {
+                    sum += x as f64;
+                    count += 1.0;
+                }
+            }
+        }
+        assert!(count > 0.0, "the sphere should be visible");
+
+        sum / count
+    }
+
+    #[test]
+    fn rendering_a_translating_sphere() {
+        let mut world = World::new(
+            vec![Sphere::default().as_shape()],
+            vec![Light::new_point_light(
+                Point(-10.0, 10.0, -10.0),
+                Color::white(),
+            )],
+        );
+        let mut cam = Camera::new(21, 11, std::f64::consts::PI / 2.0).with_transform(
+            &Matrix::view_transform(
+                Point(0.0, 0.0, -5.0),
+                Point(0.0, 0.0, 0.0),
+                Vec3(0.0, 1.0, 0.0),
+            ),
+        );
+
+        let frames = render_sequence(&mut cam, &mut world, 3, |i, _, world| {
+            world.objects[0] = Sphere::default()
+                .with_transform(&Matrix::translation(i as f64 * 1.5 - 1.5, 0.0, 0.0))
+                .as_shape();
+        })
+        .unwrap();
+
+        assert_eq!(frames.len(), 3);
+        let columns = frames.iter().map(lit_column).collect::<Vec<_>>();
+        assert!(columns[0] < columns[1] && columns[1] < columns[2]);
+    }
+
+    #[test]
+    fn updates_can_move_the_camera() {
+        let mut world = World::new(
+            vec![Sphere::default().as_shape()],
+            vec![Light::new_point_light(
+                Point(-10.0, 10.0, -10.0),
+                Color::white(),
+            )],
+        );
+        let mut cam = Camera::new(21, 11, std::f64::consts::PI / 2.0);
+
+        let frames = render_sequence(&mut cam, &mut world, 2, |i, cam, _| {
+            let from = Point(i as f64 * 2.0, 0.0, -5.0);
+            cam.set_transform(&Matrix::view_transform(
+                from,
+                Point(i as f64 * 2.0, 0.0, 0.0),
+                Vec3(0.0, 1.0, 0.0),
+            ));
+        })
+        .unwrap();
+
+        // Sliding the camera right makes the sphere slide left.
+        assert!(lit_column(&frames[1]) < lit_column(&frames[0]));
+    }
+}
@@ -25,11 +25,13 @@
 //! This would require refactoring in the yaml parser and (probably) `Camera` too, though.
 use super::{Camera, World};
 use crate::visuals::Color;
-use rand::{distributions::Uniform, prelude::*};
+use rand::{distributions::Uniform, prelude::*, rngs::StdRng};
 
 pub enum AAMethod {
     Stochastic(Stochastic),
     Multisampling(Multisampling),
+    Grid(GridSupersampling),
+    Adaptive(Adaptive),
 }
 
 /// Holds the information needed to apply the antialiasing.
@@ -37,12 +39,16 @@ pub struct AntiAliasing {
     pub method: AAMethod,
     pub level: usize,
     pub error_tolerance: f64,
+    /// Seeds the random sampling when set. See [AntiAliasing::with_seed].
+    pub seed: Option<u64>,
 }
 
 impl AntiAliasing {
     /// Does the actual antialiasing using an [AAMethod](crate::core::antialias::AAMethod). At the
-    /// moment, only [Stochastic](crate::core::antialias::Stochastic) and
-    /// [Multisampling](crate::core::antialias::Stochastic) are available.
+    /// moment, [Stochastic](crate::core::antialias::Stochastic),
+    /// [Multisampling](crate::core::antialias::Multisampling),
+    /// [GridSupersampling](crate::core::antialias::GridSupersampling), and
+    /// [Adaptive](crate::core::antialias::Adaptive) are available.
     pub fn anti_alias(
         &self,
         px: usize,
@@ -51,9 +57,33 @@ impl AntiAliasing {
         world_depth: usize,
         cam: &Camera,
     ) -> Color {
-        match self.method {
-            AAMethod::Stochastic(ref s) => s.anti_alias(px, py, world, world_depth, cam),
-            AAMethod::Multisampling(ref m) => m.anti_alias(px, py, world, world_depth, cam),
+        self.with_pixel_rng(py * cam.hsize() + px, |rng| match self.method {
+            AAMethod::Stochastic(ref s) => s.anti_alias(px, py, world, world_depth, cam, rng),
+            AAMethod::Multisampling(ref m) => m.anti_alias(px, py, world, world_depth, cam, rng),
+            AAMethod::Grid(ref g) => g.anti_alias(px, py, world, world_depth, cam, rng),
+            AAMethod::Adaptive(ref a) => a.anti_alias(px, py, world, world_depth, cam, rng),
+        })
+    }
+
+    /// Makes the random sampling reproducible: sub-pixel offsets, the lens for depth of field,
+    /// fuzzy reflections, and Russian roulette. Each pixel gets its own generator, seeded from a
+    /// mix of `seed` and the pixel's index, so the result doesn't depend on the order pixels are
+    /// rendered in.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Calls `f` with the random number generator for the pixel at `index` (counting across
+    /// rows): seeded if [AntiAliasing::with_seed] was used, and the thread's generator otherwise.
+    pub(crate) fn with_pixel_rng<T>(
+        &self,
+        index: usize,
+        f: impl FnOnce(&mut dyn RngCore) -> T,
+    ) -> T {
+        match self.seed {
+            Some(seed) => f(&mut StdRng::seed_from_u64(pixel_seed(seed, index as u64))),
+            None => f(&mut thread_rng()),
         }
     }
 
@@ -62,6 +92,10 @@ impl AntiAliasing {
         self
     }
 
+    /// Sets the anti-aliasing level. A [Camera] treats level 0 as "off" and casts a single ray
+    /// through each pixel's center. The sampling methods themselves always cast at least one ray,
+    /// so level 0 becomes level 1 for them (for [Adaptive], the level is a subdivision depth and 0
+    /// is allowed).
     pub fn with_level(mut self, aa_level: usize) -> Self {
         self.level = aa_level;
         self.set_method_level(aa_level);
@@ -77,18 +111,35 @@ impl AntiAliasing {
     fn set_method_tolerance(&mut self, etol: f64) {
         match self.method {
             AAMethod::Multisampling(ref mut m) => m.error_tolerance = etol,
-            _ => return,
+            AAMethod::Adaptive(ref mut a) => a.error_tolerance = etol,
+            _ => {}
         }
     }
 
     fn set_method_level(&mut self, aa_level: usize) {
         match self.method {
-            AAMethod::Stochastic(ref mut s) => s.level = aa_level,
-            AAMethod::Multisampling(ref mut m) => m.level = aa_level,
+            AAMethod::Stochastic(ref mut s) => s.level = aa_level.max(1),
+            AAMethod::Multisampling(ref mut m) => m.level = aa_level.max(1),
+            AAMethod::Grid(ref mut g) => g.n = aa_level.max(1),
+            AAMethod::Adaptive(ref mut a) => a.max_depth = aa_level,
         }
     }
 }
 
+/// Combines a render's seed with a pixel's index. Simply adding them would give pixel `i + 1` of
+/// seed `s` the same samples as pixel `i` of seed `s + 1`, so both go through the SplitMix64
+/// finalizer first.
+fn pixel_seed(seed: u64, index: u64) -> u64 {
+    let mix = |mut z: u64| {
+        z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+
+    mix(mix(seed) ^ index)
+}
+
 #[derive(Clone)]
 pub struct Stochastic {
     level: usize,
@@ -102,17 +153,17 @@ impl Stochastic {
         world: &World,
         world_depth: usize,
         cam: &Camera,
+        rng: &mut dyn RngCore,
     ) -> Color {
         let mut color = Color::black();
-        let mut rng = thread_rng();
         let uniform = Uniform::new(0.0, 1.0);
 
         for _ in 0..self.level {
-            let xoffset = uniform.sample(&mut rng);
-            let yoffset = uniform.sample(&mut rng);
+            let xoffset = uniform.sample(rng);
+            let yoffset = uniform.sample(rng);
 
-            if let Some(ray) = cam.ray_for_pixel(px, py, xoffset, yoffset) {
-                color = color + world.color_at(ray, world_depth)
+            if let Some(ray) = cam.ray_for_pixel_with(px, py, xoffset, yoffset, rng) {
+                color = color + world.primary_color(ray, world_depth, rng)
             }
         }
 
@@ -120,13 +171,37 @@ impl Stochastic {
     }
 }
 
+/// Keeps casting random rays through the pixel until the standard error of the mean color drops
+/// below the error tolerance. At least `level` rays are cast, and never more than `max_samples`;
+/// if `level` is the larger of the two, the cap wins.
 #[derive(Clone)]
 pub struct Multisampling {
     level: usize,
     error_tolerance: f64,
+    max_samples: usize,
 }
 
 impl Multisampling {
+    /// Sets the standard error of the mean color (summed over the channels' variances) at which
+    /// sampling stops. A quality target rather than a sample count: flat pixels stop after the
+    /// minimum, noisy ones keep going up to the cap (see [Multisampling::with_max_samples]).
+    pub fn with_confidence(mut self, target_stderr: f64) -> Self {
+        self.error_tolerance = target_stderr;
+        self
+    }
+
+    /// Caps the number of rays per pixel, so a target the pixel can't reach doesn't sample
+    /// forever. The cap also applies to the minimum of `level` rays.
+    pub fn with_max_samples(mut self, max_samples: usize) -> Self {
+        self.max_samples = max_samples;
+        self
+    }
+
+    /// The most rays cast through any one pixel, even if the anti-aliasing level asks for more.
+    pub fn max_samples(&self) -> usize {
+        self.max_samples
+    }
+
     fn anti_alias(
         &self,
         px: usize,
@@ -134,8 +209,8 @@ impl Multisampling {
         world: &World,
         world_depth: usize,
         cam: &Camera,
+        rng: &mut dyn RngCore,
     ) -> Color {
-        let mut rng = thread_rng();
         let uniform = Uniform::new(0.0, 1.0);
 
         let color = Color::black();
@@ -143,12 +218,17 @@ impl Multisampling {
         let mut color_sum = color;
         let mut n = 0.0;
 
-        while n < self.level as f64 {
-            let xoffset = uniform.sample(&mut rng);
-            let yoffset = uniform.sample(&mut rng);
+        // samples that can't be cast (e.g. through a singular camera transform) count as black
+        while (n as usize) < self.max_samples.max(1)
+            && ((n as usize) < self.level.max(1)
+                || self.color_mean_variance(n, color_squared_sum, color_sum)
+                    > self.error_tolerance * self.error_tolerance)
+        {
+            let xoffset = uniform.sample(rng);
+            let yoffset = uniform.sample(rng);
 
-            if let Some(ray) = cam.ray_for_pixel(px, py, xoffset, yoffset) {
-                let color = world.color_at(ray, world_depth);
+            if let Some(ray) = cam.ray_for_pixel_with(px, py, xoffset, yoffset, rng) {
+                let color = world.primary_color(ray, world_depth, rng);
                 color_sum = color_sum + color;
                 color_squared_sum = color_squared_sum + color * color;
             }
@@ -156,20 +236,6 @@ impl Multisampling {
             n += 1.0;
         }
 
-        while self.color_mean_variance(n, color_squared_sum, color_sum)
-            > self.error_tolerance * self.error_tolerance
-        {
-            let xoffset = uniform.sample(&mut rng);
-            let yoffset = uniform.sample(&mut rng);
-
-            if let Some(ray) = cam.ray_for_pixel(px, py, xoffset, yoffset) {
-                let color = world.color_at(ray, world_depth);
-                color_sum = color_sum + color;
-                color_squared_sum = color_squared_sum + color * color;
-                n += 1.0;
-            }
-        }
-
         color_sum / n
     }
 
@@ -182,12 +248,148 @@ impl Multisampling {
     }
 }
 
+/// Casts an `n`×`n` grid of evenly spaced rays through each pixel and averages them. Unlike the
+/// other methods there's no randomness, so renders are exactly reproducible. The anti-aliasing
+/// level sets `n`.
+#[derive(Clone)]
+pub struct GridSupersampling {
+    n: usize,
+}
+
+impl GridSupersampling {
+    /// Creates a grid with `n` rays along each side of the pixel (at least 1).
+    pub fn new(n: usize) -> Self {
+        Self { n: n.max(1) }
+    }
+
+    fn anti_alias(
+        &self,
+        px: usize,
+        py: usize,
+        world: &World,
+        world_depth: usize,
+        cam: &Camera,
+        rng: &mut dyn RngCore,
+    ) -> Color {
+        let n = self.n.max(1);
+        let mut color = Color::black();
+
+        for j in 0..n {
+            for i in 0..n {
+                let xoffset = (i as f64 + 0.5) / n as f64;
+                let yoffset = (j as f64 + 0.5) / n as f64;
+
+                if let Some(ray) = cam.ray_for_pixel_with(px, py, xoffset, yoffset, rng) {
+                    color = color + world.primary_color(ray, world_depth, rng)
+                }
+            }
+        }
+
+        color / (n * n) as f64
+    }
+}
+
+/// Samples the center and corners of each pixel, and only subdivides it into quarters (and those
+/// quarters into quarters, and so on) where the corners differ from the center by more than the
+/// error tolerance, i.e. along edges. Flat regions get five rays per pixel, so this is much
+/// cheaper than uniformly high levels of the other methods in mostly flat scenes. The
+/// anti-aliasing level sets how many times a pixel may be subdivided.
+#[derive(Clone)]
+pub struct Adaptive {
+    max_depth: usize,
+    error_tolerance: f64,
+}
+
+impl Adaptive {
+    /// Creates an adaptive sampler which subdivides up to `max_depth` times wherever the summed
+    /// channel difference between a corner and the center exceeds `error_tolerance`.
+    pub fn new(max_depth: usize, error_tolerance: f64) -> Self {
+        Self {
+            max_depth,
+            error_tolerance,
+        }
+    }
+
+    /// How different a corner may be from the center before the square is subdivided.
+    pub fn error_tolerance(&self) -> f64 {
+        self.error_tolerance
+    }
+
+    fn anti_alias(
+        &self,
+        px: usize,
+        py: usize,
+        world: &World,
+        world_depth: usize,
+        cam: &Camera,
+        rng: &mut dyn RngCore,
+    ) -> Color {
+        let mut sample = |x: f64, y: f64| match cam.ray_for_pixel_with(px, py, x, y, rng) {
+            Some(ray) => world.primary_color(ray, world_depth, rng),
+            None => Color::black(),
+        };
+        let corners = [
+            sample(0.0, 0.0),
+            sample(1.0, 0.0),
+            sample(0.0, 1.0),
+            sample(1.0, 1.0),
+        ];
+
+        self.refine(&mut sample, (0.0, 0.0), 1.0, corners, 0)
+    }
+
+    /// Averages the square with top-left corner `(x, y)` and side `size` (in pixel offsets), whose
+    /// corner colors are already known (top-left, top-right, bottom-left, bottom-right).
+    fn refine<F: FnMut(f64, f64) -> Color>(
+        &self,
+        sample: &mut F,
+        (x, y): (f64, f64),
+        size: f64,
+        corners: [Color; 4],
+        depth: usize,
+    ) -> Color {
+        let h = size / 2.0;
+        let center = sample(x + h, y + h);
+
+        let differs = corners.iter().any(|&c| {
+            let d = c - center;
+            d.r().abs() + d.g().abs() + d.b().abs() > self.error_tolerance
+        });
+        if depth >= self.max_depth || !differs {
+            return (corners.iter().copied().sum::<Color>() + center) / 5.0;
+        }
+
+        // the midpoints of each side, shared between neighboring quarters
+        let [tl, tr, bl, br] = corners;
+        let top = sample(x + h, y);
+        let left = sample(x, y + h);
+        let right = sample(x + size, y + h);
+        let bottom = sample(x + h, y + size);
+
+        let quarters = [
+            self.refine(sample, (x, y), h, [tl, top, left, center], depth + 1),
+            self.refine(sample, (x + h, y), h, [top, tr, center, right], depth + 1),
+            self.refine(sample, (x, y + h), h, [left, center, bl, bottom], depth + 1),
+            self.refine(
+                sample,
+                (x + h, y + h),
+                h,
+                [center, right, bottom, br],
+                depth + 1,
+            ),
+        ];
+
+        quarters.iter().copied().sum::<Color>() / 4.0
+    }
+}
+
 impl Default for AntiAliasing {
     fn default() -> Self {
         Self {
             method: AAMethod::Stochastic(Stochastic::default()),
             error_tolerance: 1.0,
             level: 0,
+            seed: None,
         }
     }
 }
@@ -203,6 +405,185 @@ impl Default for Multisampling {
         Self {
             level: 5,
             error_tolerance: 1.0,
+            max_samples: 256,
         }
     }
 }
+
+impl Default for Adaptive {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            error_tolerance: 0.1,
+        }
+    }
+}
+
+impl Default for GridSupersampling {
+    fn default() -> Self {
+        Self { n: 2 }
+    }
+}
+
+#[cfg(test)]
+mod antialias_tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::{
+        core::{camera::test_world_and_camera, Light, Material},
+        math::{Matrix, Point, Vec3},
+        shape::{Plane, Sphere},
+    };
+
+    #[test]
+    fn grid_averages_evenly_spaced_subpixel_rays() {
+        let (w, cam) = test_world_and_camera(5);
+
+        // a pixel on the edge of the sphere, so the sub-pixel colors differ
+        let (px, py) = (1, 2);
+        let expected = [(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]
+            .iter()
+            .map(|&(x, y)| {
+                let ray = cam.ray_for_pixel(px, py, x, y).unwrap();
+                w.color_at(ray, 0, &mut thread_rng())
+            })
+            .fold(Color::black(), |acc, c| acc + c)
+            / 4.0;
+
+        let grid = GridSupersampling::new(2);
+        let rng = &mut StdRng::seed_from_u64(0);
+        assert_eq!(grid.anti_alias(px, py, &w, 0, &cam, rng), expected);
+    }
+
+    #[test]
+    fn adaptive_sampling_subdivides_edge_pixels() {
+        let (w, cam) = test_world_and_camera(5);
+        let w = w.with_stats();
+        let aa = AntiAliasing::default()
+            .with_method(AAMethod::Adaptive(Adaptive::default()))
+            .with_tolerance(0.1)
+            .with_level(2);
+        let rays_for = |px, py| {
+            w.reset_stats();
+            aa.anti_alias(px, py, &w, 0, &cam);
+            w.stats().unwrap().primary_rays
+        };
+
+        // the background around the sphere is flat, so its pixels only need the first five rays,
+        // while pixels on the sphere's silhouette are subdivided
+        assert_eq!(rays_for(0, 0), 5);
+        assert!(rays_for(1, 2) > 5);
+        assert!(rays_for(1, 2) <= 5 + 5 * 4 + 5 * 16);
+    }
+
+    #[test]
+    fn seeded_renders_are_reproducible() {
+        let w = test_world_and_camera(12).0;
+        let cam = |seed| {
+            test_world_and_camera(12)
+                .1
+                .with_antialiasing(4)
+                .with_aperture(0.2)
+                .with_focal_distance(3.0)
+                .with_seed(seed)
+        };
+
+        let first = cam(7).render(&w, 0).unwrap();
+        assert_eq!(cam(7).render(&w, 0).unwrap(), first);
+        assert_ne!(cam(8).render(&w, 0).unwrap(), first);
+    }
+
+    #[test]
+    fn seeded_renders_of_fuzzy_reflections_are_reproducible() {
+        let w = World::new(
+            vec![
+                Sphere::default().as_shape(),
+                Plane::default()
+                    .with_material(
+                        &Material::default()
+                            .with_reflective(0.8)
+                            .with_reflection_fuzz(0.3),
+                    )
+                    .with_transform(&Matrix::translation(0.0, -1.0, 0.0))
+                    .as_shape(),
+            ],
+            vec![Light::new_point_light(
+                Point(-10.0, 10.0, -10.0),
+                Color::white(),
+            )],
+        )
+        .with_russian_roulette();
+        let cam = |seed| {
+            Camera::new(10, 10, PI / 3.0)
+                .with_transform(&Matrix::view_transform(
+                    Point(0.0, 1.0, -5.0),
+                    Point(0.0, -1.0, 0.0),
+                    Vec3(0.0, 1.0, 0.0),
+                ))
+                .with_seed(seed)
+        };
+
+        let first = cam(7).render(&w, 2).unwrap();
+        assert_eq!(cam(7).render(&w, 2).unwrap(), first);
+        assert_ne!(cam(8).render(&w, 2).unwrap(), first);
+    }
+
+    #[test]
+    fn neighbouring_seeds_do_not_share_pixel_samples() {
+        for i in 0..100 {
+            assert_ne!(pixel_seed(1, i + 1), pixel_seed(2, i));
+        }
+    }
+
+    #[test]
+    fn multisampling_stops_at_the_target_or_the_cap() {
+        let (w, cam) = test_world_and_camera(5);
+        let w = w.with_stats();
+        // a target of zero can only be met by a perfectly flat pixel
+        let ms = Multisampling::default()
+            .with_confidence(0.0)
+            .with_max_samples(40);
+        let rays_for = |px, py| {
+            w.reset_stats();
+            ms.anti_alias(px, py, &w, 0, &cam, &mut StdRng::seed_from_u64(3));
+            w.stats().unwrap().primary_rays
+        };
+
+        assert_eq!(rays_for(0, 0), 5);
+        assert_eq!(rays_for(1, 2), 40);
+
+        // the cap wins over a larger level, even for a flat pixel
+        let capped = Multisampling::default().with_max_samples(3);
+        w.reset_stats();
+        capped.anti_alias(0, 0, &w, 0, &cam, &mut StdRng::seed_from_u64(3));
+        assert_eq!(w.stats().unwrap().primary_rays, 3);
+    }
+
+    #[test]
+    fn level_zero_falls_back_to_a_single_sample() {
+        let (w, cam) = test_world_and_camera(5);
+        let w = w.with_stats();
+
+        for method in [
+            AAMethod::Stochastic(Stochastic::default()),
+            AAMethod::Multisampling(Multisampling::default()),
+            AAMethod::Grid(GridSupersampling::default()),
+        ] {
+            let aa = AntiAliasing::default().with_method(method).with_level(0);
+            w.reset_stats();
+            let color = aa.anti_alias(2, 2, &w, 0, &cam);
+
+            assert_eq!(w.stats().unwrap().primary_rays, 1);
+            assert!([color.0, color.1, color.2].iter().all(|c| c.is_finite()));
+        }
+
+        // cameras skip anti-aliasing entirely at level 0, whatever the method
+        let plain = cam.render(&w, 0).unwrap();
+        let msaa = test_world_and_camera(5)
+            .1
+            .with_aa_method(AAMethod::Multisampling(Multisampling::default()))
+            .with_antialiasing(0);
+        assert_eq!(msaa.render(&w, 0).unwrap(), plain);
+    }
+}
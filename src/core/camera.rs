@@ -4,17 +4,59 @@
 //! something important is missing, use default).
 use super::{
     antialias::{AAMethod, AntiAliasing},
+    precompute::PrecomputedData,
     world::World,
     Ray,
 };
 use crate::{
     io::error::RenderError,
-    math::{Matrix, Point},
+    math::{sampling::concentric_disk, Axis, Matrix, Point, Tuple},
     visuals::{canvas::Canvas, Color},
 };
 
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use std::sync::{Arc, Mutex};
+use rand::prelude::*;
+use rayon::{
+    iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator},
+    ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder,
+};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// What a [Camera] draws for each pixel. Anything but [RenderMode::Shaded] skips lighting
+/// entirely, which is handy for tracking down misplaced geometry or bad normals.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderMode {
+    /// The fully lit scene (the default).
+    Shaded,
+
+    /// The surface normal at each hit, with `x`, `y`, and `z` from `[-1, 1]` mapped to red,
+    /// green, and blue in `[0, 1]`.
+    Normals,
+
+    /// The distance to each hit in grayscale: white at the camera, fading to black at `far`.
+    Depth { far: f64 },
+}
+
+/// The color of a hit in one of the debug [render modes](RenderMode).
+fn debug_color(mode: RenderMode, comps: &PrecomputedData) -> Color {
+    match mode {
+        RenderMode::Normals => {
+            let n = comps.normalv;
+            Color(
+                (n.x() + 1.0) / 2.0,
+                (n.y() + 1.0) / 2.0,
+                (n.z() + 1.0) / 2.0,
+            )
+        }
+        RenderMode::Depth { far } => {
+            let level = (1.0 - comps.t / far).max(0.0);
+            Color(level, level, level)
+        }
+        RenderMode::Shaded => unreachable!("shaded pixels go through `World::color_at`"),
+    }
+}
 
 /// Cameras are specified with a horizontal size, vertical size, and a field-of-view.
 ///
@@ -27,18 +69,22 @@ use std::sync::{Arc, Mutex};
 /// [Canvas](crate::visuals::canvas::Canvas):
 ///
 /// ```ignore
-/// let canvas = cam.render(&world).unwrap();
+/// let canvas = cam.render_default_depth(&world).unwrap();
 /// ```
 pub struct Camera {
     hsize: usize,
     vsize: usize,
-    #[allow(dead_code)]
     fov: f64,
     transform: Matrix<4>,
     pixel_size: f64,
     half_width: f64,
     half_height: f64,
     aa: AntiAliasing,
+    aperture: f64,
+    focal_distance: f64,
+    roll: f64,
+    mode: RenderMode,
+    threads: Option<Arc<ThreadPool>>,
 }
 
 impl Camera {
@@ -56,18 +102,79 @@ impl Camera {
             half_width,
             half_height,
             aa: AntiAliasing::default(),
+            aperture: 0.0,
+            focal_distance: 1.0,
+            roll: 0.0,
+            mode: RenderMode::Shaded,
+            threads: None,
         }
     }
 
+    /// The width of the rendered image, in pixels.
+    pub fn hsize(&self) -> usize {
+        self.hsize
+    }
+
+    /// The height of the rendered image, in pixels.
+    pub fn vsize(&self) -> usize {
+        self.vsize
+    }
+
+    /// The field of view, in radians.
+    pub fn fov(&self) -> f64 {
+        self.fov
+    }
+
+    /// The camera's transform (not including any [roll](Camera::with_roll)).
+    pub fn transform(&self) -> Matrix<4> {
+        self.transform
+    }
+
+    /// Whether the field of view, transform, lens, and roll are all finite numbers. See
+    /// [WorldBuilder::check_camera](crate::core::world::WorldBuilder::check_camera).
+    pub(crate) fn has_finite_parameters(&self) -> bool {
+        let transform = (0..4).all(|i| self.transform[i].iter().all(|x| x.is_finite()));
+        let lens = [
+            self.fov,
+            self.pixel_size,
+            self.aperture,
+            self.focal_distance,
+            self.roll,
+        ];
+
+        transform && lens.iter().all(|x| x.is_finite())
+    }
+
+    /// The camera's anti-aliasing settings.
+    pub fn antialiasing(&self) -> &AntiAliasing {
+        &self.aa
+    }
+
     /// Creates a ray with origin at the camera and passes through the given pixel coordinates on
     /// the canvas. Returns an `Option<Ray>` since the inverse of the transform matrix may not
-    /// exist.
+    /// exist. With a non-zero aperture, the origin is jittered over the lens and the ray is aimed
+    /// at the pixel's point on the focal plane instead. Renders use [Camera::ray_for_pixel_with]
+    /// so that seeding applies; this is a shorthand for tests.
+    #[cfg(test)]
     pub(crate) fn ray_for_pixel(
         &self,
         px: usize,
         py: usize,
         x_offset: f64,
         y_offset: f64,
+    ) -> Option<Ray> {
+        self.ray_for_pixel_with(px, py, x_offset, y_offset, &mut thread_rng())
+    }
+
+    /// Like [Camera::ray_for_pixel], but picks the point on the lens using `rng`, so that seeded
+    /// renders are reproducible.
+    pub(crate) fn ray_for_pixel_with<R: Rng + ?Sized>(
+        &self,
+        px: usize,
+        py: usize,
+        x_offset: f64,
+        y_offset: f64,
+        rng: &mut R,
     ) -> Option<Ray> {
         let x_offset = (px as f64 + x_offset) * self.pixel_size;
         let y_offset = (py as f64 + y_offset) * self.pixel_size;
@@ -75,61 +182,222 @@ impl Camera {
         let world_x = self.half_width - x_offset;
         let world_y = self.half_height - y_offset;
 
-        if let Some(inv) = self.transform.inverse() {
+        if let Some(inv) = self.view().inverse() {
+            if self.aperture > 0.0 {
+                let (lens_x, lens_y) = self.sample_lens(rng);
+                let focal_pt = inv
+                    * Point(
+                        world_x * self.focal_distance,
+                        world_y * self.focal_distance,
+                        -self.focal_distance,
+                    );
+                let origin = inv * Point(lens_x, lens_y, 0.0);
+                let direction = (focal_pt - origin).normalize();
+
+                return Some(Ray::new(origin, direction).with_spread(self.pixel_size));
+            }
+
             let pixel = inv * Point(world_x, world_y, -1.0);
             let origin = inv * Point(0.0, 0.0, 0.0);
             let direction = (pixel - origin).normalize();
 
-            Some(Ray::new(origin, direction))
+            Some(Ray::new(origin, direction).with_spread(self.pixel_size))
         } else {
             None
         }
     }
 
     /// Uses the camera to render an image of the given world with specified recursion depth (for
-    /// drawing reflections). This method can fail in whichever fashion any other parallelized
-    /// function can. Also because I'm unwrapping a lot.
+    /// drawing reflections).
+    ///
+    /// Problems with the scene don't abort the render: shapes with singular transforms are
+    /// skipped, and a singular camera transform leaves the canvas black. In either case the
+    /// partially rendered canvas is returned inside [RenderError::Incomplete].
     pub fn render(&self, world: &World, depth: usize) -> Result<Canvas, RenderError> {
-        let image = Arc::new(Mutex::new(Canvas::new(
-            self.hsize as u32,
-            self.vsize as u32,
-        )));
-
-        (0..self.vsize)
-            .into_par_iter()
-            .map(|y| {
-                (0..self.hsize)
-                    .into_par_iter()
-                    .map(|x| match self.aa.level {
-                        // No anti-aliasing (default), so we define a ray through the current pixel
-                        // using the default offsets. Uses `World::color_at` to set the color of
-                        // the pixel.
-                        0 => {
-                            if let Some(r) = self.ray_for_pixel(x, y, 0.5, 0.5) {
-                                image.lock().unwrap().write_pixel(
-                                    x as u32,
-                                    y as u32,
-                                    world.color_at(r, depth),
-                                )
-                            }
-                        }
-                        // For any anti-aliasing level > 0, we use the `Camera::color_at` method to
-                        // set the color of the current pixel.
-                        _ => {
-                            let color = self.color_at(x, y, world, depth);
-                            image.lock().unwrap().write_pixel(x as u32, y as u32, color);
-                        }
-                    })
-                    .collect::<Vec<_>>()
+        self.render_with_progress(world, depth, |_, _| {})
+    }
+
+    /// Like [Camera::render], using the world's own [reflection depth](World::reflection_depth).
+    pub fn render_default_depth(&self, world: &World) -> Result<Canvas, RenderError> {
+        self.render(world, world.reflection_depth())
+    }
+
+    /// Like [Camera::render], but calls `progress(completed_rows, total_rows)` each time a row of
+    /// the image finishes, e.g. to drive a progress bar. Rows finish in parallel, so calls can
+    /// arrive slightly out of order, but exactly one of them reports all rows completed.
+    pub fn render_with_progress<F>(
+        &self,
+        world: &World,
+        depth: usize,
+        progress: F,
+    ) -> Result<Canvas, RenderError>
+    where
+        F: Fn(usize, usize) + Sync,
+    {
+        let canvas = self.render_rows(world, depth, (0, 0), (self.hsize, self.vsize), progress);
+
+        self.check_scene(world, canvas)
+    }
+
+    /// Renders only the pixels in the rectangle from `(x0, y0)` (inclusive) to `(x1, y1)`
+    /// (exclusive) onto a canvas of the region's size. Useful for previews, splitting a render
+    /// across machines, or re-rendering part of an image. Returns
+    /// [RenderError::InvalidRegion] if the region is empty or extends outside the image.
+    pub fn render_region(
+        &self,
+        world: &World,
+        depth: usize,
+        (x0, y0): (usize, usize),
+        (x1, y1): (usize, usize),
+    ) -> Result<Canvas, RenderError> {
+        if x0 >= x1 || y0 >= y1 || x1 > self.hsize || y1 > self.vsize {
+            return Err(RenderError::InvalidRegion {
+                from: (x0, y0),
+                to: (x1, y1),
+                size: (self.hsize, self.vsize),
+            });
+        }
+
+        let canvas = self.render_rows(world, depth, (x0, y0), (x1, y1), |_, _| {});
+
+        self.check_scene(world, canvas)
+    }
+
+    /// Renders the rows of the given region in parallel without any shared state, then copies
+    /// them onto a canvas the size of the region.
+    fn render_rows<F>(
+        &self,
+        world: &World,
+        depth: usize,
+        (x0, y0): (usize, usize),
+        (x1, y1): (usize, usize),
+        progress: F,
+    ) -> Canvas
+    where
+        F: Fn(usize, usize) + Sync,
+    {
+        let total = y1 - y0;
+        let completed = AtomicUsize::new(0);
+        let render = || {
+            (y0..y1)
+                .into_par_iter()
+                .map(|y| {
+                    let row = (x0..x1)
+                        .map(|x| self.pixel_color(x, y, world, depth))
+                        .collect::<Vec<_>>();
+
+                    progress(completed.fetch_add(1, Ordering::Relaxed) + 1, total);
+                    row
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let rows = match self.threads {
+            Some(ref pool) => pool.install(render),
+            None => render(),
+        };
+
+        let mut canvas = Canvas::new((x1 - x0) as u32, total as u32);
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, color) in row.into_iter().enumerate() {
+                if let Some(color) = color {
+                    canvas.write_pixel(x as u32, y as u32, color);
+                }
+            }
+        }
+
+        canvas
+    }
+
+    /// Wraps a rendered canvas in [RenderError::Incomplete] if part of the scene couldn't have
+    /// been drawn.
+    fn check_scene(&self, world: &World, canvas: Canvas) -> Result<Canvas, RenderError> {
+        let mut problems = Vec::new();
+
+        if self.view().inverse().is_none() {
+            problems.push("the camera transform is not invertible".to_string());
+        }
+
+        for (i, object) in world.objects.iter().enumerate() {
+            if !object.is_renderable() {
+                problems.push(format!(
+                    "object {} has a singular or non-finite transform",
+                    i
+                ));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(canvas)
+        } else {
+            Err(RenderError::Incomplete {
+                reason: problems.join("; "),
+                canvas,
             })
-            .collect::<Vec<_>>();
+        }
+    }
 
-        let lock = Arc::try_unwrap(image).expect("lock has multiple owners, cannot unwrap");
-        let canv = lock
-            .into_inner()
-            .expect("mutex is poisoned and cannot be locked");
+    /// Renders the whole image on the current thread. Used when parallelizing over frames instead
+    /// of within them.
+    fn render_sequential(&self, world: &World, depth: usize) -> Canvas {
+        let mut canvas = Canvas::new(self.hsize as u32, self.vsize as u32);
 
-        Ok(canv)
+        for y in 0..self.vsize {
+            for x in 0..self.hsize {
+                if let Some(color) = self.pixel_color(x, y, world, depth) {
+                    canvas.write_pixel(x as u32, y as u32, color);
+                }
+            }
+        }
+
+        canvas
+    }
+
+    /// Computes the color of a single pixel. Returns `None` if no ray can be cast through the pixel
+    /// (i.e. the camera transform is singular).
+    fn pixel_color(&self, x: usize, y: usize, world: &World, depth: usize) -> Option<Color> {
+        match (self.mode, self.aa.level) {
+            // No anti-aliasing (default), so we define a ray through the current pixel using the
+            // default offsets. Uses `World::color_at` to set the color of the pixel.
+            (RenderMode::Shaded, 0) => self.aa.with_pixel_rng(y * self.hsize + x, |rng| {
+                self.ray_for_pixel_with(x, y, 0.5, 0.5, rng)
+                    .map(|r| world.primary_color(r, depth, rng))
+            }),
+            // For any anti-aliasing level > 0, we use the `Camera::color_at` method to set the
+            // color of the current pixel.
+            (RenderMode::Shaded, _) => Some(self.color_at(x, y, world, depth)),
+            // Debug modes only look at the nearest hit, so anti-aliasing doesn't apply.
+            (mode, _) => self.center_ray(x, y).map(|r| {
+                world
+                    .primary_hit(r)
+                    .map_or(Color::black(), |c| debug_color(mode, &c))
+            }),
+        }
+    }
+
+    /// Renders a diagnostic image where each pixel's brightness is the number of reflection
+    /// bounces that contributed to it, scaled so that white means all `depth` bounces were used.
+    /// Lots of white suggests reflections are being clipped by `depth`; no white at all suggests
+    /// `depth` could be lowered.
+    pub fn depth_map(&self, world: &World, depth: usize) -> Canvas {
+        let mut canvas = Canvas::new(self.hsize as u32, self.vsize as u32);
+
+        for y in 0..self.vsize {
+            for x in 0..self.hsize {
+                if let Some(r) = self.center_ray(x, y) {
+                    let bounces = world.reflection_bounces(r, depth);
+                    let level = if depth == 0 {
+                        0.0
+                    } else {
+                        bounces as f64 / depth as f64
+                    };
+
+                    canvas.write_pixel(x as u32, y as u32, Color(level, level, level));
+                }
+            }
+        }
+
+        canvas
     }
 
     /// Sets the transformation matrix for the camera.
@@ -138,24 +406,104 @@ impl Camera {
         self
     }
 
+    /// Replaces the camera's transform in place, e.g. to move the camera between the frames of an
+    /// [animation](crate::core::animation).
+    pub fn set_transform(&mut self, m: &Matrix<4>) {
+        self.transform = *m;
+    }
+
     /// Sets the anti-aliasing level. __Note: a large number here slows the renderer down
-    /// considerably.__ Use/adjust it as needed.
+    /// considerably.__ Use/adjust it as needed. Level 0 turns anti-aliasing off, whatever the
+    /// [method](Camera::with_aa_method): one ray is cast through each pixel's center.
     pub fn with_antialiasing(mut self, level: usize) -> Self {
         self.aa.level = level;
         self
     }
 
-    /// Sets the anti-aliasing method. Currently the two available
-    /// [methods](crate::core::antialias::AAMethod) are stochastic and a multisampling-based
-    /// method.
+    /// Seeds the random numbers used for anti-aliasing, depth of field, fuzzy reflections, and
+    /// Russian roulette, so that rendering the same scene again gives exactly the same image. See
+    /// [AntiAliasing::with_seed].
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.aa.seed = Some(seed);
+        self
+    }
+
+    /// Sets the anti-aliasing method. The available [methods](crate::core::antialias::AAMethod)
+    /// are stochastic sampling, multisampling, grid supersampling, and adaptive supersampling.
     pub fn with_aa_method(mut self, method: AAMethod) -> Self {
         self.aa.method = method;
         self
     }
 
+    /// Renders on a dedicated pool of `n` threads instead of rayon's global pool, e.g. to leave
+    /// some cores free on a shared machine or to measure how rendering scales. `0` lets rayon pick
+    /// (one thread per core, unless `RAYON_NUM_THREADS` says otherwise). The pool is started here
+    /// and shared by every render, so this fails if the threads can't be spawned.
+    pub fn with_threads(mut self, n: usize) -> Result<Self, ThreadPoolBuildError> {
+        let pool = ThreadPoolBuilder::new().num_threads(n).build()?;
+        self.threads = Some(Arc::new(pool));
+
+        Ok(self)
+    }
+
+    /// Banks the camera by rotating it `radians` around its viewing direction, applied on top of
+    /// the camera's transform. Saves working out a tilted `up` vector for
+    /// [view_transform](crate::math::Matrix::view_transform).
+    pub fn with_roll(mut self, radians: f64) -> Self {
+        self.roll = radians;
+        self
+    }
+
+    /// Sets what the camera draws; see [RenderMode].
+    pub fn with_render_mode(mut self, mode: RenderMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// The camera's transform with the roll applied. The camera looks down the `-z` axis, so
+    /// rolling is a rotation around `z` after the view transform.
+    fn view(&self) -> Matrix<4> {
+        if self.roll == 0.0 {
+            self.transform
+        } else {
+            Matrix::rotation(Axis::Z, self.roll) * self.transform
+        }
+    }
+
+    /// Sets the radius of the camera's lens. An aperture of 0.0 (the default) is a pinhole camera
+    /// with everything in focus; larger apertures blur objects away from the focal distance.
+    /// Depth of field is noisy, so pair it with anti-aliasing to average several rays per pixel.
+    pub fn with_aperture(mut self, radius: f64) -> Self {
+        self.aperture = radius;
+        self
+    }
+
+    /// Sets the distance from the camera at which objects are in perfect focus. Only matters when
+    /// the aperture is non-zero.
+    pub fn with_focal_distance(mut self, d: f64) -> Self {
+        self.focal_distance = d;
+        self
+    }
+
+    /// Picks a random point on the lens, uniformly distributed over a disk with the aperture's
+    /// radius.
+    fn sample_lens<R: Rng + ?Sized>(&self, rng: &mut R) -> (f64, f64) {
+        let (x, y) = concentric_disk(rng.gen(), rng.gen());
+
+        (x * self.aperture, y * self.aperture)
+    }
+
+    /// The ray through the center of pixel `(x, y)`, with the lens (if any) sampled by the pixel's
+    /// random number generator.
+    fn center_ray(&self, x: usize, y: usize) -> Option<Ray> {
+        self.aa.with_pixel_rng(y * self.hsize + x, |rng| {
+            self.ray_for_pixel_with(x, y, 0.5, 0.5, rng)
+        })
+    }
+
     /// Uses the specified method to perform anti-aliasing.
     fn color_at(&self, x: usize, y: usize, world: &World, world_depth: usize) -> Color {
-        self.aa.anti_alias(x, y, world, world_depth, &self)
+        self.aa.anti_alias(x, y, world, world_depth, self)
     }
 
     /// For initializing private fields.
@@ -177,12 +525,52 @@ impl Camera {
     }
 }
 
+/// Renders one frame of `world` per camera, in parallel across frames rather than within each
+/// frame. For many small frames (e.g. an animation with a moving camera) this keeps every thread
+/// busy with far less coordination than [Camera::render]. Problems which would make
+/// [Camera::render] return an error leave the affected frames partially rendered instead.
+pub fn render_frames_parallel(world: &World, cameras: &[Camera], depth: usize) -> Vec<Canvas> {
+    cameras
+        .par_iter()
+        .map(|cam| cam.render_sequential(world, depth))
+        .collect()
+}
+
+/// A unit sphere lit from the upper left, and a square camera of `size` pixels looking at it from
+/// 5 units away. Shared by the tests of anything that renders, e.g. the anti-aliasing methods.
+#[cfg(test)]
+pub(crate) fn test_world_and_camera(size: usize) -> (World, Camera) {
+    use crate::{core::light::Light, math::Vec3, shape::Sphere};
+
+    let w = World::new(
+        vec![Sphere::default().as_shape()],
+        vec![Light::new_point_light(
+            Point(-10.0, 10.0, -10.0),
+            Color::white(),
+        )],
+    );
+    let c = Camera::new(size, size, std::f64::consts::PI / 4.0).with_transform(
+        &Matrix::view_transform(
+            Point(0.0, 0.0, -5.0),
+            Point(0.0, 0.0, 0.0),
+            Vec3(0.0, 1.0, 0.0),
+        ),
+    );
+
+    (w, c)
+}
+
 #[cfg(test)]
 mod camera_tests {
     use std::f64::consts::{FRAC_1_SQRT_2, PI};
 
     use super::*;
-    use crate::{assert_vpeq, math::Vec3};
+    use crate::{
+        assert_vpeq,
+        core::{antialias::GridSupersampling, light::Light, material::Material},
+        math::{Tuple, Vec3},
+        shape::{Plane, Sphere},
+    };
 
     const EPS: f64 = 1e-4;
 
@@ -241,6 +629,352 @@ mod camera_tests {
         assert_eq!(c.transform, t);
     }
 
+    #[test]
+    fn singular_shape_transform_returns_partial_canvas() {
+        let (mut w, c) = test_world_and_camera(11);
+        w.objects.push(
+            Sphere::default()
+                .with_transform(&Matrix::scaling(1.0, 0.0, 1.0))
+                .as_shape(),
+        );
+
+        match c.render(&w, 0) {
+            Err(RenderError::Incomplete { reason, canvas }) => {
+                assert!(reason.contains("object 1"));
+                // the rest of the scene was still rendered
+                assert_ne!(canvas.read_pixel(5, 5), Color::black());
+            }
+            other => panic!("expected an incomplete render, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn depth_map_reaches_max_depth_in_hall_of_mirrors() {
+        let mirror = Material::default().with_reflective(1.0);
+        let lower = Plane::default()
+            .with_material(&mirror)
+            .with_transform(&Matrix::translation(0.0, -1.0, 0.0))
+            .as_shape();
+        let upper = Plane::default()
+            .with_material(&mirror)
+            .with_transform(&Matrix::translation(0.0, 1.0, 0.0))
+            .as_shape();
+        let light = Light::new_point_light(Point(0.0, 0.0, 0.0), Color::white());
+        let w = World::new(vec![lower, upper], vec![light]);
+
+        // look straight up at the upper mirror, so the ray bounces back and forth forever
+        let c = Camera::new(1, 1, PI / 2.0).with_transform(&Matrix::view_transform(
+            Point(0.0, 0.0, 0.0),
+            Point(0.0, 1.0, 0.0),
+            Vec3(0.0, 0.0, 1.0),
+        ));
+
+        assert_eq!(c.depth_map(&w, 5).read_pixel(0, 0), Color::white());
+    }
+
+    #[test]
+    fn depth_map_is_black_without_reflections() {
+        let (w, c) = test_world_and_camera(1);
+
+        assert_eq!(c.depth_map(&w, 5).read_pixel(0, 0), Color::black());
+    }
+
+    #[test]
+    fn zero_aperture_is_a_pinhole_camera() {
+        let pinhole = Camera::new(201, 101, PI / 2.0);
+        let lens = Camera::new(201, 101, PI / 2.0)
+            .with_aperture(0.0)
+            .with_focal_distance(3.0);
+
+        let r1 = pinhole.ray_for_pixel(20, 30, 0.5, 0.5).unwrap();
+        let r2 = lens.ray_for_pixel(20, 30, 0.5, 0.5).unwrap();
+
+        assert_eq!(r1.origin, r2.origin);
+        assert_eq!(r1.direction, r2.direction);
+    }
+
+    #[test]
+    fn rays_through_a_lens_meet_at_the_focal_plane() {
+        let c = Camera::new(201, 101, PI / 2.0)
+            .with_aperture(0.5)
+            .with_focal_distance(3.0);
+
+        let r1 = c.ray_for_pixel(100, 50, 0.5, 0.5).unwrap();
+        let r2 = c.ray_for_pixel(100, 50, 0.5, 0.5).unwrap();
+        assert_ne!(r1.origin, r2.origin);
+
+        // the center pixel is in focus at (0, 0, -3)
+        for r in [r1, r2] {
+            let t = (-3.0 - r.origin.z()) / r.direction.z();
+            assert!((r.position(t) - Point(0.0, 0.0, -3.0)).magnitude() < EPS);
+        }
+    }
+
+    #[test]
+    fn parallel_render_matches_pixel_by_pixel_render() {
+        let light = Light::new_point_light(Point(-10.0, 10.0, -10.0), Color::white());
+        let sphere = Sphere::default()
+            .with_material(&Material::default().with_color(&Color(0.8, 1.0, 0.6)))
+            .as_shape();
+        let floor = Plane::default()
+            .with_material(&Material::default().with_reflective(0.5))
+            .with_transform(&Matrix::translation(0.0, -1.0, 0.0))
+            .as_shape();
+        let w = World::new(vec![sphere, floor], vec![light]);
+        let c = Camera::new(16, 9, PI / 2.0).with_transform(&Matrix::view_transform(
+            Point(0.0, 1.0, -5.0),
+            Point(0.0, 0.0, 0.0),
+            Vec3(0.0, 1.0, 0.0),
+        ));
+
+        let canvas = c.render(&w, 5).unwrap();
+
+        let mut expected = Canvas::new(16, 9);
+        for y in 0..9 {
+            for x in 0..16 {
+                let r = c.ray_for_pixel(x, y, 0.5, 0.5).unwrap();
+                expected.write_pixel(x as u32, y as u32, w.color_at(r, 5, &mut thread_rng()));
+            }
+        }
+
+        for y in 0..9 {
+            for x in 0..16 {
+                assert_eq!(canvas.read_pixel(x, y), expected.read_pixel(x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn render_reports_progress_for_every_row() {
+        let w = World::new(
+            vec![Sphere::default().as_shape()],
+            vec![Light::new_point_light(
+                Point(-10.0, 10.0, -10.0),
+                Color::white(),
+            )],
+        );
+        let c = Camera::new(8, 6, PI / 2.0);
+        let reports = std::sync::Mutex::new(Vec::new());
+
+        c.render_with_progress(&w, 0, |done, total| {
+            reports.lock().unwrap().push((done, total))
+        })
+        .unwrap();
+
+        let mut reports = reports.into_inner().unwrap();
+        reports.sort_unstable();
+
+        assert_eq!(reports, (1..=6).map(|done| (done, 6)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn default_depth_renders_use_the_worlds_reflection_depth() {
+        let mirror = Plane::default()
+            .with_material(&Material::default().with_reflective(1.0))
+            .as_shape();
+        let w = World::new(
+            vec![Sphere::default().as_shape(), mirror],
+            vec![Light::new_point_light(
+                Point(-10.0, 10.0, -10.0),
+                Color::white(),
+            )],
+        )
+        .with_reflection_depth(0);
+        let c = Camera::new(8, 6, PI / 2.0).with_transform(&Matrix::view_transform(
+            Point(0.0, 1.5, -5.0),
+            Point(0.0, 0.0, 0.0),
+            Vec3(0.0, 1.0, 0.0),
+        ));
+
+        let default = c.render_default_depth(&w).unwrap();
+        let flat = c.render(&w, 0).unwrap();
+        let deep = c.render(&w, 5).unwrap();
+
+        let pixels = |canvas: &Canvas| {
+            (0..6)
+                .flat_map(|y| (0..8).map(move |x| (x, y)))
+                .map(|(x, y)| canvas.read_pixel(x, y))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(pixels(&default), pixels(&flat));
+        assert_ne!(pixels(&default), pixels(&deep));
+    }
+
+    #[test]
+    fn thread_count_does_not_change_the_image() {
+        let w = World::new(
+            vec![Sphere::default().as_shape(), Plane::default().as_shape()],
+            vec![Light::new_point_light(
+                Point(-10.0, 10.0, -10.0),
+                Color::white(),
+            )],
+        );
+        let c = |threads| {
+            Camera::new(16, 12, PI / 2.0)
+                .with_transform(&Matrix::view_transform(
+                    Point(0.0, 1.5, -5.0),
+                    Point(0.0, 0.0, 0.0),
+                    Vec3(0.0, 1.0, 0.0),
+                ))
+                .with_threads(threads)
+                .unwrap()
+        };
+        let rows = AtomicUsize::new(0);
+
+        let single = c(1)
+            .render_with_progress(&w, 0, |_, _| {
+                rows.fetch_add(1, Ordering::Relaxed);
+            })
+            .unwrap();
+
+        assert_eq!(rows.into_inner(), 12);
+        assert_eq!(c(4).render(&w, 0).unwrap(), single);
+        assert_eq!(c(0).render(&w, 0).unwrap(), single);
+    }
+
+    #[test]
+    fn stats_count_a_primary_ray_per_sample() {
+        // the camera sits inside the sphere, so every primary ray hits it
+        let w = World::new(
+            vec![Sphere::default().as_shape(), Plane::default().as_shape()],
+            vec![Light::new_point_light(Point(0.0, 0.5, 0.0), Color::white())],
+        )
+        .with_stats();
+        let c = Camera::new(8, 6, PI / 2.0);
+
+        c.render(&w, 0).unwrap();
+        let stats = w.stats().unwrap();
+        assert_eq!(stats.primary_rays, 8 * 6);
+        assert_eq!(stats.shadow_rays, 8 * 6);
+        assert_eq!(stats.reflection_rays, 0);
+        assert!(stats.intersection_tests >= 2 * 8 * 6);
+
+        w.reset_stats();
+        c.with_antialiasing(2)
+            .with_aa_method(AAMethod::Grid(GridSupersampling::new(2)))
+            .render(&w, 0)
+            .unwrap();
+        assert_eq!(w.stats().unwrap().primary_rays, 8 * 6 * 2 * 2);
+
+        assert_eq!(World::default().stats(), None);
+    }
+
+    #[test]
+    fn parallel_frames_match_sequential_renders() {
+        let w = World::new(
+            vec![
+                Sphere::default().as_shape(),
+                Plane::default()
+                    .with_transform(&Matrix::translation(0.0, -1.0, 0.0))
+                    .as_shape(),
+            ],
+            vec![Light::new_point_light(
+                Point(-10.0, 10.0, -10.0),
+                Color::white(),
+            )],
+        );
+        let cameras = (0..4)
+            .map(|i| {
+                let from = Point(i as f64 - 2.0, 1.5, -5.0);
+                Camera::new(12, 8, PI / 3.0).with_transform(&Matrix::view_transform(
+                    from,
+                    Point(0.0, 0.0, 0.0),
+                    Vec3(0.0, 1.0, 0.0),
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        let frames = render_frames_parallel(&w, &cameras, 3);
+
+        assert_eq!(frames.len(), cameras.len());
+        for (frame, cam) in frames.iter().zip(cameras.iter()) {
+            assert_eq!(*frame, cam.render(&w, 3).unwrap());
+        }
+    }
+
+    #[test]
+    fn quadrant_regions_stitch_into_full_render() {
+        let w = World::new(
+            vec![
+                Sphere::default().as_shape(),
+                Plane::default()
+                    .with_transform(&Matrix::translation(0.0, -1.0, 0.0))
+                    .as_shape(),
+            ],
+            vec![Light::new_point_light(
+                Point(-10.0, 10.0, -10.0),
+                Color::white(),
+            )],
+        );
+        let c = Camera::new(10, 7, PI / 3.0).with_transform(&Matrix::view_transform(
+            Point(0.0, 1.5, -5.0),
+            Point(0.0, 0.0, 0.0),
+            Vec3(0.0, 1.0, 0.0),
+        ));
+        let full = c.render(&w, 3).unwrap();
+
+        let mut stitched = Canvas::new(10, 7);
+        for (from, to) in [
+            ((0, 0), (5, 3)),
+            ((5, 0), (10, 3)),
+            ((0, 3), (5, 7)),
+            ((5, 3), (10, 7)),
+        ] {
+            let region = c.render_region(&w, 3, from, to).unwrap();
+
+            for y in 0..region.height {
+                for x in 0..region.width {
+                    stitched.write_pixel(
+                        x + from.0 as u32,
+                        y + from.1 as u32,
+                        region.read_pixel(x, y),
+                    );
+                }
+            }
+        }
+
+        assert_eq!(stitched, full);
+    }
+
+    #[test]
+    fn regions_outside_the_image_are_rejected() {
+        let w = World::default();
+        let c = Camera::new(10, 7, PI / 3.0);
+
+        assert!(matches!(
+            c.render_region(&w, 0, (5, 5), (11, 7)),
+            Err(RenderError::InvalidRegion { .. })
+        ));
+        assert!(matches!(
+            c.render_region(&w, 0, (5, 5), (5, 7)),
+            Err(RenderError::InvalidRegion { .. })
+        ));
+    }
+
+    #[test]
+    fn rolling_90_degrees_rotates_the_image() {
+        let t = Matrix::view_transform(
+            Point(1.0, 2.0, -5.0),
+            Point(0.0, 0.0, 0.0),
+            Vec3(0.0, 1.0, 0.0),
+        );
+        let level = Camera::new(9, 9, PI / 2.0).with_transform(&t);
+        let rolled = Camera::new(9, 9, PI / 2.0)
+            .with_transform(&t)
+            .with_roll(PI / 2.0);
+
+        // each pixel of the rolled image sees what the level camera sees a quarter turn away
+        for y in 0..9 {
+            for x in 0..9 {
+                let r1 = rolled.ray_for_pixel(x, y, 0.5, 0.5).unwrap();
+                let r2 = level.ray_for_pixel(y, 8 - x, 0.5, 0.5).unwrap();
+
+                assert_vpeq!(r1.origin - r2.origin, Vec3(0.0, 0.0, 0.0), EPS);
+                assert_vpeq!(r1.direction, r2.direction, EPS);
+            }
+        }
+    }
+
     #[test]
     fn constructing_a_camera() {
         let hsize = 160;
@@ -253,4 +987,58 @@ mod camera_tests {
         assert_eq!(c.fov, PI / 2.0);
         assert_eq!(c.transform, Matrix::identity());
     }
+
+    #[test]
+    fn normals_mode_colors_pixels_by_the_hit_normal() {
+        let (w, c) = test_world_and_camera(11);
+        let c = c.with_render_mode(RenderMode::Normals);
+        let image = c.render(&w, 5).unwrap();
+
+        // the sphere faces the camera head on, so the normal is (0, 0, -1)
+        assert!(image.read_pixel(5, 5).approx_eq(&Color(0.5, 0.5, 0.0), EPS));
+        // the normal tilts up towards the top of the sphere
+        assert!(image.read_pixel(5, 4).g() > 0.5);
+        // and the corners miss the sphere entirely
+        assert_eq!(image.read_pixel(0, 0), Color::black());
+    }
+
+    #[test]
+    fn depth_mode_fades_with_distance() {
+        let (w, c) = test_world_and_camera(11);
+        let c = c.with_render_mode(RenderMode::Depth { far: 8.0 });
+        let image = c.render(&w, 5).unwrap();
+
+        // the nearest point on the sphere is 4 units away
+        assert!(image.read_pixel(5, 5).approx_eq(&Color(0.5, 0.5, 0.5), EPS));
+        assert_eq!(image.read_pixel(0, 0), Color::black());
+    }
+
+    #[test]
+    fn debug_modes_count_a_primary_ray_per_pixel() {
+        let w = test_world_and_camera(11).0.with_stats();
+
+        for mode in [RenderMode::Normals, RenderMode::Depth { far: 8.0 }] {
+            w.reset_stats();
+            test_world_and_camera(11)
+                .1
+                .with_antialiasing(2)
+                .with_render_mode(mode)
+                .render(&w, 5)
+                .unwrap();
+
+            // misses count too, and anti-aliasing doesn't apply
+            assert_eq!(w.stats().unwrap().primary_rays, 11 * 11);
+        }
+    }
+
+    #[test]
+    fn camera_accessors() {
+        let t = Matrix::translation(0.0, -2.0, 5.0);
+        let c = Camera::new(160, 120, PI / 3.0).with_transform(&t);
+
+        assert_eq!(c.hsize(), 160);
+        assert_eq!(c.vsize(), 120);
+        assert_eq!(c.fov(), PI / 3.0);
+        assert_eq!(c.transform(), t);
+    }
 }
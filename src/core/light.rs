@@ -1,8 +1,27 @@
-use crate::{math::Point, visuals::Color};
+use crate::{
+    math::{
+        sampling::{orthonormal_basis, GOLDEN_ANGLE},
+        Point, Vec3,
+    },
+    visuals::Color,
+};
 
 #[derive(Debug, PartialEq)]
 pub enum LightType {
     PointLight(PointLight),
+    DiskLight(DiskLight),
+    SphereLight(SphereLight),
+}
+
+/// A single point on a light's surface along with the share of the light's intensity it emits.
+/// Shading treats every sample as a point light, so lights with many samples cast soft shadows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightSample {
+    pub position: Point,
+    pub intensity: Color,
+    /// Whether the sample dims with the square of the distance, like an
+    /// [attenuated](Light::new_point_light_attenuated) point light.
+    pub attenuated: bool,
 }
 
 #[derive(Debug, PartialEq)]
@@ -17,15 +36,92 @@ impl Light {
         }
     }
 
+    /// Creates a point light whose intensity falls off with the square of the distance from it,
+    /// like a real light. `intensity` is the light's brightness one unit away, so it typically
+    /// needs to be much brighter than an unattenuated light to light a scene the same way.
+    pub fn new_point_light_attenuated(position: Point, intensity: Color) -> Self {
+        Light {
+            light_type: LightType::PointLight(PointLight::new(position, intensity).attenuated()),
+        }
+    }
+
+    /// Creates a flat, round light facing along `normal`. `samples` points spread evenly over the
+    /// disk are used when shading; more samples give smoother (but slower) soft shadows.
+    pub fn new_disk_light(
+        center: Point,
+        normal: Vec3,
+        radius: f64,
+        intensity: Color,
+        samples: usize,
+    ) -> Self {
+        Light {
+            light_type: LightType::DiskLight(DiskLight::new(
+                center, normal, radius, intensity, samples,
+            )),
+        }
+    }
+
+    /// Creates a spherical light. `samples` points spread evenly over the sphere's surface are
+    /// used when shading.
+    pub fn new_sphere_light(center: Point, radius: f64, intensity: Color, samples: usize) -> Self {
+        Light {
+            light_type: LightType::SphereLight(SphereLight::new(
+                center, radius, intensity, samples,
+            )),
+        }
+    }
+
+    /// The position of a point light, or the center of an area light.
     pub fn position(&self) -> Point {
         match &self.light_type {
             LightType::PointLight(pl) => pl.position,
+            LightType::DiskLight(dl) => dl.center,
+            LightType::SphereLight(sl) => sl.center,
         }
     }
 
+    /// The total intensity emitted by the light.
     pub fn intensity(&self) -> Color {
         match &self.light_type {
             LightType::PointLight(pl) => pl.intensity,
+            LightType::DiskLight(dl) => dl.intensity,
+            LightType::SphereLight(sl) => sl.intensity,
+        }
+    }
+
+    /// How much of the light's intensity reaches `point`: `1 / distance²` for attenuated point
+    /// lights, and all of it otherwise.
+    pub fn falloff_at(&self, point: &Point) -> f64 {
+        match &self.light_type {
+            LightType::PointLight(pl) if pl.attenuated => {
+                1.0 / (pl.position - point).magnitude().powi(2)
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Splits the light into point samples whose intensities sum to the light's intensity. Point
+    /// lights are a single sample.
+    pub fn samples(&self) -> Vec<LightSample> {
+        match &self.light_type {
+            LightType::PointLight(pl) => vec![LightSample {
+                position: pl.position,
+                intensity: pl.intensity,
+                attenuated: pl.attenuated,
+            }],
+            LightType::DiskLight(dl) => dl.samples(),
+            LightType::SphereLight(sl) => sl.samples(),
+        }
+    }
+}
+
+/// Shades each sample as a point light of its own.
+impl From<LightSample> for Light {
+    fn from(s: LightSample) -> Self {
+        if s.attenuated {
+            Light::new_point_light_attenuated(s.position, s.intensity)
+        } else {
+            Light::new_point_light(s.position, s.intensity)
         }
     }
 }
@@ -34,6 +130,7 @@ impl Light {
 pub struct PointLight {
     position: Point,
     intensity: Color,
+    attenuated: bool,
 }
 
 impl PointLight {
@@ -42,8 +139,103 @@ impl PointLight {
         Self {
             position,
             intensity,
+            attenuated: false,
+        }
+    }
+
+    /// Makes the light fall off with the square of the distance from it.
+    pub fn attenuated(mut self) -> Self {
+        self.attenuated = true;
+        self
+    }
+
+    /// Whether the light falls off with distance. See [PointLight::attenuated].
+    pub fn is_attenuated(&self) -> bool {
+        self.attenuated
+    }
+}
+
+/// A round, flat light.
+#[derive(Debug, PartialEq)]
+pub struct DiskLight {
+    center: Point,
+    normal: Vec3,
+    radius: f64,
+    intensity: Color,
+    samples: usize,
+}
+
+impl DiskLight {
+    /// Creates a new DiskLight. `normal` doesn't need to be normalized.
+    pub fn new(center: Point, normal: Vec3, radius: f64, intensity: Color, samples: usize) -> Self {
+        Self {
+            center,
+            normal: normal.normalize(),
+            radius,
+            intensity,
+            samples: samples.max(1),
         }
     }
+
+    /// Spreads the samples uniformly over the disk using a sunflower (Vogel) spiral.
+    fn samples(&self) -> Vec<LightSample> {
+        let (u, v) = orthonormal_basis(&self.normal);
+        let n = self.samples as f64;
+
+        (0..self.samples)
+            .map(|i| {
+                let r = self.radius * ((i as f64 + 0.5) / n).sqrt();
+                let theta = i as f64 * GOLDEN_ANGLE;
+
+                LightSample {
+                    position: self.center + u * (r * theta.cos()) + v * (r * theta.sin()),
+                    intensity: self.intensity * (1.0 / n),
+                    attenuated: false,
+                }
+            })
+            .collect()
+    }
+}
+
+/// A spherical light.
+#[derive(Debug, PartialEq)]
+pub struct SphereLight {
+    center: Point,
+    radius: f64,
+    intensity: Color,
+    samples: usize,
+}
+
+impl SphereLight {
+    /// Creates a new SphereLight.
+    pub fn new(center: Point, radius: f64, intensity: Color, samples: usize) -> Self {
+        Self {
+            center,
+            radius,
+            intensity,
+            samples: samples.max(1),
+        }
+    }
+
+    /// Spreads the samples uniformly over the sphere's surface using a Fibonacci lattice.
+    fn samples(&self) -> Vec<LightSample> {
+        let n = self.samples as f64;
+
+        (0..self.samples)
+            .map(|i| {
+                let y = 1.0 - 2.0 * (i as f64 + 0.5) / n;
+                let ring = (1.0 - y * y).sqrt();
+                let theta = i as f64 * GOLDEN_ANGLE;
+                let dir = Vec3(ring * theta.cos(), y, ring * theta.sin());
+
+                LightSample {
+                    position: self.center + dir * self.radius,
+                    intensity: self.intensity * (1.0 / n),
+                    attenuated: false,
+                }
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -60,4 +252,47 @@ mod light_tests {
         assert_eq!(light.position, pos);
         assert_eq!(light.intensity, intensity);
     }
+
+    #[test]
+    fn point_light_is_a_single_sample() {
+        let light = Light::new_point_light(Point(1.0, 2.0, 3.0), Color::white());
+
+        assert_eq!(
+            light.samples(),
+            vec![LightSample {
+                position: Point(1.0, 2.0, 3.0),
+                intensity: Color::white(),
+                attenuated: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn disk_light_samples_lie_within_its_radius() {
+        let center = Point(0.0, 10.0, 0.0);
+        let normal = Vec3(0.0, -1.0, 0.0);
+        let light = Light::new_disk_light(center, normal, 2.0, Color::white(), 32);
+        let samples = light.samples();
+
+        assert_eq!(samples.len(), 32);
+        for s in samples.iter() {
+            let offset = s.position - center;
+
+            assert!(offset.magnitude() <= 2.0 + 1e-9);
+            assert!(offset.dot(&normal).abs() < 1e-9);
+        }
+
+        let total: Color = samples.iter().map(|s| s.intensity).sum();
+        assert!((total.0 - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sphere_light_samples_lie_on_its_surface() {
+        let center = Point(1.0, 2.0, 3.0);
+        let light = Light::new_sphere_light(center, 0.5, Color::white(), 16);
+
+        for s in light.samples() {
+            assert!(((s.position - center).magnitude() - 0.5).abs() < 1e-9);
+        }
+    }
 }
@@ -1,29 +1,61 @@
 use crate::{
-    math::{Point, Vec3},
+    math::{
+        sampling::{orthonormal_basis, GOLDEN_ANGLE},
+        Point, Vec3,
+    },
     shape::Shape,
     visuals::Color,
 };
 
-use super::{light::Light, pattern::Pattern};
+use super::{light::Light, pattern::Pattern, precompute::PrecomputedData};
+
+/// How the specular highlight of a [Material] is computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpecularModel {
+    /// Compares the eye vector to the light reflected about the normal.
+    #[default]
+    Phong,
+
+    /// Compares the normal to the vector halfway between the light and the eye. Cheaper than
+    /// [Phong](SpecularModel::Phong), and highlights stay round at grazing angles. Highlights are
+    /// larger for the same shininess, roughly matching Phong at four times the shininess.
+    BlinnPhong,
+}
 
 /// Phong materials. Each attribute should be nonnegative. For `ambient`, `diffuse`, and
 /// `specular`, values between 0.0 and 1.0 are typical. For `shininess`, a value of 10.0 is
 /// considered very large and 200.0 very small (there is no hard upper-bound).
 #[derive(Debug, PartialEq, Clone)]
 pub struct Material {
-    pub(crate) color: Color,
-    pub(crate) pattern: Option<Pattern>,
+    pub(crate) pattern: Pattern,
     pub(crate) ambient: f64,
     pub(crate) diffuse: f64,
     pub(crate) specular: f64,
     pub(crate) shininess: f64,
     pub(crate) reflective: f64,
+    pub(crate) reflection_fuzz: f64,
+    pub(crate) emission: Color,
     pub(crate) transparency: f64,
     pub(crate) refractive_index: f64,
+    pub(crate) casts_shadow: bool,
+    pub(crate) roughness: f64,
+    pub(crate) specular_model: SpecularModel,
+    pub(crate) pattern_samples: usize,
+}
+
+/// The separate terms of the Phong lighting computed by [Material::lighting_components]. Their
+/// sum is what [Material::lighting] returns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightingComponents {
+    pub ambient: Color,
+    pub diffuse: Color,
+    pub specular: Color,
+    /// The material's [emission](Material::with_emission), which doesn't depend on the light.
+    pub emission: Color,
 }
 
 impl Material {
-    /// Computes the lighting associated with the material.
+    /// Computes the lighting associated with the material, including its emission.
     pub fn lighting(
         &self,
         object: &Shape,
@@ -33,13 +65,70 @@ impl Material {
         normalv: &Vec3,
         in_shadow: bool,
     ) -> Color {
-        let mut color = self.color;
-        if let Some(pat) = self.pattern.clone() {
-            color = pat.color_at_object(object, point).unwrap();
-        }
+        let components = self.lighting_components(object, light, point, eyev, normalv, in_shadow);
+
+        components.ambient + components.diffuse + components.specular + components.emission
+    }
+
+    /// Like [Material::lighting], but keeps the ambient, diffuse, and specular terms apart, e.g.
+    /// to render or inspect them individually when tuning a material.
+    pub fn lighting_components(
+        &self,
+        object: &Shape,
+        light: &Light,
+        point: &Point,
+        eyev: &Vec3,
+        normalv: &Vec3,
+        in_shadow: bool,
+    ) -> LightingComponents {
+        let color = self.pattern.color_at_object(object, point).unwrap();
+
+        self.components_for(color, light, point, eyev, normalv, in_shadow)
+    }
+
+    /// Like [Material::lighting], but for a hit found by a [World](crate::core::World), whose
+    /// footprint is known so that the pattern can be averaged over it (see
+    /// [Material::with_pattern_samples]). Sums the lighting from every `(light, in_shadow)` pair,
+    /// adding the emission only once however many lights there are.
+    pub(crate) fn lighting_at<I>(&self, comps: &PrecomputedData, lights: I) -> Color
+    where
+        I: IntoIterator<Item = (Light, bool)>,
+    {
+        let color = self.surface_color(comps);
+        let lit: Color = lights
+            .into_iter()
+            .map(|(light, in_shadow)| {
+                let components = self.components_for(
+                    color,
+                    &light,
+                    &comps.over_point,
+                    &comps.eyev,
+                    &comps.normalv,
+                    in_shadow,
+                );
+
+                components.ambient + components.diffuse + components.specular
+            })
+            .sum();
+
+        lit + self.emission
+    }
+
+    /// The lighting terms for a surface whose (pattern) color at `point` is `color`.
+    fn components_for(
+        &self,
+        color: Color,
+        light: &Light,
+        point: &Point,
+        eyev: &Vec3,
+        normalv: &Vec3,
+        in_shadow: bool,
+    ) -> LightingComponents {
+        // the light's color/intensity, dimmed by distance for attenuated lights
+        let intensity = light.intensity() * light.falloff_at(point);
 
         // combines surface color with the light's color/intensity
-        let effective_color = color * light.intensity();
+        let effective_color = color * intensity;
 
         // direction to light source
         let lightv = (light.position() - point).normalize();
@@ -54,45 +143,70 @@ impl Material {
         // If we are in a shadowed region, specular and diffuse are ignored and only ambient
         // contributes to the color.
         if in_shadow {
-            return ambient;
+            return LightingComponents {
+                ambient,
+                diffuse: Color::black(),
+                specular: Color::black(),
+                emission: self.emission,
+            };
         }
 
         // compute the specular and diffuse contributions
         let (specular, diffuse) = if light_dot_normal < 0.0 {
             (Color::black(), Color::black())
         } else {
-            let reflectv = -lightv.reflect(normalv);
-
-            // (cosine of the) angle between the reflection vector and the eye vector
-            // reflect_dot_eye <= 0.0 means the light reflects away from the eye
-            let reflect_dot_eye = reflectv.dot(eyev);
-            if reflect_dot_eye <= 0.0 {
-                (
-                    Color::black(),
-                    effective_color * self.diffuse * light_dot_normal,
-                )
+            let diffuse = effective_color
+                * self.diffuse
+                * light_dot_normal
+                * self.oren_nayar(&lightv, eyev, normalv);
+            // (cosine of the) angle between the reflection vector and the eye vector, or for
+            // Blinn-Phong, between the normal and the vector halfway between light and eye.
+            // highlight_cos <= 0.0 means the light reflects away from the eye
+            let highlight_cos = match self.specular_model {
+                SpecularModel::Phong => (-lightv.reflect(normalv)).dot(eyev),
+                SpecularModel::BlinnPhong => normalv.dot(&(lightv + *eyev).normalize()),
+            };
+            if highlight_cos <= 0.0 {
+                (Color::black(), diffuse)
             } else {
-                let factor = reflect_dot_eye.powi(self.shininess as i32); // specular contribution component
-                (
-                    light.intensity() * self.specular * factor,
-                    effective_color * self.diffuse * light_dot_normal,
-                )
+                // fractional shininess is allowed; negative values are treated as 0
+                let factor = highlight_cos.powf(self.shininess.max(0.0)); // specular contribution component
+                (intensity * self.specular * factor, diffuse)
             }
         };
 
-        ambient + diffuse + specular
+        LightingComponents {
+            ambient,
+            diffuse,
+            specular,
+            emission: self.emission,
+        }
     }
 
+    /// Colors the surface with `pattern`, replacing any color set before. The surface has a
+    /// single pattern, so whichever of `with_pattern` and [Material::with_color] is called last
+    /// wins.
     pub fn with_pattern(mut self, pattern: &Pattern) -> Self {
-        self.pattern = Some((*pattern).clone());
+        self.pattern = (*pattern).clone();
         self
     }
 
+    /// Colors the whole surface with `color`, replacing any pattern. Shorthand for a
+    /// [Solid](Pattern::Solid) pattern.
     pub fn with_color(mut self, color: &Color) -> Self {
-        self.color = *color;
+        self.pattern = Pattern::Solid(*color);
         self
     }
 
+    /// The color of the surface if it's a single [solid](Pattern::Solid) color (white by
+    /// default), or `None` if a pattern is set.
+    pub fn color(&self) -> Option<Color> {
+        match self.pattern {
+            Pattern::Solid(c) => Some(c),
+            _ => None,
+        }
+    }
+
     pub fn with_ambient(mut self, ambient: f64) -> Self {
         self.ambient = ambient;
         self
@@ -118,6 +232,108 @@ impl Material {
         self
     }
 
+    /// Blurs reflections by scattering reflected rays within a cone of `fuzz` radians around the
+    /// mirror direction, giving a glossy look. A fuzz of 0.0 (the default) keeps reflections sharp.
+    pub fn with_reflection_fuzz(mut self, fuzz: f64) -> Self {
+        self.reflection_fuzz = fuzz;
+        self
+    }
+
+    /// Makes the material glow with the given color, regardless of lights and shadows. Useful for
+    /// visible light sources and sky spheres. The default emission is black, i.e. none.
+    pub fn with_emission(mut self, emission: &Color) -> Self {
+        self.emission = *emission;
+        self
+    }
+
+    /// Sets whether objects with this material block light from reaching other surfaces. Turning
+    /// it off is handy for glass and decorative objects. Shadows are cast by default.
+    pub fn with_casts_shadow(mut self, casts_shadow: bool) -> Self {
+        self.casts_shadow = casts_shadow;
+        self
+    }
+
+    /// Sets how rough the surface is for the Oren-Nayar diffuse model, as the standard deviation
+    /// (in radians) of the angles of its microfacets. Rough surfaces like plaster or the moon
+    /// scatter more light back toward the light source, so they look flatter than smooth ones. A
+    /// roughness of 0.0, the default, is a perfectly Lambertian surface.
+    pub fn with_roughness(mut self, roughness: f64) -> Self {
+        self.roughness = roughness;
+        self
+    }
+
+    /// Sets how the specular highlight is computed. See [SpecularModel].
+    pub fn with_specular_model(mut self, model: SpecularModel) -> Self {
+        self.specular_model = model;
+        self
+    }
+
+    /// Scales the Lambertian diffuse term according to the Oren-Nayar model, given the unit
+    /// vectors toward the light and the eye. Always 1.0 for perfectly smooth surfaces.
+    fn oren_nayar(&self, lightv: &Vec3, eyev: &Vec3, normalv: &Vec3) -> f64 {
+        if self.roughness <= 0.0 {
+            return 1.0;
+        }
+
+        let sigma2 = self.roughness * self.roughness;
+        let a = 1.0 - 0.5 * sigma2 / (sigma2 + 0.33);
+        let b = 0.45 * sigma2 / (sigma2 + 0.09);
+
+        // angles of incidence and reflection, measured from the normal
+        let theta_i = lightv.dot(normalv).clamp(-1.0, 1.0).acos();
+        let theta_r = eyev.dot(normalv).clamp(-1.0, 1.0).acos();
+        let (alpha, beta) = (theta_i.max(theta_r), theta_i.min(theta_r));
+
+        // cosine of the azimuthal angle between the light and eye, projected onto the surface
+        let light_proj = *lightv - *normalv * lightv.dot(normalv);
+        let eye_proj = *eyev - *normalv * eyev.dot(normalv);
+        let cos_phi = if light_proj.magnitude() < 1e-9 || eye_proj.magnitude() < 1e-9 {
+            0.0
+        } else {
+            light_proj.normalize().dot(&eye_proj.normalize())
+        };
+
+        a + b * cos_phi.max(0.0) * alpha.sin() * beta.tan()
+    }
+
+    /// Averages the pattern over `n` points spread around each hit instead of reading it at the
+    /// hit alone, which blurs away the shimmer of fine patterns seen from far off or at a grazing
+    /// angle (e.g. a checkered floor running to the horizon). The points cover the patch of
+    /// surface seen through the pixel, which grows with distance from the camera. Values below 1
+    /// are treated as 1.
+    pub fn with_pattern_samples(mut self, n: usize) -> Self {
+        self.pattern_samples = n.max(1);
+        self
+    }
+
+    /// The pattern's color at the hit, averaged over the hit's footprint if `pattern_samples` is
+    /// more than 1. The samples are spread over a disk in the surface's tangent plane (stretched
+    /// at grazing angles) along a golden-angle spiral, so the result is the same on every call.
+    fn surface_color(&self, comps: &PrecomputedData) -> Color {
+        let (object, point) = (&comps.object, &comps.over_point);
+        if self.pattern_samples <= 1 || comps.footprint <= 0.0 {
+            return self.pattern.color_at_object(object, point).unwrap();
+        }
+
+        let radius = comps.footprint / 2.0 / comps.eyev.dot(&comps.normalv).abs().max(0.1);
+        let (u, v) = orthonormal_basis(&comps.normalv);
+        let n = self.pattern_samples;
+
+        let total: Color = (0..n)
+            .map(|i| {
+                let r = radius * ((i as f64 + 0.5) / n as f64).sqrt();
+                let theta = i as f64 * GOLDEN_ANGLE;
+                let offset = u * (r * theta.cos()) + v * (r * theta.sin());
+
+                self.pattern
+                    .color_at_object(object, &(*point + offset))
+                    .unwrap()
+            })
+            .sum();
+
+        total / n as f64
+    }
+
     pub fn with_transparency(mut self, transparency: f64) -> Self {
         self.transparency = transparency;
         self
@@ -127,20 +343,47 @@ impl Material {
         self.refractive_index = refractive_index;
         self
     }
+
+    /// The `(reflective, transparency)` weights for shading a surface that both reflects and
+    /// refracts. A surface can't send on more light than reaches it, so if the two add up to more
+    /// than 1 they're scaled down in proportion.
+    ///
+    /// Refracted rays aren't traced yet, so shading only uses [Material::reflectivity] for now:
+    /// scaling reflections down to make room for transparency that isn't drawn would just lose
+    /// light.
+    pub fn energy_weights(&self) -> (f64, f64) {
+        let total = self.reflective + self.transparency;
+        if total <= 1.0 {
+            (self.reflective, self.transparency)
+        } else {
+            (self.reflective / total, self.transparency / total)
+        }
+    }
+
+    /// The share of light reflected when shading: `reflective`, capped at 1. Until refraction is
+    /// traced, transparency doesn't take any away (see [Material::energy_weights]).
+    pub(crate) fn reflectivity(&self) -> f64 {
+        self.reflective.min(1.0)
+    }
 }
 
 impl Default for Material {
     fn default() -> Self {
         Self {
-            color: Color::white(),
-            pattern: None,
+            pattern: Pattern::Solid(Color::white()),
             ambient: 0.1,
             diffuse: 0.9,
             specular: 0.9,
             shininess: 200.0,
             reflective: 0.0,
+            reflection_fuzz: 0.0,
+            emission: Color::black(),
             transparency: 0.0,
             refractive_index: 1.0,
+            casts_shadow: true,
+            roughness: 0.0,
+            specular_model: SpecularModel::Phong,
+            pattern_samples: 1,
         }
     }
 }
@@ -150,7 +393,7 @@ mod material_tests {
     use crate::{
         core::{precompute::PrecomputedData, Intersection, IntersectionList, Ray},
         math::Matrix,
-        shape::Sphere,
+        shape::{Plane, Sphere},
     };
 
     use super::*;
@@ -191,8 +434,8 @@ mod material_tests {
 
         let xs = IntersectionList::new(ix);
 
-        let expected_n1 = vec![1.0, 1.5, 2.0, 2.5, 2.5, 1.5];
-        let expected_n2 = vec![1.5, 2.0, 2.5, 2.5, 1.5, 1.0];
+        let expected_n1 = [1.0, 1.5, 2.0, 2.5, 2.5, 1.5];
+        let expected_n2 = [1.5, 2.0, 2.5, 2.5, 1.5, 1.0];
 
         for idx in 0..6 {
             let comps = PrecomputedData::new(&xs[idx], &r, &xs);
@@ -235,6 +478,38 @@ mod material_tests {
         assert_eq!(res, Color(exact, exact, exact));
     }
 
+    #[test]
+    fn pattern_samples_average_over_the_footprint_of_distant_hits() {
+        let floor = Plane::default().as_shape();
+        let m = Material::default()
+            .with_pattern(&Pattern::new_checkerboard(Color::white(), Color::black()))
+            .with_ambient(1.0)
+            .with_diffuse(0.0)
+            .with_specular(0.0);
+        let light = || Light::new_point_light(Point(0.0, 10.0, 0.0), Color::white());
+        // looks straight down at the floor from `height`, 0.1 away from a checker boundary, with
+        // a ray standing for a pixel 0.01 wide per unit of distance
+        let shade = |m: &Material, height: f64| {
+            let r = Ray::new(Point(0.1, height, 0.5), Vec3(0.0, -1.0, 0.0)).with_spread(0.01);
+            let xs = IntersectionList::new(vec![Intersection::new(height, floor.clone())]);
+            let comps = PrecomputedData::new(&xs[0], &r, &xs);
+
+            m.lighting_at(&comps, [(light(), false)])
+        };
+
+        // a single sample is read at the hit, as before
+        assert_eq!(shade(&m, 100.0), Color::white());
+
+        // up close the footprint stays within one cell
+        let filtered = m.with_pattern_samples(16);
+        assert_eq!(shade(&filtered, 1.0), Color::white());
+
+        // from far away it's a whole unit across, and straddles the boundary
+        let far = shade(&filtered, 100.0);
+        assert!(far.0 > 0.0 && far.0 < 1.0);
+        assert_eq!((far.0, far.0), (far.1, far.2));
+    }
+
     #[test]
     fn lighting_eye_in_path_of_reflection_vec() {
         let object = Sphere::default().as_shape();
@@ -249,6 +524,28 @@ mod material_tests {
         assert_eq!(res, Color(exact, exact, exact));
     }
 
+    #[test]
+    fn fractional_shininess_is_not_truncated() {
+        let object = Sphere::default().as_shape();
+        let pos = Point(0.0, 0.0, 0.0);
+        let eyev = Vec3(0.0, 0.0, -1.0);
+        let normalv = Vec3(0.0, 0.0, -1.0);
+        let light = Light::new_point_light(Point(0.0, 10.0, -10.0), Color::white());
+        let specular = |shininess| {
+            Material::default()
+                .with_shininess(shininess)
+                .lighting_components(&object, &light, &pos, &eyev, &normalv, false)
+                .specular
+                .0
+        };
+
+        // the reflected light is 45° from the eye
+        assert!((specular(0.0) - 0.9).abs() < 1e-10);
+        assert!((specular(0.5) - 0.9 * ROOT2.sqrt()).abs() < 1e-10);
+        assert!((specular(1.0) - 0.9 * ROOT2).abs() < 1e-10);
+        assert_eq!(specular(-3.0), specular(0.0));
+    }
+
     #[test]
     fn lighting_eye_opposite_surface_light_offset_45() {
         let object = Sphere::default().as_shape();
@@ -266,6 +563,199 @@ mod material_tests {
         assert!((res.2 - want.2).abs() < 1e-4);
     }
 
+    #[test]
+    fn attenuated_lights_fall_off_with_the_square_of_the_distance() {
+        let object = Sphere::default().as_shape();
+        let m = Material::default();
+        let eyev = Vec3(0.0, 0.0, -1.0);
+        let normalv = Vec3(0.0, 0.0, -1.0);
+        let light =
+            Light::new_point_light_attenuated(Point(0.0, 0.0, -10.0), Color::white() * 100.0);
+
+        let near = m.lighting(
+            &object,
+            &light,
+            &Point(0.0, 0.0, -5.0),
+            &eyev,
+            &normalv,
+            false,
+        );
+        let far = m.lighting(
+            &object,
+            &light,
+            &Point(0.0, 0.0, 0.0),
+            &eyev,
+            &normalv,
+            false,
+        );
+
+        // the far point is twice as far from the light, so it gets a quarter of the light
+        assert!((near.0 - 1.9 * 100.0 / 25.0).abs() < 1e-9);
+        assert!((far.0 - near.0 / 4.0).abs() < 1e-9);
+
+        // without attenuation, the distance doesn't matter
+        let constant = Light::new_point_light(Point(0.0, 0.0, -10.0), Color::white());
+        assert_eq!(
+            m.lighting(
+                &object,
+                &constant,
+                &Point(0.0, 0.0, -5.0),
+                &eyev,
+                &normalv,
+                false
+            ),
+            m.lighting(
+                &object,
+                &constant,
+                &Point(0.0, 0.0, 0.0),
+                &eyev,
+                &normalv,
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn lighting_components_sum_to_the_lighting() {
+        let object = Sphere::default().as_shape();
+        let m = Material::default();
+        let pos = Point(0.0, 0.0, 0.0);
+        let eyev = Vec3(0.0, -ROOT2, -ROOT2);
+        let normalv = Vec3(0.0, 0.0, -1.0);
+        let light = Light::new_point_light(Point(0.0, 10.0, -10.0), Color::white());
+
+        let c = m.lighting_components(&object, &light, &pos, &eyev, &normalv, false);
+        let lit = m.lighting(&object, &light, &pos, &eyev, &normalv, false);
+
+        assert_eq!(c.ambient, Color(0.1, 0.1, 0.1));
+        assert!((c.diffuse.0 - 0.9 * ROOT2).abs() < 1e-9);
+        assert!(c.specular.0 > 0.8);
+        assert_eq!(c.ambient + c.diffuse + c.specular + c.emission, lit);
+
+        // in shadow, only the ambient term is left
+        let shadowed = m.lighting_components(&object, &light, &pos, &eyev, &normalv, true);
+        assert_eq!(shadowed.diffuse + shadowed.specular, Color::black());
+    }
+
+    #[test]
+    fn lighting_includes_the_emission() {
+        let object = Sphere::default().as_shape();
+        let m = Material::default()
+            .with_ambient(0.0)
+            .with_diffuse(0.0)
+            .with_specular(0.0)
+            .with_emission(&Color(0.5, 0.5, 0.5));
+        let pos = Point(0.0, 0.0, 0.0);
+        let eyev = Vec3(0.0, 0.0, -1.0);
+        let normalv = Vec3(0.0, 0.0, -1.0);
+        let light = Light::new_point_light(Point(0.0, 0.0, -10.0), Color::white());
+
+        for in_shadow in [false, true] {
+            let res = m.lighting(&object, &light, &pos, &eyev, &normalv, in_shadow);
+            assert_eq!(res, Color(0.5, 0.5, 0.5));
+        }
+    }
+
+    #[test]
+    fn zero_roughness_is_lambertian() {
+        let object = Sphere::default().as_shape();
+        let pos = Point(0.0, 0.0, 0.0);
+        let eyev = Vec3(0.0, -ROOT2, -ROOT2);
+        let normalv = Vec3(0.0, 0.0, -1.0);
+        let light = Light::new_point_light(Point(0.0, 10.0, -10.0), Color::white());
+
+        let smooth = Material::default()
+            .with_roughness(0.0)
+            .lighting_components(&object, &light, &pos, &eyev, &normalv, false);
+        let lambertian = 0.9 * ROOT2;
+        assert!((smooth.diffuse.0 - lambertian).abs() < 1e-12);
+        assert_eq!(
+            smooth,
+            Material::default().lighting_components(&object, &light, &pos, &eyev, &normalv, false)
+        );
+    }
+
+    #[test]
+    fn roughness_flattens_the_diffuse_falloff() {
+        let object = Sphere::default().as_shape();
+        let pos = Point(0.0, 0.0, 0.0);
+        let normalv = Vec3(0.0, 0.0, -1.0);
+        let eyev = Vec3(0.0, 3.0_f64.sqrt() / 2.0, -0.5);
+        let head_on = Light::new_point_light(Point(0.0, 0.0, -10.0), Color::white());
+        let grazing = Light::new_point_light(Point(0.0, 10.0, -1.0), Color::white());
+
+        // how much dimmer a surface is when lit at a grazing angle rather than head on
+        let falloff = |m: Material| {
+            let diffuse = |l: &Light| {
+                m.lighting_components(&object, l, &pos, &eyev, &normalv, false)
+                    .diffuse
+            };
+
+            diffuse(&grazing).0 / diffuse(&head_on).0
+        };
+        let lambertian = falloff(Material::default());
+        let rough = falloff(Material::default().with_roughness(1.0));
+
+        assert!((lambertian - 1.0 / 101.0_f64.sqrt()).abs() < 1e-9);
+        assert!(rough > lambertian);
+    }
+
+    #[test]
+    fn blinn_phong_highlights() {
+        let object = Sphere::default().as_shape();
+        let pos = Point(0.0, 0.0, 0.0);
+        let normalv = Vec3(0.0, 0.0, -1.0);
+        let blinn = Material::default().with_specular_model(SpecularModel::BlinnPhong);
+
+        // with the light and eye mirrored about the normal, both models see the full highlight
+        let eyev = Vec3(0.0, -ROOT2, -ROOT2);
+        let light = Light::new_point_light(Point(0.0, 10.0, -10.0), Color::white());
+        let c = blinn.lighting_components(&object, &light, &pos, &eyev, &normalv, false);
+        assert!((c.specular.0 - 0.9).abs() < 1e-9);
+
+        // looking straight down the normal, the half vector is 22.5 degrees off the normal while
+        // the reflection is 45 degrees off the eye, so Blinn-Phong's highlight is much brighter
+        let eyev = Vec3(0.0, 0.0, -1.0);
+        let blinn = blinn.with_shininess(8.0);
+        let phong = Material::default().with_shininess(8.0);
+        let b = blinn.lighting_components(&object, &light, &pos, &eyev, &normalv, false);
+        let p = phong.lighting_components(&object, &light, &pos, &eyev, &normalv, false);
+
+        let half_angle = std::f64::consts::PI / 8.0;
+        assert!((b.specular.0 - 0.9 * half_angle.cos().powi(8)).abs() < 1e-9);
+        assert!((p.specular.0 - 0.9 * ROOT2.powi(8)).abs() < 1e-9);
+        assert_eq!(b.diffuse, p.diffuse);
+    }
+
+    #[test]
+    fn plain_colors_are_solid_patterns() {
+        let object = Sphere::default().as_shape();
+        let pos = Point(0.0, 0.0, 0.0);
+        let eyev = Vec3(0.0, 0.0, -1.0);
+        let normalv = Vec3(0.0, 0.0, -1.0);
+        let light = Light::new_point_light(Point(0.0, 0.0, -10.0), Color::white());
+
+        let colored = Material::default().with_color(&Color(1.0, 0.5, 0.0));
+        let patterned = Material::default().with_pattern(&Pattern::solid(Color(1.0, 0.5, 0.0)));
+        assert_eq!(colored.pattern, patterned.pattern);
+
+        // ambient + diffuse + specular, with only the first two tinted by the color
+        let lit = colored.lighting(&object, &light, &pos, &eyev, &normalv, false);
+        assert_eq!(
+            lit,
+            Color(1.0, 0.5, 0.0) * (0.1 + 0.9) + Color(0.9, 0.9, 0.9)
+        );
+
+        // a solid color doesn't need the object's transform, even if it's singular
+        let flat = Sphere::default()
+            .with_transform(&Matrix::scaling(1.0, 0.0, 1.0))
+            .as_shape();
+        assert_eq!(
+            colored.lighting(&flat, &light, &pos, &eyev, &normalv, false),
+            lit
+        );
+    }
+
     #[test]
     fn lighting_eye_between_light_and_surface_offset_45() {
         let object = Sphere::default().as_shape();
@@ -296,7 +786,7 @@ mod material_tests {
     fn materials_have_a_default() {
         let m = Material::default();
 
-        assert_eq!(m.color, Color::white());
+        assert_eq!(m.color(), Some(Color::white()));
         assert_eq!(m.ambient, 0.1);
         assert_eq!(m.diffuse, 0.9);
         assert_eq!(m.specular, 0.9);
@@ -0,0 +1,159 @@
+//! A library of named material presets with reasonable Phong/reflective/refractive values.
+//!
+//! Each preset is a regular [Material], so it can be tweaked further with the usual builder
+//! methods:
+//!
+//! ```
+//! # use rtc::{core::materials, visuals::Color};
+//! let shiny_red = materials::plastic(Color::red()).with_reflective(0.2);
+//! ```
+use crate::visuals::Color;
+
+use super::Material;
+
+/// Clear glass: almost fully transparent with a refractive index of 1.5.
+pub fn glass() -> Material {
+    Material::default()
+        .with_color(&Color::black())
+        .with_ambient(0.0)
+        .with_diffuse(0.1)
+        .with_specular(1.0)
+        .with_shininess(300.0)
+        .with_reflective(0.9)
+        .with_transparency(0.9)
+        .with_refractive_index(1.5)
+}
+
+/// A perfect mirror which takes all of its color from reflections.
+pub fn mirror() -> Material {
+    Material::default()
+        .with_color(&Color::black())
+        .with_ambient(0.0)
+        .with_diffuse(0.0)
+        .with_specular(1.0)
+        .with_shininess(300.0)
+        .with_reflective(1.0)
+}
+
+/// A flat, non-reflective surface without specular highlights.
+pub fn matte(color: Color) -> Material {
+    Material::default()
+        .with_color(&color)
+        .with_ambient(0.1)
+        .with_diffuse(0.9)
+        .with_specular(0.0)
+        .with_shininess(10.0)
+}
+
+/// A smooth surface with a tight highlight and a hint of reflection.
+pub fn plastic(color: Color) -> Material {
+    Material::default()
+        .with_color(&color)
+        .with_ambient(0.1)
+        .with_diffuse(0.7)
+        .with_specular(0.5)
+        .with_shininess(150.0)
+        .with_reflective(0.05)
+}
+
+/// A dark, strongly reflective surface with a broad highlight.
+pub fn metal(color: Color) -> Material {
+    Material::default()
+        .with_color(&color)
+        .with_ambient(0.1)
+        .with_diffuse(0.3)
+        .with_specular(0.9)
+        .with_shininess(50.0)
+        .with_reflective(0.6)
+}
+
+/// Slightly blue, transparent water with a refractive index of 1.333.
+pub fn water() -> Material {
+    Material::default()
+        .with_color(&Color(0.0, 0.05, 0.1))
+        .with_ambient(0.0)
+        .with_diffuse(0.1)
+        .with_specular(1.0)
+        .with_shininess(300.0)
+        .with_reflective(0.8)
+        .with_transparency(0.9)
+        .with_refractive_index(1.333)
+}
+
+/// Looks up a preset by name. `color` is used by the presets which take one (`matte`, `plastic`,
+/// and `metal`) and ignored otherwise. Returns `None` if the name isn't recognized.
+pub fn by_name(name: &str, color: Color) -> Option<Material> {
+    match name {
+        "glass" => Some(glass()),
+        "mirror" => Some(mirror()),
+        "matte" => Some(matte(color)),
+        "plastic" => Some(plastic(color)),
+        "metal" => Some(metal(color)),
+        "water" => Some(water()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod materials_tests {
+    use super::*;
+
+    #[test]
+    fn glass_is_transparent_and_refractive() {
+        let m = glass();
+
+        assert_eq!(m.transparency, 0.9);
+        assert_eq!(m.refractive_index, 1.5);
+    }
+
+    #[test]
+    fn mirror_is_fully_reflective() {
+        let m = mirror();
+
+        assert_eq!(m.reflective, 1.0);
+        assert_eq!(m.diffuse, 0.0);
+        assert_eq!(m.color(), Some(Color::black()));
+    }
+
+    #[test]
+    fn matte_has_no_specular_highlight() {
+        let m = matte(Color::red());
+
+        assert_eq!(m.color(), Some(Color::red()));
+        assert_eq!(m.specular, 0.0);
+        assert_eq!(m.reflective, 0.0);
+    }
+
+    #[test]
+    fn plastic_has_tight_highlight() {
+        let m = plastic(Color::green());
+
+        assert_eq!(m.color(), Some(Color::green()));
+        assert_eq!(m.specular, 0.5);
+        assert_eq!(m.shininess, 150.0);
+    }
+
+    #[test]
+    fn metal_is_reflective() {
+        let m = metal(Color::blue());
+
+        assert_eq!(m.color(), Some(Color::blue()));
+        assert_eq!(m.reflective, 0.6);
+        assert_eq!(m.specular, 0.9);
+    }
+
+    #[test]
+    fn water_has_refractive_index_of_water() {
+        let m = water();
+
+        assert_eq!(m.transparency, 0.9);
+        assert_eq!(m.refractive_index, 1.333);
+    }
+
+    #[test]
+    fn presets_can_be_looked_up_by_name() {
+        assert_eq!(by_name("glass", Color::white()), Some(glass()));
+        assert_eq!(by_name("metal", Color::red()), Some(metal(Color::red())));
+        assert_eq!(by_name("velvet", Color::white()), None);
+    }
+}
@@ -1,4 +1,6 @@
 //! Patterns can be applied to shapes and respect applied transformations.
+use image::RgbImage;
+
 use crate::{
     math::{Matrix, Point},
     shape::Shape,
@@ -8,11 +10,23 @@ use crate::{
 pub mod blended;
 pub mod checkers;
 pub mod gradient;
+pub mod image_texture;
+pub mod nested_checkers;
+pub mod perturbed;
 pub mod rings;
 pub mod stripes;
+pub mod texture_map;
 
 pub use self::{
-    blended::Blended, checkers::Checkers, gradient::Gradient, rings::Rings, stripes::StripePattern,
+    blended::Blended,
+    checkers::Checkers,
+    gradient::Gradient,
+    image_texture::{ImageTexture, Sampling},
+    nested_checkers::NestedCheckers,
+    perturbed::Perturbed,
+    rings::Rings,
+    stripes::StripePattern,
+    texture_map::{TextureMap, UvMapping, UvPattern},
 };
 
 /// An enumeration of different patterns.
@@ -33,6 +47,11 @@ pub use self::{
 /// ```
 #[derive(Debug, Clone, PartialEq)]
 pub enum Pattern {
+    /// The same color everywhere. This is what a plain [Material::with_color] uses.
+    ///
+    /// [Material::with_color]: crate::core::Material::with_color
+    Solid(Color),
+
     /// A pattern consisting of alternating stripes
     Stripes(StripePattern),
 
@@ -47,17 +66,47 @@ pub enum Pattern {
 
     /// A pattern obtained by blending two other patterns together
     Blended(Blended),
+
+    /// A checker pattern alternating between two other patterns
+    NestedCheckers(NestedCheckers),
+
+    /// A 2D pattern wrapped onto a shape via a UV mapping
+    TextureMap(TextureMap),
+
+    /// An image wrapped onto a shape via a UV mapping
+    ImageTexture(ImageTexture),
+
+    /// Another pattern whose lookup points are jittered by noise
+    Perturbed(Perturbed),
 }
 
 impl Pattern {
+    /// A pattern which is `color` everywhere.
+    pub fn solid(color: Color) -> Self {
+        Self::Solid(color)
+    }
+
     /// Stores any number of colors for an alternating stripe pattern.
     pub fn new_stripes(colors: Vec<Color>) -> Self {
-        Self::Stripes(StripePattern::new(colors))
+        Self::Stripes(StripePattern::new(colors, false))
+    }
+
+    /// Like [Pattern::new_stripes], but each stripe blends linearly into the next color, which
+    /// avoids the hard (and easily aliased) edges between stripes.
+    pub fn new_smooth_stripes(colors: Vec<Color>) -> Self {
+        Self::Stripes(StripePattern::new(colors, true))
     }
 
     /// Stores two colors to linearly interpolate between when computing the color at a point.
+    /// The gradient repeats every unit along the x-axis.
     pub fn new_gradient(color1: Color, color2: Color) -> Self {
-        Self::Gradient(Gradient::new(color1, color2))
+        Self::Gradient(Gradient::new(color1, color2, false))
+    }
+
+    /// Like [Pattern::new_gradient], but every other unit runs from `color2` back to `color1` so
+    /// that the repeats don't have a visible seam.
+    pub fn new_ping_pong_gradient(color1: Color, color2: Color) -> Self {
+        Self::Gradient(Gradient::new(color1, color2, true))
     }
 
     /// Stores any number of colors to construct a concentric ring pattern.
@@ -65,9 +114,15 @@ impl Pattern {
         Self::Rings(Rings::new(colors))
     }
 
-    /// TODO: docs
-    pub fn new_checkers(color1: Color, color2: Color) -> Self {
-        Self::Checkers(Checkers::new(color1, color2))
+    /// Stores any number of colors for a 3D checker pattern, cycling through them from one cell to
+    /// the next.
+    pub fn new_checkers(colors: Vec<Color>) -> Self {
+        Self::Checkers(Checkers::new(colors))
+    }
+
+    /// A classic two-color checkerboard. Shorthand for [Pattern::new_checkers] with two colors.
+    pub fn new_checkerboard(color1: Color, color2: Color) -> Self {
+        Self::new_checkers(vec![color1, color2])
     }
 
     /// Create a new pattern which blends the supplied patterns, taking the average color at each
@@ -76,60 +131,282 @@ impl Pattern {
         Self::Blended(Blended::new(pattern1, pattern2))
     }
 
+    /// Create a new checker pattern whose cells alternate between the supplied patterns, e.g.
+    /// stripes on one set of cells and a gradient on the other. Each inner pattern keeps its own
+    /// transform.
+    pub fn new_checkers_nested(pattern1: Self, pattern2: Self) -> Self {
+        Self::NestedCheckers(NestedCheckers::new(pattern1, pattern2))
+    }
+
+    /// Wraps `pattern`, jittering each lookup point by Perlin noise. `scale` controls how far
+    /// points are displaced; a scale of 0.0 reproduces the inner pattern.
+    pub fn new_perturbed(pattern: Self, scale: f64) -> Self {
+        Self::Perturbed(Perturbed::new(pattern, scale))
+    }
+
+    /// Creates a pattern which maps points to `(u, v)` coordinates using `mapping`, then looks up
+    /// the color in the given [UvPattern].
+    pub fn new_texture_map(uv_pattern: UvPattern, mapping: UvMapping) -> Self {
+        Self::TextureMap(TextureMap::new(uv_pattern, mapping))
+    }
+
+    /// Creates a pattern which wraps the given image onto a shape using `mapping`.
+    pub fn new_image_texture(image: RgbImage, mapping: UvMapping) -> Self {
+        Self::ImageTexture(ImageTexture::new(image, mapping))
+    }
+
+    /// Loads an image from `path` (in any format supported by the [image] crate) and wraps it onto
+    /// a shape using `mapping`.
+    pub fn load_image_texture<P>(path: P, mapping: UvMapping) -> image::ImageResult<Self>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let image = image::open(&path)?.to_rgb8();
+        let mut texture = ImageTexture::new(image, mapping);
+        texture.path = Some(path.as_ref().to_path_buf());
+
+        Ok(Self::ImageTexture(texture))
+    }
+
     /// Given a `Point`, returns the color of the pattern at that point.
-    pub(crate) fn color_at(&self, pt: &Point) -> Color {
+    pub fn color_at(&self, pt: &Point) -> Color {
         match self {
+            Self::Solid(color) => *color,
             Self::Stripes(stripe_pattern) => stripe_pattern.color_at(pt),
             Self::Gradient(gradient_pattern) => gradient_pattern.color_at(pt),
             Self::Rings(ring_pattern) => ring_pattern.color_at(pt),
             Self::Checkers(checker_pattern) => checker_pattern.color_at(pt),
             Self::Blended(blended_pattern) => blended_pattern.color_at(pt),
+            Self::NestedCheckers(nested_checkers) => nested_checkers.color_at(pt),
+            Self::TextureMap(texture_map) => texture_map.color_at(pt),
+            Self::ImageTexture(image_texture) => image_texture.color_at(pt),
+            Self::Perturbed(perturbed_pattern) => perturbed_pattern.color_at(pt),
         }
     }
 
     /// Given a `Shape`, returns the color of the object at the specified world-space point by
     /// converting to pattern-space coordinates. Returns `None` if either the object or the pattern
     /// inverse transformation matrices don't exist.
-    pub(crate) fn color_at_object(&self, object: &Shape, world_pt: &Point) -> Option<Color> {
+    pub fn color_at_object(&self, object: &Shape, world_pt: &Point) -> Option<Color> {
+        // the point doesn't matter, so there's no need to invert any transforms
+        if let Self::Solid(color) = self {
+            return Some(*color);
+        }
+
         let object_pt = object.transform().inverse()? * *world_pt;
         let pattern_pt = self.transform().inverse()? * object_pt;
 
         Some(self.color_at(&pattern_pt))
     }
 
-    /// Sets the transformation matrix for the pattern.
+    /// Sets the transformation matrix for the pattern. [Solid](Pattern::Solid) patterns look the
+    /// same however they're transformed, so they're returned unchanged.
     pub fn with_transform(mut self, m: &Matrix<4>) -> Self {
         match self {
+            Self::Solid(_) => {}
             Self::Stripes(ref mut sp) => sp.transform = *m,
             Self::Gradient(ref mut gp) => gp.transform = *m,
             Self::Rings(ref mut rp) => rp.transform = *m,
             Self::Checkers(ref mut cp) => cp.transform = *m,
             Self::Blended(ref mut bp) => bp.transform = *m,
+            Self::NestedCheckers(ref mut nc) => nc.transform = *m,
+            Self::TextureMap(ref mut tm) => tm.transform = *m,
+            Self::ImageTexture(ref mut it) => it.transform = *m,
+            Self::Perturbed(ref mut pp) => pp.transform = *m,
+        }
+
+        self
+    }
+
+    /// Sets how image textures sample their image. Patterns other than
+    /// [ImageTexture](Pattern::ImageTexture) don't sample anything and are returned unchanged.
+    pub fn with_sampling(mut self, sampling: Sampling) -> Self {
+        if let Self::ImageTexture(ref mut it) = self {
+            it.sampling = sampling;
         }
 
         self
     }
 
-    fn transform(&self) -> Matrix<4> {
+    pub(crate) fn transform(&self) -> Matrix<4> {
         match self {
+            Self::Solid(_) => Matrix::identity(),
             Self::Stripes(sp) => sp.transform,
             Self::Gradient(gp) => gp.transform,
             Self::Rings(rp) => rp.transform,
             Self::Checkers(cp) => cp.transform,
             Self::Blended(bp) => bp.transform,
+            Self::NestedCheckers(nc) => nc.transform,
+            Self::TextureMap(tm) => tm.transform,
+            Self::ImageTexture(it) => it.transform,
+            Self::Perturbed(pp) => pp.transform,
         }
     }
 }
 
 #[cfg(test)]
 mod pattern_tests {
+    use std::f64::consts::FRAC_1_SQRT_2;
+
     use crate::{math::Matrix, shape::Sphere};
 
-    use super::*;
+    use super::{
+        texture_map::{cylindrical_map, planar_map, spherical_map},
+        *,
+    };
+
+    const EPS: f64 = 1e-4;
+
+    #[test]
+    fn perturbing_changes_some_colors() {
+        let inner = Pattern::new_stripes(vec![Color::white(), Color::black()]);
+        let pat = Pattern::new_perturbed(inner.clone(), 0.5);
+
+        let changed = (0..100)
+            .map(|i| Point(i as f64 * 0.173, 0.31, i as f64 * 0.07))
+            .filter(|pt| pat.color_at(pt) != inner.color_at(pt))
+            .count();
+
+        assert!(changed > 0);
+    }
+
+    #[test]
+    fn zero_scale_perturbation_matches_inner_pattern() {
+        let inner = Pattern::new_gradient(Color::white(), Color::black());
+        let pat = Pattern::new_perturbed(inner.clone(), 0.0);
+
+        for i in 0..100 {
+            let pt = Point(i as f64 * 0.173, 0.31, i as f64 * 0.07);
+            assert_eq!(pat.color_at(&pt), inner.color_at(&pt));
+        }
+    }
+
+    #[test]
+    fn image_textures_read_corner_pixels() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/samples/textures/2x2.png");
+        let pat = Pattern::load_image_texture(path, UvMapping::Planar).unwrap();
+
+        let Pattern::ImageTexture(tex) = pat else {
+            panic!("expected an image texture");
+        };
+
+        // v is flipped, so v = 0.0 is the bottom row of the image
+        assert_eq!(tex.uv_color_at(0.0, 1.0), Color::red());
+        assert_eq!(tex.uv_color_at(1.0, 1.0), Color::green());
+        assert_eq!(tex.uv_color_at(0.0, 0.0), Color::blue());
+        assert_eq!(tex.uv_color_at(1.0, 0.0), Color::white());
+    }
+
+    #[test]
+    fn empty_image_textures_are_black() {
+        for sampling in [Sampling::Nearest, Sampling::Bilinear] {
+            let Pattern::ImageTexture(tex) =
+                Pattern::new_image_texture(RgbImage::new(0, 0), UvMapping::Planar)
+                    .with_sampling(sampling)
+            else {
+                panic!("expected an image texture");
+            };
+
+            assert_eq!(tex.uv_color_at(0.5, 0.5), Color::black());
+        }
+    }
+
+    #[test]
+    fn texture_map_with_spherical_map() {
+        let checkers = UvPattern::new_checkers(16.0, 8.0, Color::black(), Color::white());
+        let pat = Pattern::new_texture_map(checkers, UvMapping::Spherical);
+        let expected = [
+            (Point(0.4315, 0.4670, 0.7719), Color::white()),
+            (Point(-0.9654, 0.2552, -0.0534), Color::black()),
+            (Point(0.1039, 0.7090, 0.6975), Color::white()),
+            (Point(-0.4986, -0.7856, -0.3663), Color::black()),
+            (Point(-0.0317, -0.9395, 0.3411), Color::black()),
+            (Point(0.4809, -0.7721, 0.4154), Color::black()),
+            (Point(0.0285, -0.9612, -0.2745), Color::black()),
+            (Point(-0.5734, -0.2162, -0.7903), Color::white()),
+            (Point(0.7688, -0.1470, 0.6223), Color::black()),
+            (Point(-0.7652, 0.2175, 0.6060), Color::black()),
+        ];
+
+        for (pt, color) in expected {
+            assert_eq!(pat.color_at(&pt), color);
+        }
+    }
+
+    #[test]
+    fn cylindrical_mapping_on_3d_point() {
+        let expected = [
+            (Point(0.0, 0.0, -1.0), (0.0, 0.0)),
+            (Point(0.0, 0.5, -1.0), (0.0, 0.5)),
+            (Point(0.0, 1.0, -1.0), (0.0, 0.0)),
+            (Point(FRAC_1_SQRT_2, 0.5, -FRAC_1_SQRT_2), (0.125, 0.5)),
+            (Point(1.0, 0.5, 0.0), (0.25, 0.5)),
+            (Point(FRAC_1_SQRT_2, 0.5, FRAC_1_SQRT_2), (0.375, 0.5)),
+            (Point(0.0, -0.25, 1.0), (0.5, 0.75)),
+            (Point(-FRAC_1_SQRT_2, 0.5, FRAC_1_SQRT_2), (0.625, 0.5)),
+            (Point(-1.0, 1.25, 0.0), (0.75, 0.25)),
+            (Point(-FRAC_1_SQRT_2, 0.5, -FRAC_1_SQRT_2), (0.875, 0.5)),
+        ];
+
+        for (pt, (u, v)) in expected {
+            let (mu, mv) = cylindrical_map(&pt);
+            assert!((mu - u).abs() < EPS);
+            assert!((mv - v).abs() < EPS);
+        }
+    }
+
+    #[test]
+    fn planar_mapping_on_3d_point() {
+        let expected = [
+            (Point(0.25, 0.0, 0.5), (0.25, 0.5)),
+            (Point(0.25, 0.0, -0.25), (0.25, 0.75)),
+            (Point(0.25, 0.5, -0.25), (0.25, 0.75)),
+            (Point(1.25, 0.0, 0.5), (0.25, 0.5)),
+            (Point(0.25, 0.0, -1.75), (0.25, 0.25)),
+            (Point(1.0, 0.0, -1.0), (0.0, 0.0)),
+            (Point(0.0, 0.0, 0.0), (0.0, 0.0)),
+        ];
+
+        for (pt, (u, v)) in expected {
+            let (mu, mv) = planar_map(&pt);
+            assert!((mu - u).abs() < EPS);
+            assert!((mv - v).abs() < EPS);
+        }
+    }
+
+    #[test]
+    fn spherical_mapping_on_3d_point() {
+        let expected = [
+            (Point(0.0, 0.0, -1.0), (0.0, 0.5)),
+            (Point(1.0, 0.0, 0.0), (0.25, 0.5)),
+            (Point(0.0, 0.0, 1.0), (0.5, 0.5)),
+            (Point(-1.0, 0.0, 0.0), (0.75, 0.5)),
+            (Point(0.0, 1.0, 0.0), (0.5, 1.0)),
+            (Point(0.0, -1.0, 0.0), (0.5, 0.0)),
+            (Point(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0), (0.25, 0.75)),
+        ];
+
+        for (pt, (u, v)) in expected {
+            let (mu, mv) = spherical_map(&pt);
+            assert!((mu - u).abs() < EPS);
+            assert!((mv - v).abs() < EPS);
+        }
+    }
+
+    #[test]
+    fn checker_pattern_in_2d() {
+        let checkers = UvPattern::new_checkers(2.0, 2.0, Color::black(), Color::white());
+
+        assert_eq!(checkers.uv_color_at(0.0, 0.0), Color::black());
+        assert_eq!(checkers.uv_color_at(0.5, 0.0), Color::white());
+        assert_eq!(checkers.uv_color_at(0.0, 0.5), Color::white());
+        assert_eq!(checkers.uv_color_at(0.5, 0.5), Color::black());
+        assert_eq!(checkers.uv_color_at(1.0, 1.0), Color::black());
+    }
 
     #[test]
     fn checkers_alternate_in_x() {
-        let pat = Pattern::new_checkers(Color::white(), Color::black());
+        let pat = Pattern::new_checkerboard(Color::white(), Color::black());
 
         assert_eq!(pat.color_at(&Point(0.0, 0.0, 0.0)), Color::white());
         assert_eq!(pat.color_at(&Point(0.99, 0.0, 0.0)), Color::white());
@@ -138,7 +415,7 @@ mod pattern_tests {
 
     #[test]
     fn checkers_alternate_in_y() {
-        let pat = Pattern::new_checkers(Color::white(), Color::black());
+        let pat = Pattern::new_checkerboard(Color::white(), Color::black());
 
         assert_eq!(pat.color_at(&Point(0.0, 0.0, 0.0)), Color::white());
         assert_eq!(pat.color_at(&Point(0.0, 0.99, 0.0)), Color::white());
@@ -147,13 +424,51 @@ mod pattern_tests {
 
     #[test]
     fn checkers_alternate_in_z() {
-        let pat = Pattern::new_checkers(Color::white(), Color::black());
+        let pat = Pattern::new_checkerboard(Color::white(), Color::black());
 
         assert_eq!(pat.color_at(&Point(0.0, 0.0, 0.0)), Color::white());
         assert_eq!(pat.color_at(&Point(0.0, 0.0, 0.99)), Color::white());
         assert_eq!(pat.color_at(&Point(0.0, 0.0, 1.01)), Color::black());
     }
 
+    #[test]
+    fn checkers_cycle_through_three_colors() {
+        let pat = Pattern::new_checkers(vec![Color::red(), Color::green(), Color::blue()]);
+
+        assert_eq!(pat.color_at(&Point(0.5, 0.5, 0.5)), Color::red());
+        assert_eq!(pat.color_at(&Point(1.5, 0.5, 0.5)), Color::green());
+        assert_eq!(pat.color_at(&Point(1.5, 1.5, 0.5)), Color::blue());
+        assert_eq!(pat.color_at(&Point(1.5, 1.5, 1.5)), Color::red());
+        assert_eq!(pat.color_at(&Point(0.5, 0.5, 1.5)), Color::green());
+        assert_eq!(pat.color_at(&Point(-0.5, 0.5, 0.5)), Color::blue());
+        assert_eq!(pat.color_at(&Point(-1.5, 0.5, 0.5)), Color::green());
+    }
+
+    #[test]
+    fn nested_checkers_alternate_between_inner_patterns() {
+        let stripes = Pattern::new_stripes(vec![Color::white(), Color::black()])
+            .with_transform(&Matrix::scaling(0.25, 1.0, 1.0));
+        let gradient = Pattern::new_gradient(Color::red(), Color::blue());
+        let pat = Pattern::new_checkers_nested(stripes.clone(), gradient.clone());
+
+        // even cells sample the stripes in their own (scaled) space
+        let p = Point(0.3, 0.0, 0.0);
+        let stripes_pt = Matrix::scaling(4.0, 1.0, 1.0) * p;
+        assert_eq!(pat.color_at(&p), stripes.color_at(&stripes_pt));
+        assert_eq!(pat.color_at(&p), Color::black());
+
+        // odd cells sample the gradient
+        let q = Point(1.5, 0.0, 0.0);
+        assert_eq!(pat.color_at(&q), gradient.color_at(&q));
+        assert_eq!(
+            pat.color_at(&Point(0.5, 1.0, 0.0)),
+            gradient.color_at(&Point(0.5, 1.0, 0.0))
+        );
+
+        // and back to the stripes once more
+        assert_eq!(pat.color_at(&Point(1.1, 1.0, 0.0)), Color::white());
+    }
+
     #[test]
     fn rings_extend_in_x_and_z() {
         let pat = Pattern::new_rings(vec![Color::white(), Color::black()]);
@@ -164,6 +479,39 @@ mod pattern_tests {
         assert_eq!(pat.color_at(&Point(0.708, 0.0, 0.708)), Color::black());
     }
 
+    #[test]
+    fn nearest_sampling_returns_source_pixels() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/samples/textures/2x2.png");
+        let pat = Pattern::load_image_texture(path, UvMapping::Planar)
+            .unwrap()
+            .with_sampling(Sampling::Nearest);
+
+        let Pattern::ImageTexture(tex) = pat else {
+            panic!("expected an image texture");
+        };
+
+        assert_eq!(tex.uv_color_at(0.25, 1.0), Color::red());
+        assert_eq!(tex.uv_color_at(0.75, 1.0), Color::green());
+        assert_eq!(tex.uv_color_at(0.25, 0.0), Color::blue());
+    }
+
+    #[test]
+    fn bilinear_sampling_interpolates_between_pixels() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/samples/textures/2x2.png");
+        let pat = Pattern::load_image_texture(path, UvMapping::Planar)
+            .unwrap()
+            .with_sampling(Sampling::Bilinear);
+
+        let Pattern::ImageTexture(tex) = pat else {
+            panic!("expected an image texture");
+        };
+
+        // exact pixel centers are unchanged
+        assert_eq!(tex.uv_color_at(0.0, 1.0), Color::red());
+        assert_eq!(tex.uv_color_at(0.25, 1.0), Color(0.75, 0.25, 0.0));
+        assert_eq!(tex.uv_color_at(0.5, 0.5), Color(0.5, 0.5, 0.5));
+    }
+
     #[test]
     fn gradient_linearly_interpolates_colors() {
         let pat = Pattern::new_gradient(Color::white(), Color::black());
@@ -180,6 +528,56 @@ mod pattern_tests {
         );
     }
 
+    #[test]
+    fn smooth_stripes_blend_adjacent_colors() {
+        let pat = Pattern::new_smooth_stripes(vec![Color::red(), Color::blue(), Color::green()]);
+
+        assert_eq!(pat.color_at(&Point(0.0, 0.0, 0.0)), Color::red());
+        assert_eq!(pat.color_at(&Point(0.5, 0.0, 0.0)), Color(0.5, 0.0, 0.5));
+        assert_eq!(pat.color_at(&Point(1.5, 0.0, 0.0)), Color(0.0, 0.5, 0.5));
+        // the last stripe blends back into the first
+        assert_eq!(pat.color_at(&Point(2.5, 0.0, 0.0)), Color(0.5, 0.5, 0.0));
+    }
+
+    #[test]
+    fn gradient_wraps_every_unit() {
+        let pat = Pattern::new_gradient(Color::white(), Color::black());
+
+        assert_eq!(pat.color_at(&Point(1.5, 0.0, 0.0)), Color(0.5, 0.5, 0.5));
+        assert_eq!(pat.color_at(&Point(2.0, 0.0, 0.0)), Color::white());
+    }
+
+    #[test]
+    fn gradient_wraps_negative_x() {
+        let pat = Pattern::new_gradient(Color::white(), Color::black());
+
+        assert_eq!(
+            pat.color_at(&Point(-0.25, 0.0, 0.0)),
+            Color(0.25, 0.25, 0.25)
+        );
+        assert_eq!(pat.color_at(&Point(-1.0, 0.0, 0.0)), Color::white());
+    }
+
+    #[test]
+    fn ping_pong_gradient_reflects_every_other_unit() {
+        let pat = Pattern::new_ping_pong_gradient(Color::white(), Color::black());
+
+        assert_eq!(
+            pat.color_at(&Point(0.25, 0.0, 0.0)),
+            Color(0.75, 0.75, 0.75)
+        );
+        assert_eq!(
+            pat.color_at(&Point(1.25, 0.0, 0.0)),
+            Color(0.25, 0.25, 0.25)
+        );
+        assert_eq!(pat.color_at(&Point(2.0, 0.0, 0.0)), Color::white());
+        // mirrored around x = 0.0
+        assert_eq!(
+            pat.color_at(&Point(-0.25, 0.0, 0.0)),
+            Color(0.75, 0.75, 0.75)
+        );
+    }
+
     #[test]
     fn stripes_with_object_and_pattern_transformation() {
         let object = Sphere::default().with_transform(&Matrix::scaling(2.0, 2.0, 2.0));
@@ -222,6 +620,7 @@ mod pattern_tests {
     fn stripes_alternate_in_x() {
         let pat = StripePattern {
             colors: vec![Color::white(), Color::black()],
+            smooth: false,
             transform: Matrix::identity(),
         };
 
@@ -237,6 +636,7 @@ mod pattern_tests {
     fn stripes_constant_in_z() {
         let pat = StripePattern {
             colors: vec![Color::white(), Color::black()],
+            smooth: false,
             transform: Matrix::identity(),
         };
 
@@ -249,6 +649,7 @@ mod pattern_tests {
     fn stripes_constant_in_y() {
         let pat = StripePattern {
             colors: vec![Color::white(), Color::black()],
+            smooth: false,
             transform: Matrix::identity(),
         };
 
@@ -261,6 +662,7 @@ mod pattern_tests {
     fn stripe_patterns_hold_colors() {
         let pat = StripePattern {
             colors: vec![Color::white(), Color::black()],
+            smooth: false,
             transform: Matrix::identity(),
         };
 
@@ -8,8 +8,8 @@ use super::Pattern;
 /// TODO: docs
 #[derive(Debug, PartialEq, Clone)]
 pub struct Blended {
-    pattern1: Box<Pattern>,
-    pattern2: Box<Pattern>,
+    pub(crate) pattern1: Box<Pattern>,
+    pub(crate) pattern2: Box<Pattern>,
     pub(super) transform: Matrix<4>,
 }
 
@@ -3,31 +3,26 @@ use crate::{
     visuals::Color,
 };
 
-/// TODO: docs
+/// A 3D checkerboard of unit cubes, cycling through an arbitrary number of colors. Neighboring
+/// cells along any axis always get consecutive colors.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Checkers {
-    color1: Color,
-    color2: Color,
+    pub(crate) colors: Vec<Color>,
     pub(super) transform: Matrix<4>,
 }
 
 impl Checkers {
-    pub(super) fn new(color1: Color, color2: Color) -> Self {
+    pub(super) fn new(colors: Vec<Color>) -> Self {
         Self {
-            color1,
-            color2,
+            colors,
             transform: Matrix::identity(),
         }
     }
 
     pub(super) fn color_at(&self, pt: &Point) -> Color {
-        let picker =
-            (pt.x().floor().abs() + pt.y().floor().abs() + pt.z().floor().abs()) as usize % 2;
+        let cell = (pt.x().floor() + pt.y().floor() + pt.z().floor()) as i64;
+        let idx = cell.rem_euclid(self.colors.len() as i64) as usize;
 
-        if picker == 0 {
-            self.color1
-        } else {
-            self.color2
-        }
+        self.colors[idx]
     }
 }
@@ -3,19 +3,23 @@ use crate::{
     visuals::Color,
 };
 
-/// A simple gradient pattern which linearly interpolates between two colors.
+/// A simple gradient pattern which linearly interpolates between two colors. The gradient repeats
+/// every unit along the x-axis; in ping-pong mode every other unit runs backwards, so there's no
+/// seam where the gradient restarts.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Gradient {
-    color1: Color,
-    color2: Color,
+    pub(crate) color1: Color,
+    pub(crate) color2: Color,
+    pub(crate) ping_pong: bool,
     pub(super) transform: Matrix<4>,
 }
 
 impl Gradient {
-    pub(super) fn new(color1: Color, color2: Color) -> Self {
+    pub(super) fn new(color1: Color, color2: Color, ping_pong: bool) -> Self {
         Self {
             color1,
             color2,
+            ping_pong,
             transform: Matrix::identity(),
         }
     }
@@ -24,6 +28,12 @@ impl Gradient {
         let c1 = self.color1;
         let c2 = self.color2;
 
-        c1 + (c2 - c1) * pt.x()
+        let mut fraction = pt.x() - pt.x().floor();
+
+        if self.ping_pong && (pt.x().floor() as i64).rem_euclid(2) == 1 {
+            fraction = 1.0 - fraction;
+        }
+
+        c1 + (c2 - c1) * fraction
     }
 }
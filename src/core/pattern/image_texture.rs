@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+
+use image::RgbImage;
+
+use crate::{
+    math::{Matrix, Point},
+    visuals::Color,
+};
+
+use super::UvMapping;
+
+/// How an [ImageTexture] turns `(u, v)` coordinates into a color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sampling {
+    /// Reads the single closest pixel. Keeps pixel art crisp.
+    #[default]
+    Nearest,
+
+    /// Interpolates between the four surrounding pixels. Better for photos.
+    Bilinear,
+}
+
+/// A pattern which wraps an image onto a shape using a [UvMapping].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageTexture {
+    image: RgbImage,
+    pub(crate) mapping: UvMapping,
+    pub(crate) sampling: Sampling,
+    pub(super) transform: Matrix<4>,
+
+    /// The file the image was loaded from, if any, so the scene can be written back out.
+    pub(crate) path: Option<PathBuf>,
+}
+
+impl ImageTexture {
+    pub(crate) fn new(image: RgbImage, mapping: UvMapping) -> Self {
+        Self {
+            image,
+            mapping,
+            sampling: Sampling::default(),
+            transform: Matrix::identity(),
+            path: None,
+        }
+    }
+
+    pub(super) fn color_at(&self, pt: &Point) -> Color {
+        let (u, v) = self.mapping.map(pt);
+
+        self.uv_color_at(u, v)
+    }
+
+    /// Gets the color of the image at `(u, v)` using the texture's [Sampling] method. `v` is
+    /// flipped so that `v = 0.0` is the bottom of the image and `v = 1.0` the top. An empty image
+    /// is black everywhere.
+    pub fn uv_color_at(&self, u: f64, v: f64) -> Color {
+        let (width, height) = self.image.dimensions();
+        if width == 0 || height == 0 {
+            return Color::black();
+        }
+        let x = (u * (width - 1) as f64).clamp(0.0, (width - 1) as f64);
+        let y = ((1.0 - v) * (height - 1) as f64).clamp(0.0, (height - 1) as f64);
+
+        match self.sampling {
+            Sampling::Nearest => self.pixel(x.round() as u32, y.round() as u32),
+            Sampling::Bilinear => {
+                let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+                let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+                let (tx, ty) = (x - x0 as f64, y - y0 as f64);
+
+                let top = self.pixel(x0, y0) * (1.0 - tx) + self.pixel(x1, y0) * tx;
+                let bottom = self.pixel(x0, y1) * (1.0 - tx) + self.pixel(x1, y1) * tx;
+
+                top * (1.0 - ty) + bottom * ty
+            }
+        }
+    }
+
+    fn pixel(&self, x: u32, y: u32) -> Color {
+        let p = self.image.get_pixel(x, y);
+
+        Color(
+            p[0] as f64 / 255.0,
+            p[1] as f64 / 255.0,
+            p[2] as f64 / 255.0,
+        )
+    }
+}
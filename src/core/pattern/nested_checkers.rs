@@ -0,0 +1,36 @@
+use crate::{
+    math::{Matrix, Point, Tuple},
+    visuals::Color,
+};
+
+use super::Pattern;
+
+/// A checkerboard whose cells alternate between two other patterns instead of two colors.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NestedCheckers {
+    pub(crate) pattern1: Box<Pattern>,
+    pub(crate) pattern2: Box<Pattern>,
+    pub(super) transform: Matrix<4>,
+}
+
+impl NestedCheckers {
+    pub(super) fn new(pattern1: Pattern, pattern2: Pattern) -> Self {
+        Self {
+            pattern1: Box::new(pattern1),
+            pattern2: Box::new(pattern2),
+            transform: Matrix::identity(),
+        }
+    }
+
+    pub(super) fn color_at(&self, pt: &Point) -> Color {
+        let cell = (pt.x().floor() + pt.y().floor() + pt.z().floor()) as i64;
+        let inner = if cell.rem_euclid(2) == 0 {
+            &self.pattern1
+        } else {
+            &self.pattern2
+        };
+        let inner_pt = inner.transform().inverse().unwrap() * *pt;
+
+        inner.color_at(&inner_pt)
+    }
+}
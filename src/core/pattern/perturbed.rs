@@ -0,0 +1,40 @@
+use crate::{
+    math::{noise::Perlin, Matrix, Point, Vec3},
+    visuals::Color,
+};
+
+use super::Pattern;
+
+/// Wraps another pattern, jittering each lookup point with Perlin noise before delegating to the
+/// inner pattern. Gives marble-like or wavy variants of the other patterns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Perturbed {
+    pattern: Box<Pattern>,
+    scale: f64,
+    noise: Perlin,
+    pub(super) transform: Matrix<4>,
+}
+
+impl Perturbed {
+    pub(super) fn new(pattern: Pattern, scale: f64) -> Self {
+        Self {
+            pattern: Box::new(pattern),
+            scale,
+            noise: Perlin::default(),
+            transform: Matrix::identity(),
+        }
+    }
+
+    pub(super) fn color_at(&self, pt: &Point) -> Color {
+        // sample the noise at offset points so each axis is jittered independently
+        let jitter = Vec3(
+            self.noise.noise(pt),
+            self.noise.noise(&(*pt + Vec3(17.3, 5.1, -9.7))),
+            self.noise.noise(&(*pt + Vec3(-3.9, 23.8, 11.2))),
+        );
+        let perturbed_pt = *pt + jitter * self.scale;
+        let inner_pt = self.pattern.transform().inverse().unwrap() * perturbed_pt;
+
+        self.pattern.color_at(&inner_pt)
+    }
+}
@@ -6,7 +6,7 @@ use crate::{
 /// A pattern of concentric rings, alternating between an arbitrary number of colors.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Rings {
-    colors: Vec<Color>,
+    pub(crate) colors: Vec<Color>,
     pub(super) transform: Matrix<4>,
 }
 
@@ -3,17 +3,20 @@ use crate::{
     visuals::Color,
 };
 
-/// Accepts a vector of colors to construct a striped pattern.
+/// Accepts a vector of colors to construct a striped pattern. Smooth stripes blend linearly into
+/// the next color instead of switching abruptly at each stripe boundary.
 #[derive(Debug, Clone, PartialEq)]
 pub struct StripePattern {
-    pub(super) colors: Vec<Color>,
+    pub(crate) colors: Vec<Color>,
+    pub(crate) smooth: bool,
     pub(super) transform: Matrix<4>,
 }
 
 impl StripePattern {
-    pub(super) fn new(colors: Vec<Color>) -> Self {
+    pub(super) fn new(colors: Vec<Color>, smooth: bool) -> Self {
         Self {
             colors,
+            smooth,
             transform: Matrix::identity(),
         }
     }
@@ -22,6 +25,13 @@ impl StripePattern {
     pub(super) fn color_at(&self, pt: &Point) -> Color {
         let idx = pt.x().floor().abs() as usize % self.colors.len();
 
-        self.colors[idx]
+        if self.smooth {
+            let next = self.colors[(idx + 1) % self.colors.len()];
+            let fraction = pt.x() - pt.x().floor();
+
+            self.colors[idx] + (next - self.colors[idx]) * fraction
+        } else {
+            self.colors[idx]
+        }
     }
 }
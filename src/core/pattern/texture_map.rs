@@ -0,0 +1,145 @@
+use std::f64::consts::PI;
+
+use crate::{
+    math::{Matrix, Point, Tuple},
+    visuals::Color,
+};
+
+/// Functions which convert a point on the surface of a shape into a 2D `(u, v)` coordinate, with
+/// both `u` and `v` between 0.0 and 1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UvMapping {
+    /// Wraps the texture around a unit sphere. See [spherical_map].
+    Spherical,
+
+    /// Tiles the texture along the `xz`-plane. See [planar_map].
+    Planar,
+
+    /// Wraps the texture around a unit cylinder, repeating every unit in `y`. See
+    /// [cylindrical_map].
+    Cylindrical,
+}
+
+impl UvMapping {
+    /// Maps the given point to `(u, v)` coordinates using the chosen mapping function.
+    pub fn map(&self, pt: &Point) -> (f64, f64) {
+        match self {
+            Self::Spherical => spherical_map(pt),
+            Self::Planar => planar_map(pt),
+            Self::Cylindrical => cylindrical_map(pt),
+        }
+    }
+}
+
+/// Maps a point on the surface of a sphere to `(u, v)`, where `u` is the longitude and `v` the
+/// latitude, both scaled to `[0.0, 1.0]`.
+pub fn spherical_map(pt: &Point) -> (f64, f64) {
+    // azimuthal angle in (-pi, pi], increasing clockwise when viewed from above
+    let theta = pt.x().atan2(pt.z());
+    let radius = (pt.x() * pt.x() + pt.y() * pt.y() + pt.z() * pt.z()).sqrt();
+
+    // polar angle in [0, pi]
+    let phi = (pt.y() / radius).acos();
+    let raw_u = theta / (2.0 * PI);
+
+    // flip u so it increases counter-clockwise when viewed from above
+    let u = 1.0 - (raw_u + 0.5);
+    let v = 1.0 - phi / PI;
+
+    (u, v)
+}
+
+/// Maps a point to `(u, v)` by taking the fractional parts of its `x` and `z` coordinates. The
+/// texture repeats every unit.
+pub fn planar_map(pt: &Point) -> (f64, f64) {
+    (pt.x().rem_euclid(1.0), pt.z().rem_euclid(1.0))
+}
+
+/// Maps a point on the surface of a cylinder to `(u, v)`. `u` wraps around the `y`-axis like the
+/// [spherical_map], and `v` repeats every unit in `y`.
+pub fn cylindrical_map(pt: &Point) -> (f64, f64) {
+    let theta = pt.x().atan2(pt.z());
+    let raw_u = theta / (2.0 * PI);
+    let u = 1.0 - (raw_u + 0.5);
+    let v = pt.y().rem_euclid(1.0);
+
+    (u, v)
+}
+
+/// Patterns defined in 2D `(u, v)` space rather than 3D space. These are applied to shapes through
+/// a [TextureMap].
+#[derive(Debug, Clone, PartialEq)]
+pub enum UvPattern {
+    /// A checkerboard in `(u, v)` space.
+    Checkers(UvCheckers),
+}
+
+impl UvPattern {
+    /// Creates a checkerboard with `width` squares along `u` and `height` squares along `v`.
+    pub fn new_checkers(width: f64, height: f64, color1: Color, color2: Color) -> Self {
+        Self::Checkers(UvCheckers::new(width, height, color1, color2))
+    }
+
+    /// Gets the color of the pattern at the given `(u, v)` coordinates.
+    pub fn uv_color_at(&self, u: f64, v: f64) -> Color {
+        match self {
+            Self::Checkers(checkers) => checkers.uv_color_at(u, v),
+        }
+    }
+}
+
+/// A two-color checkerboard in `(u, v)` space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UvCheckers {
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    pub(crate) color1: Color,
+    pub(crate) color2: Color,
+}
+
+impl UvCheckers {
+    fn new(width: f64, height: f64, color1: Color, color2: Color) -> Self {
+        Self {
+            width,
+            height,
+            color1,
+            color2,
+        }
+    }
+
+    fn uv_color_at(&self, u: f64, v: f64) -> Color {
+        let u2 = (u * self.width).floor();
+        let v2 = (v * self.height).floor();
+
+        if (u2 + v2).rem_euclid(2.0) == 0.0 {
+            self.color1
+        } else {
+            self.color2
+        }
+    }
+}
+
+/// Ties a [UvMapping] to a [UvPattern], converting 3D points to `(u, v)` before looking up the
+/// color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureMap {
+    pub(crate) uv_pattern: UvPattern,
+    pub(crate) mapping: UvMapping,
+    pub(super) transform: Matrix<4>,
+}
+
+impl TextureMap {
+    pub(super) fn new(uv_pattern: UvPattern, mapping: UvMapping) -> Self {
+        Self {
+            uv_pattern,
+            mapping,
+            transform: Matrix::identity(),
+        }
+    }
+
+    pub(super) fn color_at(&self, pt: &Point) -> Color {
+        let (u, v) = self.mapping.map(pt);
+
+        self.uv_pattern.uv_color_at(u, v)
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Intersectable, Intersection, IntersectionList, Ray, EPS};
+use super::{Intersection, IntersectionList, Ray, EPS};
 use crate::{
     math::{Point, Vec3},
     shape::Shape,
@@ -31,6 +31,10 @@ pub struct PrecomputedData {
     /// TODO: docs
     pub(crate) n1: f64,
     pub(crate) n2: f64,
+
+    /// Roughly how wide the patch of surface covered by the ray is at the hit (see
+    /// [Ray::spread]).
+    pub(crate) footprint: f64,
 }
 
 impl PrecomputedData {
@@ -64,6 +68,7 @@ impl PrecomputedData {
             reflectv,
             n1,
             n2,
+            footprint: ray.spread * t,
         }
     }
 }
@@ -74,6 +79,9 @@ fn set_refractive_indices(ix: &Intersection, xs: &IntersectionList) -> (f64, f64
     let mut n1 = None;
     let mut n2 = None;
 
+    // `ix` is one of the entries of `xs`, so exact equality is what we want here: an approximate
+    // comparison could mistake a neighboring intersection at almost the same `t` (e.g. where two
+    // objects touch) for the hit
     for interesction in xs.data.iter() {
         if interesction == ix {
             n1 = containers.last().map(|o| o.material().refractive_index);
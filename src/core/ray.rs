@@ -10,12 +10,58 @@ use crate::math::{Matrix, Point, Vec3};
 pub struct Ray {
     pub origin: Point,
     pub direction: Vec3,
+
+    /// How fast the patch of surface the ray stands for widens with distance: at `t`, it's about
+    /// `spread * t` across. Camera rays spread by one pixel per unit of distance; other rays
+    /// default to 0, i.e. an infinitely thin ray. Used to filter patterns (see
+    /// [Material::with_pattern_samples](crate::core::Material::with_pattern_samples)).
+    pub spread: f64,
 }
 
 impl Ray {
     /// Constructs a new ray with the given origin (a [`Point`]) and direction (a [`Vec3`]).
     pub fn new(origin: Point, direction: Vec3) -> Self {
-        Self { origin, direction }
+        Self {
+            origin,
+            direction,
+            spread: 0.0,
+        }
+    }
+
+    /// Sets the ray's [spread](Ray::spread).
+    pub fn with_spread(mut self, spread: f64) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    /// The ray leaving `origin` after `incoming` bounces off a surface with the given `normal`.
+    pub fn reflected(origin: Point, incoming: Vec3, normal: Vec3) -> Self {
+        Self::new(origin, incoming.reflect(&normal))
+    }
+
+    /// The ray leaving `origin` after `incoming` passes from a medium with refractive index `n1`
+    /// into one with index `n2`, bent according to Snell's law. `normal` must be a unit vector
+    /// pointing back toward the side the ray came from. Returns `None` on total internal
+    /// reflection, when no light passes through.
+    pub fn refracted(
+        origin: Point,
+        incoming: Vec3,
+        normal: Vec3,
+        n1: f64,
+        n2: f64,
+    ) -> Option<Self> {
+        let eyev = -incoming.normalize();
+        let n_ratio = n1 / n2;
+        let cos_i = eyev.dot(&normal);
+        let sin2_t = n_ratio * n_ratio * (1.0 - cos_i * cos_i);
+        if sin2_t > 1.0 {
+            return None;
+        }
+
+        let cos_t = (1.0 - sin2_t).sqrt();
+        let direction = normal * (n_ratio * cos_i - cos_t) - eyev * n_ratio;
+
+        Some(Self::new(origin, direction))
     }
 
     /// Given a time `t`, determines the position of the ray.
@@ -26,12 +72,14 @@ impl Ray {
     /// Applies the transformation matrix m to the ray, which allows us to manipulate simple rays
     /// instead of complicated shapes/objects.
     pub(crate) fn transform(&self, m: Matrix<4>) -> Self {
-        Self::new(m * self.origin, m * self.direction)
+        Self::new(m * self.origin, m * self.direction).with_spread(self.spread)
     }
 }
 
 #[cfg(test)]
 mod ray_tests {
+    use std::f64::consts::FRAC_1_SQRT_2;
+
     use super::*;
 
     #[test]
@@ -64,6 +112,49 @@ mod ray_tests {
         assert_eq!(r.position(2.5), Point(4.5, 3.0, 4.0));
     }
 
+    #[test]
+    fn reflecting_a_ray_at_45_degrees() {
+        let r = Ray::reflected(
+            Point(0.0, 0.0, 0.0),
+            Vec3(FRAC_1_SQRT_2, -FRAC_1_SQRT_2, 0.0),
+            Vec3(0.0, 1.0, 0.0),
+        );
+
+        assert_eq!(r.origin, Point(0.0, 0.0, 0.0));
+        assert!((r.direction - Vec3(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0)).magnitude() < 1e-10);
+    }
+
+    #[test]
+    fn refracting_a_ray_follows_snells_law() {
+        let origin = Point(0.0, 0.0, 0.0);
+        let normal = Vec3(0.0, 1.0, 0.0);
+
+        // head-on rays pass straight through
+        let straight = Ray::refracted(origin, Vec3(0.0, -1.0, 0.0), normal, 1.0, 1.5).unwrap();
+        assert!((straight.direction - Vec3(0.0, -1.0, 0.0)).magnitude() < 1e-10);
+
+        // entering glass bends the ray toward the normal
+        let incoming = Vec3(FRAC_1_SQRT_2, -FRAC_1_SQRT_2, 0.0);
+        let bent = Ray::refracted(origin, incoming, normal, 1.0, 1.5).unwrap();
+        assert!((bent.direction.magnitude() - 1.0).abs() < 1e-10);
+        assert!((bent.direction.0 - FRAC_1_SQRT_2 / 1.5).abs() < 1e-10);
+        assert!(bent.direction.1 < 0.0);
+    }
+
+    #[test]
+    fn refracting_past_the_critical_angle_reflects_totally() {
+        let incoming = Vec3(FRAC_1_SQRT_2, -FRAC_1_SQRT_2, 0.0);
+        let r = Ray::refracted(
+            Point(0.0, 0.0, 0.0),
+            incoming,
+            Vec3(0.0, 1.0, 0.0),
+            1.5,
+            1.0,
+        );
+
+        assert!(r.is_none());
+    }
+
     #[test]
     fn test_making_rays() {
         let o = Point(1.0, 2.0, 3.0);
@@ -0,0 +1,51 @@
+//! Counting the work done during a render. See [World::with_stats](super::World::with_stats).
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// How many rays of each kind were traced, and how many times a ray was tested against a shape.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    /// Rays cast from the camera, one per pixel or per anti-aliasing sample.
+    pub primary_rays: u64,
+
+    /// Rays cast toward (samples of) lights to check for shadows.
+    pub shadow_rays: u64,
+
+    /// Rays cast off reflective surfaces.
+    pub reflection_rays: u64,
+
+    /// Ray-shape intersection tests, for any kind of ray.
+    pub intersection_tests: u64,
+}
+
+/// The counters behind [RenderStats]. They're atomic so that rows can be rendered in parallel.
+#[derive(Debug, Default)]
+pub(crate) struct RayCounters {
+    pub(crate) primary_rays: AtomicU64,
+    pub(crate) shadow_rays: AtomicU64,
+    pub(crate) reflection_rays: AtomicU64,
+    pub(crate) intersection_tests: AtomicU64,
+}
+
+impl RayCounters {
+    /// The current counts.
+    pub(crate) fn snapshot(&self) -> RenderStats {
+        RenderStats {
+            primary_rays: self.primary_rays.load(Ordering::Relaxed),
+            shadow_rays: self.shadow_rays.load(Ordering::Relaxed),
+            reflection_rays: self.reflection_rays.load(Ordering::Relaxed),
+            intersection_tests: self.intersection_tests.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Sets every count back to zero.
+    pub(crate) fn reset(&self) {
+        for counter in [
+            &self.primary_rays,
+            &self.shadow_rays,
+            &self.reflection_rays,
+            &self.intersection_tests,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
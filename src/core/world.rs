@@ -1,84 +1,499 @@
 //! A structure consisting of collections of objects in a scene.
-use crate::{math::Point, shape::Shape, visuals::Color};
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use image::RgbImage;
+use rand::prelude::*;
+
+use crate::{
+    io::error::SceneError,
+    math::{sampling::uniform_cone, Matrix, Point, Tuple},
+    shape::Shape,
+    visuals::Color,
+};
 
 use super::{
-    light::Light, material::Material, precompute::PrecomputedData, Intersectable, IntersectionList,
-    Ray,
+    camera::Camera,
+    light::{Light, LightType},
+    pattern::{texture_map::spherical_map, ImageTexture, UvMapping},
+    precompute::PrecomputedData,
+    stats::{RayCounters, RenderStats},
+    Intersectable, Intersection, IntersectionList, Ray,
 };
 
+/// How many rays are averaged for each bounce off a surface with fuzzy reflections.
+const FUZZ_SAMPLES: usize = 8;
+
+/// A predicate deciding whether an intersection should be kept. See
+/// [World::with_intersection_filter].
+pub type IntersectionFilter = Box<dyn Fn(&Intersection, &Ray) -> bool + Send + Sync>;
+
+/// What rays which don't hit anything see. See [World::with_background],
+/// [World::with_sky_gradient], and [World::with_environment_map].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Background {
+    /// The same color in every direction.
+    Solid(Color),
+
+    /// Blends from `bottom` (looking straight down) to `top` (looking straight up).
+    SkyGradient { top: Color, bottom: Color },
+
+    /// An equirectangular panorama wrapped around the whole scene.
+    EnvironmentMap(ImageTexture),
+}
+
 /// A structure containing objects and lights.
-#[derive(Default)]
 pub struct World {
     pub objects: Vec<Shape>,
     pub lights: Vec<Light>,
+    /// What rays which don't hit anything see.
+    pub background: Background,
+    filter: Option<IntersectionFilter>,
+    russian_roulette: bool,
+    reflection_depth: usize,
+    fog: Option<(Color, f64)>,
+    stats: Option<RayCounters>,
 }
 
 impl World {
+    /// How many times a ray may bounce between reflective surfaces unless configured otherwise.
+    pub const DEFAULT_REFLECTION_DEPTH: usize = 5;
+
     /// Creates a new world with the specified objects and lights.
     pub fn new(objects: Vec<Shape>, lights: Vec<Light>) -> Self {
-        Self { objects, lights }
+        Self {
+            objects,
+            lights,
+            background: Background::Solid(Color::black()),
+            filter: None,
+            russian_roulette: false,
+            reflection_depth: Self::DEFAULT_REFLECTION_DEPTH,
+            fog: None,
+            stats: None,
+        }
+    }
+
+    /// Sets the color seen by rays which escape the scene, including reflected rays.
+    pub fn with_background(mut self, background: Color) -> Self {
+        self.background = Background::Solid(background);
+        self
+    }
+
+    /// Colors escaping rays by their direction, blending from `bottom` for rays heading straight
+    /// down to `top` for rays heading straight up, e.g. a blue sky fading to a white horizon.
+    pub fn with_sky_gradient(mut self, top: Color, bottom: Color) -> Self {
+        self.background = Background::SkyGradient { top, bottom };
+        self
+    }
+
+    /// Surrounds the scene with an equirectangular panorama (360 degrees across, 180 degrees top to
+    /// bottom), so that escaping rays, and reflections of them, see a real environment. The image
+    /// can be loaded like an image texture, e.g. `image::open("sky.png")?.to_rgb8()`.
+    pub fn with_environment_map(mut self, image: RgbImage) -> Self {
+        self.background =
+            Background::EnvironmentMap(ImageTexture::new(image, UvMapping::Spherical));
+        self
+    }
+
+    /// Finds the first object with the given [name](Shape::name).
+    pub fn object_by_name(&self, name: &str) -> Option<&Shape> {
+        self.objects.iter().find(|o| o.name() == Some(name))
+    }
+
+    /// Intersects the ray with every object in the world, e.g. to find what's under the mouse in
+    /// a viewer. The intersections are sorted by `t`, nearest first, and include those behind the
+    /// ray's origin (negative `t`). The [intersection filter](World::with_intersection_filter)
+    /// applies.
+    pub fn cast_ray(&self, ray: Ray) -> IntersectionList {
+        self.intersect_world(ray)
+            .unwrap_or_else(|| IntersectionList::new(vec![]))
+    }
+
+    /// The object first hit by the ray, in front of its origin. Objects are told apart by their
+    /// place in [objects](World::objects) rather than by comparing them, so of several identical
+    /// objects hit at the same distance, the one added first is picked.
+    pub fn pick(&self, ray: Ray) -> Option<&Shape> {
+        self.count(|c| &c.intersection_tests, self.objects.len() as u64);
+
+        self.objects
+            .iter()
+            .filter_map(|o| {
+                let mut xs = o.intersect(ray)?;
+                if let Some(ref keep) = self.filter {
+                    xs.data.retain(|ix| keep(ix, &ray));
+                }
+
+                Some((o, xs.hit()?.t))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(o, _)| o)
+    }
+
+    /// The color seen along a ray which escapes the scene.
+    pub fn background_at(&self, r: &Ray) -> Color {
+        match &self.background {
+            Background::Solid(color) => *color,
+            Background::SkyGradient { top, bottom } => {
+                let t = (r.direction.normalize().y() + 1.0) / 2.0;
+
+                *bottom + (*top - *bottom) * t
+            }
+            Background::EnvironmentMap(texture) => {
+                // the direction is where the ray would hit a unit sphere centered on its origin
+                let d = r.direction.normalize();
+                let (u, v) = spherical_map(&Point(d.x(), d.y(), d.z()));
+
+                texture.uv_color_at(u, v)
+            }
+        }
+    }
+
+    /// Sets the recursion depth this world is meant to be rendered with, i.e. how many reflection
+    /// bounces are followed before giving up. A depth of 0 disables reflections entirely.
+    pub fn with_reflection_depth(mut self, depth: usize) -> Self {
+        self.reflection_depth = depth;
+        self
+    }
+
+    /// The recursion depth set by [World::with_reflection_depth], to pass along to
+    /// [Camera::render](crate::core::Camera::render) and friends.
+    pub fn reflection_depth(&self) -> usize {
+        self.reflection_depth
+    }
+
+    /// Only keeps the intersections for which `filter` returns `true`; everything else is treated
+    /// as if the ray passed straight through. Handy for cutaways and clipping planes, e.g.
+    /// dropping every intersection above `y = 0` to show a cross-section:
+    ///
+    /// ```
+    /// # use rtc::{core::World, math::Tuple};
+    /// let world = World::default()
+    ///     .with_intersection_filter(|ix, ray| ray.position(ix.t).y() <= 0.0);
+    /// ```
+    pub fn with_intersection_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&Intersection, &Ray) -> bool + Send + Sync + 'static,
+    {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Enables Russian-roulette termination for reflections. Normally a reflection stops once the
+    /// recursion depth passed to the render runs out, which darkens surfaces that keep reflecting
+    /// each other. With this enabled, that depth becomes a minimum instead: past it, each
+    /// reflected ray survives with a probability equal to the surface's reflectiveness (capped at
+    /// 0.95), and survivors are scaled up to make up for the rays that were killed. Individual
+    /// samples get noisier, but their average converges to the fully-reflected color, so this
+    /// pairs well with anti-aliasing methods that take many samples per pixel.
+    pub fn with_russian_roulette(mut self) -> Self {
+        self.russian_roulette = true;
+        self
+    }
+
+    /// Fades hits toward `color` the farther away they are, blending by `1 - exp(-density * t)`
+    /// for a hit at distance `t`. A density of 0 has no effect, and larger densities make the fog
+    /// thicker. Gives a sense of depth to large scenes. Rays which miss everything see the
+    /// background as usual.
+    pub fn with_fog(mut self, color: Color, density: f64) -> Self {
+        self.fog = Some((color, density));
+        self
+    }
+
+    /// Counts the rays traced and intersection tests done while rendering this world, to find out
+    /// what makes a scene expensive. Read the counts with [World::stats]. Counting has a small cost
+    /// of its own, so it's off by default.
+    pub fn with_stats(mut self) -> Self {
+        self.stats = Some(RayCounters::default());
+        self
+    }
+
+    /// The counts accumulated since [World::with_stats] or the last [World::reset_stats], or
+    /// `None` if counting isn't enabled.
+    pub fn stats(&self) -> Option<RenderStats> {
+        self.stats.as_ref().map(RayCounters::snapshot)
+    }
+
+    /// Sets the counts back to zero, e.g. between renders of the same world.
+    pub fn reset_stats(&self) {
+        if let Some(ref stats) = self.stats {
+            stats.reset();
+        }
+    }
+
+    /// Adds `n` to one of the counters, if counting is enabled.
+    fn count(&self, counter: impl FnOnce(&RayCounters) -> &AtomicU64, n: u64) {
+        if let Some(ref stats) = self.stats {
+            counter(stats).fetch_add(n, Ordering::Relaxed);
+        }
+    }
+
+    /// Notes that a ray was cast from the camera. See [World::primary_color].
+    pub(crate) fn count_primary_ray(&self) {
+        self.count(|c| &c.primary_rays, 1);
+    }
+
+    /// Like [World::color_at], but for rays cast from the camera, which are counted separately
+    /// from the rays they spawn.
+    pub(crate) fn primary_color(&self, r: Ray, remaining: usize, rng: &mut dyn RngCore) -> Color {
+        self.count_primary_ray();
+        self.color_at(r, remaining, rng)
+    }
+
+    /// Like [World::hit_data], but for rays cast from the camera. Debug render modes use this so
+    /// their primary rays are counted the same way as shaded ones.
+    pub(crate) fn primary_hit(&self, r: Ray) -> Option<PrecomputedData> {
+        self.count_primary_ray();
+        self.hit_data(r)
+    }
+
+    /// Describes what's in the world: how many objects and lights of each kind there are, and the
+    /// box containing every bounded object. Useful for checking that a scene file was parsed as
+    /// expected before starting a long render. Also available through the `Display` impl.
+    ///
+    /// ```text
+    /// 3 objects (2 spheres, 1 plane)
+    /// 1 light (1 point)
+    /// bounds: (-1.00, -1.00, -1.00) to (3.00, 1.00, 1.00), plus 1 unbounded object
+    /// ```
+    pub fn summary(&self) -> String {
+        let shapes = tally(self.objects.iter().map(|o| match o {
+            Shape::Sphere(_) => "sphere",
+            Shape::Plane(_) => "plane",
+            Shape::Quad(_) => "quad",
+            Shape::Portal(_) => "portal",
+            Shape::Custom(_) => "custom shape",
+        }));
+        let lights = tally(self.lights.iter().map(|l| match l.light_type {
+            LightType::PointLight(_) => "point",
+            LightType::DiskLight(_) => "disk",
+            LightType::SphereLight(_) => "sphere",
+        }));
+
+        let bounds = self
+            .objects
+            .iter()
+            .filter_map(|o| o.bounds())
+            .reduce(|a, b| {
+                (
+                    Point(
+                        a.0.x().min(b.0.x()),
+                        a.0.y().min(b.0.y()),
+                        a.0.z().min(b.0.z()),
+                    ),
+                    Point(
+                        a.1.x().max(b.1.x()),
+                        a.1.y().max(b.1.y()),
+                        a.1.z().max(b.1.z()),
+                    ),
+                )
+            });
+        let unbounded = self.objects.iter().filter(|o| o.bounds().is_none()).count();
+
+        let mut bounds = match bounds {
+            Some((min, max)) => format!(
+                "bounds: ({:.2}, {:.2}, {:.2}) to ({:.2}, {:.2}, {:.2})",
+                min.x(),
+                min.y(),
+                min.z(),
+                max.x(),
+                max.y(),
+                max.z()
+            ),
+            None => String::from("bounds: none"),
+        };
+        if unbounded > 0 {
+            bounds += &format!(", plus {}", plural(unbounded, "unbounded object"));
+        }
+
+        let with_breakdown = |count, noun, breakdown: String| {
+            if breakdown.is_empty() {
+                plural(count, noun)
+            } else {
+                format!("{} ({})", plural(count, noun), breakdown)
+            }
+        };
+
+        format!(
+            "{}\n{}\n{}",
+            with_breakdown(self.objects.len(), "object", shapes),
+            with_breakdown(self.lights.len(), "light", lights),
+            bounds
+        )
     }
 
     /// Iterates over every object ([Shape](crate::shape::Shape)) in the world, intersecting
     /// each with the ray, and collecting the intersections. __Note:__ this sorts the collected
     /// intersections (see [IntersectionList](crate::core::IntersectionList)).
     pub(crate) fn intersect_world(&self, ray: Ray) -> Option<IntersectionList> {
-        let xs = self.objects.iter().flat_map(|o| o.intersect(ray)).collect();
+        self.count(|c| &c.intersection_tests, self.objects.len() as u64);
+        let mut xs: Vec<Intersection> =
+            self.objects.iter().flat_map(|o| o.intersect(ray)).collect();
+
+        if let Some(ref keep) = self.filter {
+            xs.retain(|ix| keep(ix, &ray));
+        }
 
         Some(IntersectionList::new(xs))
     }
 
-    /// Determines the color of the pixel hit by the provided ray. If there was no hit,
-    /// `Color::black()` is returned instead.
-    pub(crate) fn color_at(&self, r: Ray, remaining: usize) -> Color {
-        let xs = self.intersect_world(r);
+    /// Determines the color of the pixel hit by the provided ray. If there was no hit, the
+    /// world's background is returned instead. Any random choices along the way (fuzzy
+    /// reflections, Russian roulette) are drawn from `rng`, so a seeded `rng` gives the same color
+    /// every time.
+    pub(crate) fn color_at(&self, r: Ray, remaining: usize, rng: &mut dyn RngCore) -> Color {
+        match self.hit_data(r) {
+            Some(comps) => {
+                let color = self.shade_hit(&comps, remaining, rng);
 
-        // TODO: added a clone here that I'm not sure I want to keep. And I'm unwrapping xs below.
-        if let Some(mut ix) = xs.clone() {
-            if let Some(hit) = ix.hit() {
-                let comps = PrecomputedData::new(hit, &r, &xs.unwrap());
-                self.shade_hit(&comps, remaining)
-            } else {
-                Color::black()
+                match self.fog {
+                    Some((fog, density)) => {
+                        let amount = 1.0 - (-density * comps.t).exp();
+                        color + (fog - color) * amount
+                    }
+                    None => color,
+                }
             }
-        } else {
-            Color::black()
+            None => self.background_at(&r),
         }
     }
 
+    /// Finds the nearest hit along the ray and precomputes everything needed to shade it.
+    pub(crate) fn hit_data(&self, r: Ray) -> Option<PrecomputedData> {
+        let xs = self.intersect_world(r)?;
+        let hit = xs.hit()?;
+
+        Some(PrecomputedData::new(hit, &r, &xs))
+    }
+
     /// Shades the hit by blending the object's surface color and the reflected color. __Note:__
     /// this calls `reflected_color()`, which calls `color_at()`, which calls `shade_hit()`...
-    fn shade_hit(&self, comps: &PrecomputedData, remaining: usize) -> Color {
-        let surface: Color = self
-            .lights
-            .iter()
-            .map(|l| {
-                Material::lighting(
-                    &comps.object.material(),
-                    &comps.object,
-                    l,
-                    &comps.over_point,
-                    &comps.eyev,
-                    &comps.normalv,
-                    self.is_shadowed(&comps.over_point, l),
+    fn shade_hit(&self, comps: &PrecomputedData, remaining: usize, rng: &mut dyn RngCore) -> Color {
+        // portals have no surface; the ray just carries on from the linked location
+        if let Shape::Portal(ref portal) = comps.object {
+            return if remaining == 0 {
+                Color::black()
+            } else {
+                self.color_at(
+                    portal.teleport(comps.point, -comps.eyev),
+                    remaining - 1,
+                    rng,
                 )
-            })
-            .sum();
-        let reflected = self.reflected_color(comps, remaining);
+            };
+        }
+
+        // area lights are shaded as a collection of point lights, each shadowed separately
+        let lights = self.lights.iter().flat_map(|l| l.samples()).map(|s| {
+            let l = Light::from(s);
+            let in_shadow = self.is_shadowed(&comps.over_point, &l);
+
+            (l, in_shadow)
+        });
+        let surface = comps.object.material().lighting_at(comps, lights);
+        let reflected = self.reflected_color(comps, remaining, rng);
 
         surface + reflected
     }
 
     /// Determines the color of the material, taking into account its reflectiveness.
-    pub(crate) fn reflected_color(&self, comps: &PrecomputedData, remaining: usize) -> Color {
-        if remaining == 0 || comps.object.material().reflective == 0.0 {
-            Color::black()
+    pub(crate) fn reflected_color(
+        &self,
+        comps: &PrecomputedData,
+        remaining: usize,
+        rng: &mut dyn RngCore,
+    ) -> Color {
+        let reflective = comps.object.material().reflectivity();
+        if reflective == 0.0 {
+            return Color::black();
+        }
+
+        if remaining > 0 {
+            return self.trace_reflection(comps, remaining - 1, FUZZ_SAMPLES, rng) * reflective;
+        }
+        if !self.russian_roulette {
+            return Color::black();
+        }
+
+        // past the minimum depth: kill the ray with probability `1 - survival`, and compensate the
+        // survivors so the expected color stays the same. Fuzzy surfaces only follow a single
+        // scattered ray here, otherwise every bounce would spawn more survivors than it kills and
+        // the recursion would never end.
+        let survival = reflective.min(0.95);
+        if rng.gen::<f64>() < survival {
+            self.trace_reflection(comps, 0, 1, rng) * (reflective / survival)
         } else {
-            let reflect_ray = Ray::new(comps.over_point, comps.reflectv);
-            let col = self.color_at(reflect_ray, remaining - 1);
+            Color::black()
+        }
+    }
 
-            col * comps.object.material().reflective
+    /// Follows the reflection off the hit, before it's attenuated by the material. Fuzzy
+    /// materials average `samples` rays scattered around the mirror direction.
+    fn trace_reflection(
+        &self,
+        comps: &PrecomputedData,
+        remaining: usize,
+        samples: usize,
+        rng: &mut dyn RngCore,
+    ) -> Color {
+        let fuzz = comps.object.material().reflection_fuzz;
+        if fuzz == 0.0 {
+            self.count(|c| &c.reflection_rays, 1);
+            let r = Ray::reflected(comps.over_point, -comps.eyev, comps.normalv);
+            return self.color_at(r, remaining, rng);
         }
+
+        self.count(|c| &c.reflection_rays, samples as u64);
+        let total: Color = (0..samples)
+            .map(|_| {
+                let direction = uniform_cone(&comps.reflectv, fuzz, rng.gen(), rng.gen());
+                // rays scattered into the surface are mirrored back out of it
+                let r = if direction.dot(&comps.normalv) < 0.0 {
+                    Ray::reflected(comps.over_point, direction, comps.normalv)
+                } else {
+                    Ray::new(comps.over_point, direction)
+                };
+
+                self.color_at(r, remaining, rng)
+            })
+            .sum();
+
+        total / samples as f64
+    }
+
+    /// Counts how many reflection bounces contribute to the color seen along `r`, following the
+    /// same path as [World::color_at]. Counting stops when `remaining` runs out, the ray escapes,
+    /// it hits a non-reflective surface, or the reflected light is too dim to show up in an 8-bit
+    /// image. Portals are followed just like in `color_at`, using up depth without counting as a
+    /// bounce.
+    pub(crate) fn reflection_bounces(&self, r: Ray, remaining: usize) -> usize {
+        let mut ray = r;
+        let mut remaining = remaining;
+        let mut bounces = 0;
+        let mut throughput = 1.0;
+
+        while remaining > 0 {
+            let Some(comps) = self.hit_data(ray) else {
+                return bounces;
+            };
+            remaining -= 1;
+
+            // like color_at, passing through a portal uses up depth without being a reflection
+            if let Shape::Portal(ref portal) = comps.object {
+                ray = portal.teleport(comps.point, -comps.eyev);
+                continue;
+            }
+
+            throughput *= comps.object.material().reflectivity();
+            if throughput < 1.0 / 255.0 {
+                return bounces;
+            }
+
+            ray = Ray::reflected(comps.over_point, -comps.eyev, comps.normalv);
+            bounces += 1;
+        }
+
+        bounces
     }
 
     /// Iterates through every light source and determines if the point in question lies in a
@@ -86,35 +501,440 @@ impl World {
     fn is_shadowed(&self, p: &Point, light: &Light) -> bool {
         let v = light.position() - p;
         let distance = v.magnitude();
-        let direction = v.normalize();
-        let r = Ray::new(*p, direction);
+        let r = Ray::new(*p, v.normalize());
+        self.count(|c| &c.shadow_rays, 1);
 
-        let xs = self.intersect_world(r);
-        if let Some(mut ix) = xs {
-            if let Some(hit) = ix.hit() {
-                hit.t < distance
-            } else {
-                false
-            }
-        } else {
-            false
+        self.any_hit(r, distance)
+    }
+
+    /// Checks whether the ray hits any object at some `t` in `[0, max_t)`, stopping at the first
+    /// one found. Only objects which [cast shadows](super::Material::with_casts_shadow) count, so
+    /// portals (which light passes straight through) and shadowless materials are skipped.
+    pub fn any_hit(&self, r: Ray, max_t: f64) -> bool {
+        self.objects
+            .iter()
+            .filter(|o| o.casts_shadow())
+            .inspect(|_| self.count(|c| &c.intersection_tests, 1))
+            .any(|o| self.blocks(o, r, max_t))
+    }
+
+    /// Whether `object` is hit at some `t` in `[0, max_t)`, skipping any intersections the
+    /// [intersection filter](World::with_intersection_filter) drops, so that clipped-away
+    /// geometry doesn't cast shadows either.
+    fn blocks(&self, object: &Shape, r: Ray, max_t: f64) -> bool {
+        match self.filter {
+            None => object.intersects_before(r, max_t),
+            Some(ref keep) => object.intersect(r).is_some_and(|xs| {
+                xs.data
+                    .iter()
+                    .any(|ix| ix.t >= 0.0 && ix.t < max_t && keep(ix, &r))
+            }),
         }
     }
 }
 
+impl Default for World {
+    fn default() -> Self {
+        Self::new(vec![], vec![])
+    }
+}
+
+/// Builds a [World] one piece at a time, checking it for common mistakes (like forgetting the
+/// lights, so that everything renders black) before a long render is started.
+///
+/// # Example
+/// ```
+/// # use rtc::{core::{world::WorldBuilder, Light}, math::Point, shape::Sphere, visuals::Color};
+/// let world = WorldBuilder::new()
+///     .add_shape(Sphere::default().as_shape())
+///     .add_light(Light::new_point_light(Point(-10.0, 10.0, -10.0), Color::white()))
+///     .set_background(Color(0.1, 0.1, 0.2))
+///     .build()
+///     .unwrap();
+/// assert_eq!(world.objects.len(), 1);
+/// ```
+#[derive(Default)]
+pub struct WorldBuilder {
+    objects: Vec<Shape>,
+    lights: Vec<Light>,
+    background: Option<Color>,
+    invalid_camera: bool,
+}
+
+impl WorldBuilder {
+    /// Starts an empty scene.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an object to the scene.
+    pub fn add_shape(mut self, shape: Shape) -> Self {
+        self.objects.push(shape);
+        self
+    }
+
+    /// Adds a light to the scene.
+    pub fn add_light(mut self, light: Light) -> Self {
+        self.lights.push(light);
+        self
+    }
+
+    /// Sets the color seen by rays which escape the scene. See [World::with_background].
+    pub fn set_background(mut self, background: Color) -> Self {
+        self.background = Some(background);
+        self
+    }
+
+    /// Has [build](WorldBuilder::build) also check the camera the world will be rendered with.
+    /// The camera isn't part of the world, but a NaN field of view or transform would otherwise
+    /// only show up as a black image at the end of the render.
+    pub fn check_camera(mut self, camera: &Camera) -> Self {
+        self.invalid_camera |= !camera.has_finite_parameters();
+        self
+    }
+
+    /// Checks the scene and builds the world. Fails if there are no lights, if any object's
+    /// transform or any light's position or intensity isn't finite, or if a
+    /// [checked camera](WorldBuilder::check_camera) has parameters which aren't finite.
+    pub fn build(self) -> Result<World, SceneError> {
+        if self.lights.is_empty() {
+            return Err(SceneError::NoLights);
+        }
+        if self.invalid_camera {
+            return Err(SceneError::InvalidCamera);
+        }
+
+        let finite_matrix = |m: Matrix<4>| (0..4).all(|i| m[i].iter().all(|x| x.is_finite()));
+        if let Some(i) = self
+            .objects
+            .iter()
+            .position(|o| !finite_matrix(o.transform()))
+        {
+            return Err(SceneError::InvalidTransform(i));
+        }
+
+        let finite_light = |l: &Light| {
+            let (p, c) = (l.position(), l.intensity());
+            [p.0, p.1, p.2, c.0, c.1, c.2].iter().all(|x| x.is_finite())
+        };
+        if let Some(i) = self.lights.iter().position(|l| !finite_light(l)) {
+            return Err(SceneError::InvalidLight(i));
+        }
+
+        let world = World::new(self.objects, self.lights);
+        Ok(match self.background {
+            Some(background) => world.with_background(background),
+            None => world,
+        })
+    }
+}
+
+impl fmt::Display for World {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.summary())
+    }
+}
+
+/// Formats a count followed by a noun, e.g. `1 sphere` or `2 spheres`.
+fn plural(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("{} {}", n, noun)
+    } else {
+        format!("{} {}s", n, noun)
+    }
+}
+
+/// Counts how often each name appears, keeping the order in which they were first seen, e.g.
+/// `2 spheres, 1 plane`.
+fn tally<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for name in names {
+        match counts.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((name, 1)),
+        }
+    }
+
+    counts
+        .iter()
+        .map(|&(name, count)| plural(count, name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 #[cfg(test)]
 mod world_tests {
+    use rand::{rngs::StdRng, SeedableRng};
     use std::f64::consts::FRAC_1_SQRT_2;
 
     use crate::{
-        core::{camera::Camera, material::Material, precompute::PrecomputedData, Intersection},
-        math::{Matrix, Point, Vec3},
-        shape::{Plane, Sphere},
+        core::{
+            camera::Camera, material::Material, precompute::PrecomputedData, Intersection, Pattern,
+        },
+        math::{Matrix, Point, Tuple, Vec3},
+        shape::{Plane, Portal, Sphere},
         visuals::Color,
     };
 
     use super::*;
 
+    #[test]
+    fn environment_maps_are_sampled_by_ray_direction() {
+        // each texel gets its own color: red encodes the column, green the row
+        let image =
+            image::RgbImage::from_fn(4, 2, |x, y| image::Rgb([x as u8 * 50, y as u8 * 50, 0]));
+        let texel = |x: f64, y: f64| Color(x * 50.0 / 255.0, y * 50.0 / 255.0, 0.0);
+        let w = World::new(vec![], vec![]).with_environment_map(image);
+        let color_toward = |d: Vec3| w.background_at(&Ray::new(Point(0.0, 0.0, 0.0), d));
+
+        // -z is the left edge of the panorama and +z its middle, both on the horizon
+        assert_eq!(color_toward(Vec3(0.0, 0.0, -1.0)), texel(0.0, 1.0));
+        assert_eq!(color_toward(Vec3(0.0, 0.0, 1.0)), texel(2.0, 1.0));
+        assert_eq!(color_toward(Vec3(0.0, 3.0, 0.0)), texel(2.0, 0.0));
+        assert_eq!(color_toward(Vec3(0.0, -1.0, 0.0)), texel(2.0, 1.0));
+    }
+
+    #[test]
+    fn building_a_valid_scene() {
+        let w = WorldBuilder::new()
+            .add_shape(Sphere::default().as_shape())
+            .add_shape(Plane::default().as_shape())
+            .add_light(Light::new_point_light(Point(0.0, 5.0, 0.0), Color::white()))
+            .set_background(Color(0.1, 0.2, 0.3))
+            .build()
+            .unwrap();
+
+        assert_eq!(w.objects.len(), 2);
+        assert_eq!(w.lights.len(), 1);
+        assert_eq!(w.background, Background::Solid(Color(0.1, 0.2, 0.3)));
+    }
+
+    #[test]
+    fn building_a_scene_catches_mistakes() {
+        let light = || Light::new_point_light(Point(0.0, 5.0, 0.0), Color::white());
+
+        let dark = WorldBuilder::new()
+            .add_shape(Sphere::default().as_shape())
+            .build();
+        assert_eq!(dark.err(), Some(SceneError::NoLights));
+        assert_eq!(
+            SceneError::NoLights.to_string(),
+            "The scene has no lights, so it would render completely black"
+        );
+
+        let nan = WorldBuilder::new()
+            .add_shape(Sphere::default().as_shape())
+            .add_shape(
+                Sphere::default()
+                    .with_transform(&Matrix::translation(f64::NAN, 0.0, 0.0))
+                    .as_shape(),
+            )
+            .add_light(light())
+            .build();
+        assert_eq!(nan.err(), Some(SceneError::InvalidTransform(1)));
+
+        let infinite = WorldBuilder::new()
+            .add_light(light())
+            .add_light(Light::new_point_light(
+                Point(0.0, f64::INFINITY, 0.0),
+                Color::white(),
+            ))
+            .build();
+        assert_eq!(infinite.err(), Some(SceneError::InvalidLight(1)));
+
+        let camera = WorldBuilder::new()
+            .add_light(light())
+            .check_camera(&Camera::new(10, 10, 1.0))
+            .check_camera(&Camera::new(10, 10, f64::NAN))
+            .build();
+        assert_eq!(camera.err(), Some(SceneError::InvalidCamera));
+    }
+
+    /// The `y = 0` plane, written the way code outside the crate would.
+    #[derive(Debug)]
+    struct Floor;
+
+    impl Intersectable for Floor {
+        fn intersect(&self, r: Ray) -> Option<IntersectionList> {
+            if r.direction.y().abs() < 1e-9 {
+                return None;
+            }
+            let t = -r.origin.y() / r.direction.y();
+
+            Some(IntersectionList {
+                data: vec![Intersection::new(t, Plane::default().as_shape())],
+            })
+        }
+
+        fn normal_at(&self, _world_pt: Point) -> Option<Vec3> {
+            Some(Vec3(0.0, 1.0, 0.0))
+        }
+
+        fn material(&self) -> Material {
+            Material::default().with_color(&Color(0.8, 0.2, 0.2))
+        }
+    }
+
+    #[test]
+    fn custom_shapes_render_like_builtin_ones() {
+        let light = || Light::new_point_light(Point(-2.0, 5.0, -3.0), Color::white());
+        let custom = World::new(
+            vec![Shape::Custom(std::sync::Arc::new(Floor))],
+            vec![light()],
+        );
+        let builtin = World::new(
+            vec![Plane::default().with_material(&Floor.material()).as_shape()],
+            vec![light()],
+        );
+        let camera = Camera::new(11, 11, 1.0).with_transform(&Matrix::view_transform(
+            Point(0.0, 3.0, -4.0),
+            Point(0.0, 0.0, 0.0),
+            Vec3(0.0, 1.0, 0.0),
+        ));
+
+        let image = camera.render(&custom, 0).unwrap();
+        assert_eq!(image, camera.render(&builtin, 0).unwrap());
+        assert_ne!(image.read_pixel(5, 5), Color::black());
+
+        // hits report the custom shape itself, not the placeholder it returned
+        let r = Ray::new(Point(0.0, 1.0, 0.0), Vec3(0.0, -1.0, 0.0));
+        let xs = custom.intersect_world(r).unwrap();
+        assert_eq!(xs[0].object, custom.objects[0]);
+        assert_eq!(
+            custom.summary().lines().next(),
+            Some("1 object (1 custom shape)")
+        );
+    }
+
+    #[test]
+    fn clipping_filter_reveals_object_interiors() {
+        let w = default_world().with_intersection_filter(|ix, ray| ray.position(ix.t).y() <= 0.0);
+
+        // looking down at the spheres, their top halves are clipped away, so the ray only hits the
+        // lower halves of the inner and then the outer sphere
+        let r = Ray::new(Point(0.0, 5.0, 0.0), Vec3(0.0, -1.0, 0.0));
+        let xs = w.intersect_world(r).unwrap();
+        let ts = xs.data.iter().map(|ix| ix.t).collect::<Vec<_>>();
+
+        assert_eq!(ts, vec![5.5, 6.0]);
+        assert!(xs.data.iter().all(|ix| r.position(ix.t).y() <= 0.0));
+
+        // an unfiltered world still sees all four intersections
+        assert_eq!(default_world().intersect_world(r).unwrap().data.len(), 4);
+    }
+
+    #[test]
+    fn clipped_geometry_casts_no_shadows() {
+        let light = || Light::new_point_light(Point(0.0, 10.0, 0.0), Color::white());
+        let occluder = Sphere::default().as_shape();
+        let p = Point(0.0, -10.0, 0.0);
+
+        let w = World::new(vec![occluder.clone()], vec![light()]);
+        assert!(w.is_shadowed(&p, &light()));
+
+        // the filter cuts away everything above y = -2, including the whole sphere
+        let clipped = World::new(vec![occluder], vec![light()])
+            .with_intersection_filter(|ix, ray| ray.position(ix.t).y() <= -2.0);
+        assert!(!clipped.is_shadowed(&p, &light()));
+        assert!(!clipped.any_hit(Ray::new(p, Vec3(0.0, 1.0, 0.0)), 20.0));
+    }
+
+    #[test]
+    fn summary_counts_objects_lights_and_bounds() {
+        let s1 = Sphere::default().as_shape();
+        let s2 = Sphere::default()
+            .with_transform(&Matrix::translation(2.0, 0.0, 0.0))
+            .as_shape();
+        let floor = Plane::default().as_shape();
+        let light = Light::new_point_light(Point(-10.0, 10.0, -10.0), Color::white());
+        let w = World::new(vec![s1, s2, floor], vec![light]);
+
+        assert_eq!(
+            w.summary(),
+            "3 objects (2 spheres, 1 plane)\n\
+             1 light (1 point)\n\
+             bounds: (-1.00, -1.00, -1.00) to (3.00, 1.00, 1.00), plus 1 unbounded object"
+        );
+        assert_eq!(w.to_string(), w.summary());
+        assert_eq!(
+            World::default().summary(),
+            "0 objects\n0 lights\nbounds: none"
+        );
+    }
+
+    #[test]
+    fn rays_through_portals_see_the_linked_location() {
+        let portal = Portal::new(Matrix::translation(10.0, 0.0, 0.0)).as_shape();
+        let sphere = Sphere::default()
+            .with_transform(&Matrix::translation(10.0, -3.0, 0.0))
+            .as_shape();
+        let light = Light::new_point_light(Point(10.0, 10.0, -10.0), Color::white());
+        let w = World::new(vec![portal, sphere], vec![light]);
+
+        let through_portal = Ray::new(Point(0.0, 5.0, 0.0), Vec3(0.0, -1.0, 0.0));
+        let direct = Ray::new(Point(10.0, 0.0, 0.0), Vec3(0.0, -1.0, 0.0));
+        let seen = w.color_at(through_portal, 5, &mut thread_rng());
+
+        assert_ne!(seen, Color::black());
+        assert_eq!(seen, w.color_at(direct, 4, &mut thread_rng()));
+    }
+
+    #[test]
+    fn reflection_bounces_follow_portals() {
+        let portal = Portal::new(Matrix::translation(10.0, 0.0, 0.0)).as_shape();
+        let mirror = Plane::default()
+            .with_material(&Material::default().with_reflective(1.0))
+            .with_transform(&Matrix::translation(0.0, -3.0, 0.0))
+            .as_shape();
+        let w = World::new(vec![portal, mirror], vec![]);
+
+        // through the portal, off the mirror and away; the portal itself isn't a bounce
+        let r = Ray::new(Point(0.0, 5.0, 0.0), Vec3(0.0, -1.0, 0.0));
+
+        assert_eq!(w.reflection_bounces(r, 5), 1);
+        assert_eq!(w.reflection_bounces(r, 1), 0);
+    }
+
+    #[test]
+    fn disk_lights_cast_softer_shadows_than_point_lights() {
+        let floor = Plane::default().as_shape();
+        let occluder = Sphere::default()
+            .with_transform(&Matrix::translation(0.0, 5.0, 0.0))
+            .as_shape();
+        let point = Light::new_point_light(Point(0.0, 10.0, 0.0), Color::white());
+        let disk = Light::new_disk_light(
+            Point(0.0, 10.0, 0.0),
+            Vec3(0.0, -1.0, 0.0),
+            5.0,
+            Color::white(),
+            64,
+        );
+
+        // a ray hitting the floor directly beneath the occluder, without passing near it
+        let r = Ray::new(Point(3.0, 1.0, 0.0), Vec3(-3.0, -1.0, 0.0).normalize());
+
+        let hard = World::new(vec![floor.clone(), occluder.clone()], vec![point]).color_at(
+            r,
+            0,
+            &mut thread_rng(),
+        );
+        let soft =
+            World::new(vec![floor.clone(), occluder], vec![disk]).color_at(r, 0, &mut thread_rng());
+        let unshadowed = World::new(
+            vec![floor],
+            vec![Light::new_point_light(
+                Point(0.0, 10.0, 0.0),
+                Color::white(),
+            )],
+        )
+        .color_at(r, 0, &mut thread_rng());
+
+        // the point light leaves only ambient light, while some of the disk peeks past the sphere
+        assert_eq!(hard, Color(0.1, 0.1, 0.1));
+        assert!(soft.0 > hard.0);
+        assert!(soft.0 < unshadowed.0);
+    }
+
     #[test]
     fn reflected_color_at_max_recursion_depth() {
         let mut w = default_world();
@@ -132,13 +952,34 @@ mod world_tests {
         let xs = IntersectionList::new(vec![ix.clone()]);
         let comps = PrecomputedData::new(&ix, &r, &xs);
 
-        let col = w.reflected_color(&comps, 0);
+        let col = w.reflected_color(&comps, 0, &mut thread_rng());
         assert_eq!(col, Color::black());
     }
 
+    #[test]
+    fn fog_blends_hits_toward_the_fog_color() {
+        let gray = Color(0.5, 0.5, 0.5);
+        let r = Ray::new(Point(0.0, 0.0, -5.0), Vec3(0.0, 0.0, 1.0));
+        let clear = default_world().color_at(r, 5, &mut thread_rng());
+
+        assert_eq!(
+            default_world()
+                .with_fog(gray, 0.0)
+                .color_at(r, 5, &mut thread_rng()),
+            clear
+        );
+
+        // the hit is 4 units away, so at this density it's all but hidden
+        let foggy = default_world()
+            .with_fog(gray, 5.0)
+            .color_at(r, 5, &mut thread_rng());
+        assert!((foggy - gray).0.abs() < 1e-6);
+        assert!((foggy - gray).1.abs() < 1e-6);
+        assert!((foggy - gray).2.abs() < 1e-6);
+    }
+
     #[test]
     fn color_at_with_mutually_reflective_surfaces() {
-        // this will blow the stack, since I'm not handling infinite recursion yet
         let lp = Plane::default()
             .with_material(&Material::default().with_reflective(1.0))
             .with_transform(&Matrix::translation(0.0, -1.0, 0.0))
@@ -152,8 +993,100 @@ mod world_tests {
         let w = World::new(vec![lp, up], vec![light]);
         let r = Ray::new(Point(0.0, 0.0, 0.0), Vec3(0.0, 1.0, 0.0));
 
-        // then w.color_at(r, d) terminates successfully
-        w.color_at(r, 5);
+        // then w.color_at(r, d, &mut thread_rng()) terminates successfully
+        w.color_at(r, 5, &mut thread_rng());
+    }
+
+    #[test]
+    fn reflection_depth_is_configured_on_the_world() {
+        let mirror = Material::default().with_reflective(1.0);
+        let parallel_mirrors = || {
+            let lp = Plane::default()
+                .with_material(&mirror)
+                .with_transform(&Matrix::translation(0.0, -1.0, 0.0))
+                .as_shape();
+            let up = Plane::default()
+                .with_material(&mirror)
+                .with_transform(&Matrix::translation(0.0, 1.0, 0.0))
+                .as_shape();
+            let light = Light::new_point_light(Point(0.0, 0.0, 0.0), Color::white());
+
+            World::new(vec![lp, up], vec![light])
+        };
+        let r = Ray::new(Point(0.0, 0.0, 0.0), Vec3(0.0, 1.0, 0.0));
+
+        assert_eq!(World::default().reflection_depth(), 5);
+
+        // a depth of 0 only shows the surface itself
+        let flat = parallel_mirrors().with_reflection_depth(0);
+        let surface = flat.color_at(r, flat.reflection_depth(), &mut thread_rng());
+        let no_mirrors = World::new(
+            vec![Plane::default()
+                .with_transform(&Matrix::translation(0.0, 1.0, 0.0))
+                .as_shape()],
+            vec![Light::new_point_light(Point(0.0, 0.0, 0.0), Color::white())],
+        );
+        assert_eq!(surface, no_mirrors.color_at(r, 0, &mut thread_rng()));
+
+        // each extra bounce adds another copy of the surface color, and the recursion still ends
+        let deep = parallel_mirrors().with_reflection_depth(100);
+        let c = deep.color_at(r, deep.reflection_depth(), &mut thread_rng());
+        assert!((c.0 - surface.0 * 101.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn russian_roulette_converges_to_the_fully_reflected_color() {
+        let material = Material::default().with_reflective(0.5);
+        let lp = Plane::default()
+            .with_material(&material)
+            .with_transform(&Matrix::translation(0.0, -1.0, 0.0))
+            .as_shape();
+        let up = Plane::default()
+            .with_material(&material)
+            .with_transform(&Matrix::translation(0.0, 1.0, 0.0))
+            .as_shape();
+        let light = Light::new_point_light(Point(0.0, 0.0, 0.0), Color::white());
+        let r = Ray::new(Point(0.0, 0.0, 0.0), Vec3(0.0, 1.0, 0.0));
+
+        let fixed = World::new(vec![lp.clone(), up.clone()], vec![light]);
+        let shallow = fixed.color_at(r, 1, &mut thread_rng());
+        let deep = fixed.color_at(r, 40, &mut thread_rng());
+
+        let light = Light::new_point_light(Point(0.0, 0.0, 0.0), Color::white());
+        let roulette = World::new(vec![lp, up], vec![light]).with_russian_roulette();
+        let rng = &mut StdRng::seed_from_u64(7);
+        let samples = 4_000;
+        let mean = (0..samples)
+            .map(|_| roulette.color_at(r, 1, rng))
+            .sum::<Color>()
+            * (1.0 / samples as f64);
+
+        assert!((deep.0 - shallow.0).abs() > 0.5);
+        assert!((mean.0 - deep.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn russian_roulette_terminates_between_fuzzy_mirrors() {
+        let material = Material::default()
+            .with_reflective(0.9)
+            .with_reflection_fuzz(0.3);
+        let lp = Plane::default()
+            .with_material(&material)
+            .with_transform(&Matrix::translation(0.0, -1.0, 0.0))
+            .as_shape();
+        let up = Plane::default()
+            .with_material(&material)
+            .with_transform(&Matrix::translation(0.0, 1.0, 0.0))
+            .as_shape();
+        let light = Light::new_point_light(Point(0.0, 0.0, 0.0), Color::white());
+        let w = World::new(vec![lp, up], vec![light]).with_russian_roulette();
+        let r = Ray::new(Point(0.0, 0.0, 0.0), Vec3(0.0, 1.0, 0.0));
+        let rng = &mut StdRng::seed_from_u64(7);
+
+        for _ in 0..100 {
+            let c = w.color_at(r, 1, rng);
+            assert!([c.0, c.1, c.2].iter().all(|x| x.is_finite()));
+        }
     }
 
     #[test]
@@ -173,7 +1106,7 @@ mod world_tests {
         let xs = IntersectionList::new(vec![ix.clone()]);
         let comps = PrecomputedData::new(&ix, &r, &xs);
 
-        let col = w.shade_hit(&comps, 5);
+        let col = w.shade_hit(&comps, 5, &mut thread_rng());
         let expected_color = Color(0.87677, 0.92436, 0.82918);
 
         assert!((col.0 - expected_color.0).abs() < 0.0001);
@@ -181,6 +1114,31 @@ mod world_tests {
         assert!((col.2 - expected_color.2).abs() < 0.0001);
     }
 
+    #[test]
+    fn transparency_does_not_dim_reflections_until_refraction_is_traced() {
+        let material = Material::default()
+            .with_ambient(0.0)
+            .with_diffuse(0.0)
+            .with_specular(0.0)
+            .with_reflective(0.8)
+            .with_transparency(0.8);
+        let p = Plane::default()
+            .with_material(&material)
+            .with_transform(&Matrix::translation(0.0, -1.0, 0.0))
+            .as_shape();
+        let w = World::new(vec![p], vec![]).with_background(Color::white());
+
+        let r = Ray::new(
+            Point(0.0, 0.0, -3.0),
+            Vec3(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        );
+        let col = w.color_at(r, 5, &mut thread_rng());
+
+        // over-specified weights are scaled down, but only the reflection is drawn so far
+        assert_eq!(material.energy_weights(), (0.5, 0.5));
+        assert!(col.approx_eq(&Color(0.8, 0.8, 0.8), 0.0001));
+    }
+
     #[test]
     fn reflected_color_of_reflective_material() {
         let mut w = default_world();
@@ -197,12 +1155,126 @@ mod world_tests {
         let ix = Intersection::new(2.0_f64.sqrt(), p);
         let xs = IntersectionList::new(vec![ix.clone()]);
         let comps = PrecomputedData::new(&ix, &r, &xs);
-        let col = w.reflected_color(&comps, 5);
-        let expected_color = Color(0.19032, 0.2379, 0.14274);
+        let col = w.reflected_color(&comps, 5, &mut thread_rng());
+        assert!(col.approx_eq(&Color(0.19032, 0.2379, 0.14274), 0.0001));
+    }
 
-        assert!((col.0 - expected_color.0).abs() < 0.0001);
-        assert!((col.1 - expected_color.1).abs() < 0.0001);
-        assert!((col.2 - expected_color.2).abs() < 0.0001);
+    #[test]
+    fn fuzzy_reflections_stay_near_the_sharp_reflection() {
+        let reflected_with = |material: Material| {
+            let mut w = default_world();
+            let p = Plane::default()
+                .with_material(&material)
+                .with_transform(&Matrix::translation(0.0, -1.0, 0.0))
+                .as_shape();
+            w.objects.push(p.clone());
+
+            let r = Ray::new(
+                Point(0.0, 0.0, -3.0),
+                Vec3(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+            );
+            let ix = Intersection::new(2.0_f64.sqrt(), p);
+            let xs = IntersectionList::new(vec![ix.clone()]);
+            let comps = PrecomputedData::new(&ix, &r, &xs);
+
+            w.reflected_color(&comps, 5, &mut thread_rng())
+        };
+        let sharp = reflected_with(Material::default().with_reflective(0.5));
+
+        let unfuzzed = reflected_with(
+            Material::default()
+                .with_reflective(0.5)
+                .with_reflection_fuzz(0.0),
+        );
+        assert_eq!(unfuzzed, sharp);
+
+        let glossy = reflected_with(
+            Material::default()
+                .with_reflective(0.5)
+                .with_reflection_fuzz(0.05),
+        );
+        for (g, s) in [
+            (glossy.0, sharp.0),
+            (glossy.1, sharp.1),
+            (glossy.2, sharp.2),
+        ] {
+            assert!(g.is_finite());
+            assert!((g - s).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn missed_rays_see_the_background() {
+        let sky = Color(0.2, 0.4, 0.8);
+        let w = default_world().with_background(sky);
+        let r = Ray::new(Point(0.0, 0.0, -5.0), Vec3(0.0, 1.0, 0.0));
+
+        assert_eq!(
+            default_world().color_at(r, 5, &mut thread_rng()),
+            Color::black()
+        );
+        assert_eq!(w.color_at(r, 5, &mut thread_rng()), sky);
+    }
+
+    #[test]
+    fn sky_gradient_blends_by_ray_direction() {
+        let (top, bottom) = (Color(0.5, 0.7, 1.0), Color::white());
+        let w = World::default().with_sky_gradient(top, bottom);
+        let origin = Point(0.0, 0.0, 0.0);
+
+        let up = Ray::new(origin, Vec3(0.0, 1.0, 0.0));
+        let down = Ray::new(origin, Vec3(0.0, -1.0, 0.0));
+        let horizontal = Ray::new(origin, Vec3(1.0, 0.0, 1.0).normalize());
+
+        assert_eq!(w.color_at(up, 5, &mut thread_rng()), top);
+        assert_eq!(w.color_at(down, 5, &mut thread_rng()), bottom);
+        assert_eq!(
+            w.color_at(horizontal, 5, &mut thread_rng()),
+            Color(0.75, 0.85, 1.0)
+        );
+    }
+
+    #[test]
+    fn reflected_rays_escaping_the_scene_see_the_background() {
+        let sky = Color(0.2, 0.4, 0.8);
+        let mirror = Plane::default()
+            .with_material(
+                &Material::default()
+                    .with_color(&Color::black())
+                    .with_ambient(0.0)
+                    .with_diffuse(0.0)
+                    .with_specular(0.0)
+                    .with_reflective(1.0),
+            )
+            .as_shape();
+        let w = World::new(vec![mirror], vec![]).with_background(sky);
+        let r = Ray::new(Point(0.0, 1.0, -1.0), Vec3(0.0, -1.0, 1.0).normalize());
+
+        assert_eq!(w.color_at(r, 5, &mut thread_rng()), sky);
+    }
+
+    #[test]
+    fn emissive_surfaces_glow_without_lighting() {
+        let glowing = Material::default()
+            .with_ambient(0.0)
+            .with_diffuse(0.0)
+            .with_specular(0.0)
+            .with_emission(&Color(0.5, 0.5, 0.5));
+        let s = Sphere::default().with_material(&glowing).as_shape();
+        let r = Ray::new(Point(0.0, 0.0, -5.0), Vec3(0.0, 0.0, 1.0));
+
+        // whether lit, unlit, or in total darkness, the sphere shows its emission exactly once
+        let lit = World::new(
+            vec![s.clone()],
+            vec![
+                Light::new_point_light(Point(-10.0, 10.0, -10.0), Color::white()),
+                Light::new_point_light(Point(10.0, 10.0, -10.0), Color::white()),
+            ],
+        );
+        let dark = World::new(vec![s], vec![]);
+
+        assert_eq!(lit.color_at(r, 5, &mut thread_rng()), Color(0.5, 0.5, 0.5));
+        assert_eq!(dark.color_at(r, 5, &mut thread_rng()), Color(0.5, 0.5, 0.5));
     }
 
     #[test]
@@ -210,7 +1282,7 @@ mod world_tests {
         let light = Light::new_point_light(Point(-10.0, 10.0, -10.0), Color::white());
         let s1 = Sphere {
             material: Material {
-                color: Color(0.8, 1.0, 0.6),
+                pattern: Pattern::Solid(Color(0.8, 1.0, 0.6)),
                 diffuse: 0.7,
                 specular: 0.2,
                 ..Default::default()
@@ -221,16 +1293,13 @@ mod world_tests {
             .with_transform(&Matrix::scaling(0.5, 0.5, 0.5))
             .with_material(&Material::default().with_ambient(1.0));
 
-        let w = World {
-            objects: vec![s1.as_shape(), s2.as_shape()],
-            lights: vec![light],
-        };
+        let w = World::new(vec![s1.as_shape(), s2.as_shape()], vec![light]);
 
         let r = Ray::new(Point(0.0, 0.0, 0.0), Vec3(0.0, 0.0, 1.0));
         let ix = Intersection::new(1.0, s2.as_shape());
         let xs = IntersectionList::new(vec![ix.clone()]);
         let comps = PrecomputedData::new(&ix, &r, &xs);
-        let color = w.reflected_color(&comps, 5);
+        let color = w.reflected_color(&comps, 5, &mut thread_rng());
 
         assert_eq!(color, Color::black());
     }
@@ -268,7 +1337,89 @@ mod world_tests {
     }
 
     #[test]
-    #[ignore = "doesn't work now that shadows are rendered"]
+    fn any_hit_agrees_with_nearest_hit() {
+        let mut w = default_world();
+        w.objects.push(
+            Plane::default()
+                .with_transform(&Matrix::translation(0.0, -1.0, 0.0))
+                .as_shape(),
+        );
+        let light = w.lights[0].position();
+
+        let points = [
+            Point(-2.0, 2.0, -2.0),
+            Point(-20.0, 20.0, -20.0),
+            Point(10.0, -10.0, 10.0),
+            Point(0.0, 10.0, 0.0),
+            Point(0.0, -2.0, 0.0),
+            Point(0.5, 0.0, 0.0),
+            Point(3.0, -0.5, 3.0),
+        ];
+        for p in points {
+            let v = light - p;
+            let r = Ray::new(p, v.normalize());
+            let expected = w
+                .intersect_world(r)
+                .and_then(|xs| xs.hit().map(|hit| hit.t < v.magnitude()))
+                .unwrap_or(false);
+
+            assert_eq!(w.any_hit(r, v.magnitude()), expected, "point {:?}", p);
+            assert_eq!(w.is_shadowed(&p, &w.lights[0]), expected, "point {:?}", p);
+        }
+    }
+
+    #[test]
+    fn objects_can_opt_out_of_casting_shadows() {
+        let light = Light::new_point_light(Point(0.0, 10.0, 0.0), Color::white());
+        let glass = Sphere::default()
+            .with_material(&Material::default().with_casts_shadow(false))
+            .as_shape();
+        let w = World::new(vec![glass], vec![light]);
+        let p = Point(0.0, -10.0, 0.0);
+
+        assert!(!w.any_hit(Ray::new(p, Vec3(0.0, 1.0, 0.0)), 20.0));
+        assert!(!w.is_shadowed(&p, &w.lights[0]));
+    }
+
+    #[test]
+    fn finding_objects_by_name() {
+        let w = World::new(
+            vec![
+                Sphere::default().as_shape(),
+                Sphere::default().with_name("left").as_shape(),
+                Plane::default().with_name("floor").as_shape(),
+            ],
+            vec![],
+        );
+
+        assert_eq!(w.object_by_name("floor"), Some(&w.objects[2]));
+        assert_eq!(
+            w.object_by_name("left").and_then(|o| o.name()),
+            Some("left")
+        );
+        assert_eq!(w.object_by_name("right"), None);
+    }
+
+    #[test]
+    fn any_hit_ignores_hits_past_max_t() {
+        let w = default_world();
+        let r = Ray::new(Point(0.0, 0.0, -5.0), Vec3(0.0, 0.0, 1.0));
+
+        assert!(w.any_hit(r, 5.0));
+        assert!(!w.any_hit(r, 3.0));
+    }
+
+    #[test]
+    fn portals_do_not_block_any_hit() {
+        let portal = Portal::new(Matrix::identity()).as_shape();
+        let w = World::new(vec![portal], vec![]);
+        let r = Ray::new(Point(0.0, 5.0, 0.0), Vec3(0.0, -1.0, 0.0));
+
+        assert!(w.objects[0].intersects_before(r, 10.0));
+        assert!(!w.any_hit(r, 10.0));
+    }
+
+    #[test]
     fn rendering_world_with_camera() {
         let w = default_world();
         let from = Point(0.0, 0.0, -5.0);
@@ -279,23 +1430,23 @@ mod world_tests {
             .with_transform(&Matrix::view_transform(from, to, up));
         let image = c.render(&w, 0).unwrap();
 
-        // weirdly inaccurate
-        assert!((image.read_pixel(5, 5).0 - Color(0.38066, 0.47583, 0.2855).0).abs() < 1e-3);
-        assert!((image.read_pixel(5, 5).1 - Color(0.38066, 0.47583, 0.2855).1).abs() < 1e-2);
-        assert!((image.read_pixel(5, 5).2 - Color(0.38066, 0.47583, 0.2855).2).abs() < 1e-3);
+        assert!(image
+            .read_pixel(5, 5)
+            .approx_eq(&Color(0.38066, 0.47583, 0.2855), 1e-4));
     }
 
     #[test]
     fn color_with_intersection_behind_ray() {
         let s1 = Shape::Sphere(Sphere {
             material: Material {
-                color: Color(0.8, 1.0, 0.6),
+                pattern: Pattern::Solid(Color(0.8, 1.0, 0.6)),
                 diffuse: 0.7,
                 specular: 0.2,
                 ambient: 1.0,
                 ..Default::default()
             },
             transform: Default::default(),
+            name: None,
         });
         let s2 = Shape::Sphere(Sphere {
             material: Material {
@@ -303,6 +1454,7 @@ mod world_tests {
                 ..Default::default()
             },
             transform: Matrix::scaling(0.5, 0.5, 0.5),
+            name: None,
         });
         let w = World {
             objects: vec![s1, s2],
@@ -312,16 +1464,16 @@ mod world_tests {
         let inner = w.objects[1].clone();
 
         let r = Ray::new(Point(0.0, 0.0, 0.75), Vec3(0.0, 0.0, -1.0));
-        let c = w.color_at(r, 5);
+        let c = w.color_at(r, 5, &mut thread_rng());
 
-        assert_eq!(c, inner.material().color);
+        assert_eq!(c, inner.material().color().unwrap());
     }
 
     #[test]
     fn color_when_ray_hits() {
         let w = default_world();
         let r = Ray::new(Point(0.0, 0.0, -5.0), Vec3(0.0, 0.0, 1.0));
-        let c = w.color_at(r, 5);
+        let c = w.color_at(r, 5, &mut thread_rng());
         let expected_color = Color(0.38066, 0.47583, 0.2855);
 
         assert!((c.0 - expected_color.0).abs() < 1e-4);
@@ -333,7 +1485,7 @@ mod world_tests {
     fn color_when_ray_misses() {
         let w = default_world();
         let r = Ray::new(Point(0.0, 0.0, -5.0), Vec3(0.0, 1.0, 0.0));
-        let c = w.color_at(r, 5);
+        let c = w.color_at(r, 5, &mut thread_rng());
 
         assert_eq!(c, Color::black());
     }
@@ -350,7 +1502,7 @@ mod world_tests {
         let ix = Intersection::new(0.5, shape);
         let xs = IntersectionList::new(vec![ix.clone()]);
         let comps = PrecomputedData::new(&ix, &r, &xs);
-        let c = w.shade_hit(&comps, 5);
+        let c = w.shade_hit(&comps, 5, &mut thread_rng());
         let expected_color = Color(0.90498, 0.90498, 0.90498);
 
         assert!((c.0 - expected_color.0).abs() < 1e-4);
@@ -366,7 +1518,7 @@ mod world_tests {
         let ix = Intersection::new(4.0, shape);
         let xs = IntersectionList::new(vec![ix.clone()]);
         let comps = PrecomputedData::new(&ix, &r, &xs);
-        let c = w.shade_hit(&comps, 5);
+        let c = w.shade_hit(&comps, 5, &mut thread_rng());
         let expected_color = Color(0.38066, 0.47583, 0.2855);
 
         assert!((c.0 - expected_color.0).abs() < 1e-4);
@@ -387,6 +1539,50 @@ mod world_tests {
         assert_eq!(xs[3].t, 6.0);
     }
 
+    #[test]
+    fn picking_returns_the_nearest_object() {
+        let near = Sphere::default().with_name("near").as_shape();
+        let far = Sphere::default()
+            .with_name("far")
+            .with_transform(&Matrix::translation(0.0, 0.0, 5.0))
+            .as_shape();
+        let w = World::new(vec![far, near.clone()], vec![]);
+
+        let r = Ray::new(Point(0.0, 0.0, -5.0), Vec3(0.0, 0.0, 1.0));
+        let xs = w.cast_ray(r);
+        let ts: Vec<f64> = xs.data.iter().map(|ix| ix.t).collect();
+
+        assert_eq!(ts, vec![4.0, 6.0, 9.0, 11.0]);
+        assert_eq!(w.pick(r), Some(&near));
+        assert_eq!(
+            w.pick(Ray::new(Point(0.0, 5.0, -5.0), Vec3(0.0, 0.0, 1.0))),
+            None
+        );
+    }
+
+    #[test]
+    fn identical_objects_are_picked_by_their_place_in_the_world() {
+        let back = Sphere::default()
+            .with_transform(&Matrix::translation(0.0, 0.0, 5.0))
+            .as_shape();
+        let w = World::new(
+            vec![
+                Sphere::default().as_shape(),
+                back.clone(),
+                Sphere::default().as_shape(),
+                back,
+            ],
+            vec![],
+        );
+        assert_eq!(w.objects[0], w.objects[2]);
+
+        let front = w.pick(Ray::new(Point(0.0, 0.0, -5.0), Vec3(0.0, 0.0, 1.0)));
+        let rear = w.pick(Ray::new(Point(0.0, 0.0, 10.0), Vec3(0.0, 0.0, -1.0)));
+
+        assert!(std::ptr::eq(front.unwrap(), &w.objects[0]));
+        assert!(std::ptr::eq(rear.unwrap(), &w.objects[1]));
+    }
+
     #[test]
     fn worlds_have_a_default() {
         let w = default_world();
@@ -401,7 +1597,7 @@ mod world_tests {
         let light = Light::new_point_light(Point(-10.0, 10.0, -10.0), Color::white());
         let s1 = Sphere {
             material: Material {
-                color: Color(0.8, 1.0, 0.6),
+                pattern: Pattern::Solid(Color(0.8, 1.0, 0.6)),
                 diffuse: 0.7,
                 specular: 0.2,
                 ..Default::default()
@@ -413,9 +1609,6 @@ mod world_tests {
             .with_transform(&Matrix::scaling(0.5, 0.5, 0.5))
             .as_shape();
 
-        World {
-            objects: vec![s1, s2],
-            lights: vec![light],
-        }
+        World::new(vec![s1, s2], vec![light])
     }
 }
@@ -1,6 +1,8 @@
 //! General I/O interface. Mainly for parsing YAML files into render-able worlds.
 //!
-//! This module provides a means for specifying a world through easy-to-read text files in YAML
-//! format.
+//! This module provides a means for specifying a world through easy-to-read text files in YAML (or
+//! JSON) format.
 pub mod error;
+pub mod gif;
+pub mod json;
 pub mod yaml;
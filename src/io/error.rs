@@ -1,47 +1,196 @@
-/// A result obtained from parsing YAML files. An `Ok(_)` variant contains an
+use crate::visuals::Canvas;
+
+/// A result obtained from parsing YAML or JSON scene files. An `Ok(_)` variant contains an
 /// [`Option<Camera>`](crate::core::Camera) and a [World](crate::core::World). An `Err(_)` variant
-/// contains a [YamlError](crate::io::error::YamlError).
-pub type ParseResult<C, W> = Result<(Option<C>, W), YamlError>;
+/// contains a [SceneParseError](crate::io::error::SceneParseError).
+pub type ParseResult<C, W> = Result<(Option<C>, W), SceneParseError>;
 
 // pub type RtcResult = Result<(), RtcError>;
 pub type RtcResult<T> = anyhow::Result<T>;
 
-/// Possible errors encountered when attempting to construct world data from YAML files.
+/// Possible errors encountered when attempting to construct world data from a scene file. JSON
+/// scenes share the YAML schema (see [json](crate::io::json)), so both formats report the same
+/// errors.
 #[derive(thiserror::Error, Debug)]
-pub enum YamlError {
+pub enum SceneParseError {
     /// Standard library IO error.
     IO(std::io::Error),
 
     /// A scanning error reported by [yaml_rust](yaml_rust::ScanError).
     Scan(yaml_rust::ScanError),
+
+    /// A syntax error in a [JSON](crate::io::json) scene, reported by
+    /// [serde_json](serde_json::Error).
+    Json(serde_json::Error),
+
+    /// A sequence under `key` (e.g. a point or a color) had the wrong number of components.
+    WrongArity {
+        key: String,
+        expected: usize,
+        found: usize,
+    },
+
+    /// A name was referenced (e.g. `material: name`) without a matching `- define: name` block.
+    Undefined(String),
+
+    /// A `- include: path` item led back to a file that was already being included.
+    IncludeCycle(std::path::PathBuf),
+
+    /// The input held no document at all (e.g. an empty string).
+    Empty,
+
+    /// Part of the scene didn't have the expected structure, e.g. the document isn't a list of
+    /// items. The message says what was expected.
+    Malformed(String),
+
+    /// An `- add: item` named an item type that isn't supported.
+    UnknownItem(String),
+
+    /// `key` was set to a value it doesn't accept, e.g. `specular_model: gouraud`.
+    InvalidValue { key: String, value: String },
+    /// The image for an image-texture pattern couldn't be loaded.
+    Texture {
+        path: std::path::PathBuf,
+        source: image::ImageError,
+    },
 }
 
+/// The old name of [SceneParseError], from before scenes could also be written in JSON.
+#[deprecated(note = "renamed to `SceneParseError`")]
+pub type YamlError = SceneParseError;
+
 #[derive(thiserror::Error, Debug)]
 pub enum RtcError {
     #[error("Invalid YAML file")]
-    InvalidYaml(YamlError),
+    InvalidYaml(SceneParseError),
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum RenderError {
     #[error("Could not render the specified scene")]
     SceneError(String),
+
+    /// The render ran to completion, but part of the scene could not be drawn (e.g. a shape with
+    /// a singular transform). Whatever could be rendered is kept in `canvas`.
+    #[error("Render incomplete: {reason}")]
+    Incomplete { reason: String, canvas: Canvas },
+
+    /// A region passed to [Camera::render_region](crate::core::Camera::render_region) was empty
+    /// or didn't fit within the image.
+    #[error("Region {from:?}..{to:?} does not fit within the {size:?} image")]
+    InvalidRegion {
+        from: (usize, usize),
+        to: (usize, usize),
+        size: (usize, usize),
+    },
 }
 
-impl From<std::io::Error> for YamlError {
+/// Errors encountered when reading a plain-text (`P3`) PPM file into a
+/// [Canvas](crate::visuals::Canvas).
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum PpmError {
+    #[error("Invalid PPM magic number {0:?}, expected \"P3\"")]
+    InvalidMagic(String),
+
+    #[error("Invalid or missing PPM header field `{0}`")]
+    InvalidHeader(&'static str),
+
+    #[error("Invalid PPM color value {0:?}")]
+    InvalidValue(String),
+
+    #[error("Expected {expected} PPM color values, found {found}")]
+    MissingValues { expected: usize, found: usize },
+}
+
+/// Errors from [Canvas](crate::visuals::Canvas) operations given sizes that don't fit the canvas.
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CanvasError {
+    /// The canvas can't be split evenly into `factor`x`factor` blocks (or `factor` is 0).
+    #[error("A {width}x{height} canvas can't be split into {factor}x{factor} blocks")]
+    NotDivisible {
+        width: u32,
+        height: u32,
+        factor: u32,
+    },
+
+    /// Two canvases which need to be the same size (e.g. for blending) aren't.
+    #[error("Expected a {expected:?} canvas, found {found:?}")]
+    SizeMismatch {
+        expected: (u32, u32),
+        found: (u32, u32),
+    },
+
+    /// A region (e.g. for cropping) that doesn't fit within the canvas.
+    #[error("A {width}x{height} region at {origin:?} doesn't fit within the {size:?} canvas")]
+    OutOfBounds {
+        origin: (u32, u32),
+        width: u32,
+        height: u32,
+        size: (u32, u32),
+    },
+}
+
+/// Mistakes in a scene caught by [WorldBuilder::build](crate::core::world::WorldBuilder::build)
+/// before anything is rendered.
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum SceneError {
+    #[error("The scene has no lights, so it would render completely black")]
+    NoLights,
+
+    #[error("Object {0} has a transform containing NaN or infinite values")]
+    InvalidTransform(usize),
+
+    #[error("Light {0} has a position or intensity containing NaN or infinite values")]
+    InvalidLight(usize),
+
+    #[error("The camera has a field of view, transform, or lens setting that isn't finite")]
+    InvalidCamera,
+}
+
+impl From<std::io::Error> for SceneParseError {
     fn from(e: std::io::Error) -> Self {
-        YamlError::IO(e)
+        SceneParseError::IO(e)
     }
 }
 
-impl From<yaml_rust::ScanError> for YamlError {
+impl From<yaml_rust::ScanError> for SceneParseError {
     fn from(e: yaml_rust::ScanError) -> Self {
-        YamlError::Scan(e)
+        SceneParseError::Scan(e)
+    }
+}
+
+impl From<serde_json::Error> for SceneParseError {
+    fn from(e: serde_json::Error) -> Self {
+        SceneParseError::Json(e)
     }
 }
 
-impl std::fmt::Display for YamlError {
+impl std::fmt::Display for SceneParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+        match self {
+            Self::IO(e) => write!(f, "{}", e),
+            Self::Scan(e) => write!(f, "{}", e),
+            Self::Json(e) => write!(f, "{}", e),
+            Self::WrongArity {
+                key,
+                expected,
+                found,
+            } => write!(
+                f,
+                "`{}` should have {} components, but {} were given",
+                key, expected, found
+            ),
+            Self::Undefined(name) => write!(f, "`{}` was never defined", name),
+            Self::IncludeCycle(path) => write!(f, "`{}` includes itself", path.display()),
+            Self::Empty => write!(f, "the scene is empty"),
+            Self::Malformed(msg) => write!(f, "{}", msg),
+            Self::UnknownItem(item) => write!(f, "`{}` is not a known item type", item),
+            Self::InvalidValue { key, value } => {
+                write!(f, "`{}` is not a valid value for `{}`", value, key)
+            }
+            Self::Texture { path, source } => {
+                write!(f, "could not load texture `{}`: {}", path.display(), source)
+            }
+        }
     }
 }
@@ -0,0 +1,99 @@
+//! Animated GIF export, e.g. for frames from
+//! [render_sequence](crate::core::animation::render_sequence).
+use crate::visuals::Canvas;
+
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    error::{ImageError, ParameterError, ParameterErrorKind},
+    Delay, DynamicImage, Frame, ImageResult,
+};
+use std::{fs::File, io::Write};
+
+/// Writes the frames to `path` as a looping animated GIF, showing each frame for `delay_ms`
+/// milliseconds. Every frame must be the same size.
+pub fn export_gif(frames: &[Canvas], path: &str, delay_ms: u16) -> ImageResult<()> {
+    encode_gif(frames, File::create(path)?, delay_ms)
+}
+
+/// Like [export_gif], but writes the GIF to any writer (e.g. an in-memory buffer).
+pub fn encode_gif<W: Write>(frames: &[Canvas], writer: W, delay_ms: u16) -> ImageResult<()> {
+    let first = frames.first().ok_or_else(|| {
+        ImageError::Parameter(ParameterError::from_kind(ParameterErrorKind::Generic(
+            "an animation needs at least one frame".to_string(),
+        )))
+    })?;
+    if frames
+        .iter()
+        .any(|f| (f.width, f.height) != (first.width, first.height))
+    {
+        return Err(ImageError::Parameter(ParameterError::from_kind(
+            ParameterErrorKind::DimensionMismatch,
+        )));
+    }
+
+    let delay = Delay::from_numer_denom_ms(delay_ms as u32, 1);
+    let mut encoder = GifEncoder::new(writer);
+    encoder.set_repeat(Repeat::Infinite)?;
+    encoder.encode_frames(frames.iter().map(|canvas| {
+        let img = DynamicImage::ImageRgb8(canvas.to_rgb_image()).into_rgba8();
+        Frame::from_parts(img, 0, 0, delay)
+    }))
+}
+
+#[cfg(test)]
+mod gif_tests {
+    use super::*;
+    use crate::visuals::Color;
+    use image::{codecs::gif::GifDecoder, AnimationDecoder};
+
+    #[test]
+    fn encoding_two_frames() {
+        let frames = [
+            Canvas::filled(4, 3, Color::red()),
+            Canvas::filled(4, 3, Color::blue()),
+        ];
+        let mut buf = Vec::new();
+        encode_gif(&frames, &mut buf, 100).unwrap();
+
+        let decoded = GifDecoder::new(buf.as_slice())
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].buffer().dimensions(), (4, 3));
+        assert_eq!(decoded[0].delay().numer_denom_ms(), (100, 1));
+        assert_eq!(decoded[0].buffer().get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(decoded[1].buffer().get_pixel(0, 0).0, [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn frames_of_different_sizes_are_an_error() {
+        let frames = [Canvas::new(4, 3), Canvas::new(3, 4)];
+
+        assert!(matches!(
+            encode_gif(&frames, Vec::new(), 100),
+            Err(ImageError::Parameter(_))
+        ));
+    }
+
+    #[test]
+    fn no_frames_is_an_error() {
+        assert!(encode_gif(&[], Vec::new(), 100).is_err());
+    }
+
+    #[test]
+    #[ignore = "I don't want to save a file every time I run this test."]
+    fn exporting_a_gif() {
+        let frames = [Canvas::new(4, 3), Canvas::filled(4, 3, Color::white())];
+        export_gif(&frames, "/tmp/test.gif", 250).unwrap();
+
+        let decoded = GifDecoder::new(File::open("/tmp/test.gif").unwrap())
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        assert_eq!(decoded.len(), 2);
+    }
+}
@@ -0,0 +1,138 @@
+//! Parsing scenes written in JSON.
+//!
+//! JSON scenes have exactly the same structure as [YAML](crate::io::yaml) scenes: a list of
+//! `{"add": ...}` and `{"define": ...}` items with the same keys. The JSON is converted to a YAML
+//! document and built by the same code, so the two formats always support the same features.
+use serde_json::Value;
+use yaml_rust::{yaml, Yaml};
+
+use crate::core::{Camera, World};
+
+use super::{error::ParseResult, yaml::build_scene};
+
+/// Attempts to parse the specified JSON file. Can fail when reading the file to string or when the
+/// file isn't valid JSON.
+///
+/// # Example
+/// ```ignore
+/// let (camera, world) = parse_json("world.json").unwrap();
+///
+/// let canvas = camera.unwrap().render_default_depth(&world).unwrap();
+/// canvas.export("rendered_image.png").unwrap();
+/// ```
+pub fn parse_json<P>(path: P) -> ParseResult<Camera, World>
+where
+    P: AsRef<std::path::Path>,
+{
+    let json = std::fs::read_to_string(path)?;
+
+    parse_json_str(&json)
+}
+
+/// Like [parse_json], but parses a scene that's already in memory.
+pub fn parse_json_str(json: &str) -> ParseResult<Camera, World> {
+    let value: Value = serde_json::from_str(json)?;
+
+    build_scene(&to_yaml(value))
+}
+
+/// Converts a JSON value to the equivalent YAML value. Whole numbers become integers, like they
+/// would when scanning YAML, so keys that expect integers (e.g. `hsize`) work the same way.
+fn to_yaml(value: Value) -> Yaml {
+    match value {
+        Value::Null => Yaml::Null,
+        Value::Bool(b) => Yaml::Boolean(b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => Yaml::Integer(i),
+            None => Yaml::Real(n.to_string()),
+        },
+        Value::String(s) => Yaml::String(s),
+        Value::Array(values) => Yaml::Array(values.into_iter().map(to_yaml).collect()),
+        Value::Object(map) => Yaml::Hash(
+            map.into_iter()
+                .map(|(k, v)| (Yaml::String(k), to_yaml(v)))
+                .collect::<yaml::Hash>(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod json_tests {
+    use super::*;
+    use crate::io::{error::SceneParseError, yaml::parse_yaml_str};
+
+    #[test]
+    fn json_and_yaml_scenes_build_the_same_world() -> Result<(), SceneParseError> {
+        let yaml = r##"
+---
+- add: camera
+  hsize: 40
+  vsize: 30
+  fov: 1.0471975512
+  from: [0.0, 1.5, -5.0]
+  to: [0.0, 1.0, 0.0]
+  up: [0.0, 1.0, 0.0]
+  aa: 2
+- add: world
+  background: "#102030"
+- add: light
+  type: point
+  at: [-10.0, 10.0, -10.0]
+  intensity: [1.0, 1.0, 1.0]
+- define: shiny
+  value:
+    color: [0.1, 1.0, 0.5]
+    reflective: 0.3
+- add: plane
+  material:
+    pattern:
+      type: stripes
+      colors:
+        - [1.0, 1.0, 1.0]
+        - [0.0, 0.0, 0.0]
+- add: sphere
+  transform:
+    - [translate, -0.5, 1.0, 0.5]
+    - [scale, 0.5, 0.5, 0.5]
+  material: shiny
+"##;
+        let json = r##"
+[
+  { "add": "camera", "hsize": 40, "vsize": 30, "fov": 1.0471975512,
+    "from": [0.0, 1.5, -5.0], "to": [0.0, 1.0, 0.0], "up": [0.0, 1.0, 0.0], "aa": 2 },
+  { "add": "world", "background": "#102030" },
+  { "add": "light", "type": "point", "at": [-10.0, 10.0, -10.0],
+    "intensity": [1.0, 1.0, 1.0] },
+  { "define": "shiny", "value": { "color": [0.1, 1.0, 0.5], "reflective": 0.3 } },
+  { "add": "plane", "material": { "pattern": { "type": "stripes",
+    "colors": [[1.0, 1.0, 1.0], [0.0, 0.0, 0.0]] } } },
+  { "add": "sphere",
+    "transform": [["translate", -0.5, 1.0, 0.5], ["scale", 0.5, 0.5, 0.5]],
+    "material": "shiny" }
+]
+"##;
+        let (yaml_cam, yaml_world) = parse_yaml_str(yaml)?;
+        let (json_cam, json_world) = parse_json_str(json)?;
+        let (yaml_cam, json_cam) = (yaml_cam.unwrap(), json_cam.unwrap());
+
+        assert_eq!(json_world.objects.len(), 2);
+        assert_eq!(json_world.objects, yaml_world.objects);
+        assert_eq!(json_world.lights, yaml_world.lights);
+        assert_eq!(json_world.background, yaml_world.background);
+        assert_eq!(
+            (json_cam.hsize(), json_cam.vsize(), json_cam.fov()),
+            (yaml_cam.hsize(), yaml_cam.vsize(), yaml_cam.fov())
+        );
+        assert_eq!(json_cam.transform(), yaml_cam.transform());
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_json_is_an_error() {
+        assert!(matches!(
+            parse_json_str(r#"[{ "add": "sphere" "#),
+            Err(SceneParseError::Json(_))
+        ));
+    }
+}
@@ -1,256 +1,689 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
 use yaml_rust::{yaml, Yaml, YamlLoader};
 
 use crate::{
     core::{
-        antialias::{AAMethod, AntiAliasing, Multisampling, Stochastic},
+        antialias::{
+            AAMethod, Adaptive, AntiAliasing, GridSupersampling, Multisampling, Stochastic,
+        },
         camera::Camera,
         light::Light,
-        material::Material,
-        pattern::Pattern,
+        material::{Material, SpecularModel},
+        materials,
+        pattern::{Pattern, Sampling, UvMapping, UvPattern},
         world::World,
     },
     math::{Axis, Matrix, Point, Vec3},
-    shape::{Plane, Shape, Sphere},
+    shape::{Plane, Quad, Shape, Sphere},
     visuals::Color,
 };
 
-use super::error::ParseResult;
+use super::error::{ParseResult, SceneParseError};
+
+mod writer;
+
+pub use writer::to_yaml_string;
 
 /// Attempts to parse the specified YAML file. Scans the file for items of the form `- add: item`.
 /// Can fail when reading the file to string or when scanning the file with
 /// [YamlLoader](yaml_rust::YamlLoader::load_from_str).
 ///
+/// Items of the form `- include: other.yml` are replaced by the items of that file, resolved
+/// relative to the including file. Included files can hold shapes, lights and defines, but any
+/// camera they add is ignored; a file that ends up including itself is an
+/// [IncludeCycle](SceneParseError::IncludeCycle).
+///
 /// # Example
 /// ```ignore
 /// let (camera, world) = parse_yaml("world.yml").unwrap();
 ///
-/// let canvas = camera.unwrap().render(&world).unwrap();
+/// let canvas = camera.unwrap().render_default_depth(&world).unwrap();
 /// canvas.export("rendered_image.png").unwrap();
 /// ```
 pub fn parse_yaml<P>(path: P) -> ParseResult<Camera, World>
 where
     P: AsRef<std::path::Path>,
 {
-    let yaml = std::fs::read_to_string(path)?;
-    let docs = YamlLoader::load_from_str(&yaml)?;
-    let doc = &docs[0];
+    let mut visited = HashSet::new();
+    let items = load_items(path.as_ref(), &mut visited)?;
+
+    build_scene(&Yaml::Array(items))
+}
+
+/// Like [parse_yaml], but parses a scene that's already in memory, e.g. one built by a GUI or
+/// received over the network. Any `- include:` paths are relative to the working directory.
+///
+/// # Example
+/// ```
+/// use rtc::io::yaml::parse_yaml_str;
+///
+/// let (camera, world) = parse_yaml_str("- add: sphere").unwrap();
+///
+/// assert!(camera.is_none());
+/// assert_eq!(world.objects.len(), 1);
+/// ```
+pub fn parse_yaml_str(yaml: &str) -> ParseResult<Camera, World> {
+    let docs = YamlLoader::load_from_str(yaml)?;
+    let doc = docs.first().ok_or(SceneParseError::Empty)?;
+    let items = expand_includes(doc, Path::new("."), &mut HashSet::new())?;
+
+    build_scene(&Yaml::Array(items))
+}
 
+/// Reads the scene file at `path` and returns its items, with includes expanded. `visited` holds
+/// the files currently being included, to catch cycles.
+fn load_items(path: &Path, visited: &mut HashSet<PathBuf>) -> Result<Vec<Yaml>, SceneParseError> {
+    let canonical = path.canonicalize()?;
+    if !visited.insert(canonical.clone()) {
+        return Err(SceneParseError::IncludeCycle(canonical));
+    }
+
+    let docs = YamlLoader::load_from_str(&std::fs::read_to_string(path)?)?;
+    let dir = canonical.parent().unwrap_or(Path::new("."));
+    let doc = docs.first().ok_or(SceneParseError::Empty)?;
+    let items = expand_includes(doc, dir, visited)?;
+
+    // the same file may still be included again from elsewhere, as long as it's not a cycle
+    visited.remove(&canonical);
+
+    Ok(items)
+}
+
+/// Flattens a scene document into a list of items, replacing each `- include: path` with the
+/// items of the included file. Cameras from included files are dropped.
+fn expand_includes(
+    doc: &Yaml,
+    dir: &Path,
+    visited: &mut HashSet<PathBuf>,
+) -> Result<Vec<Yaml>, SceneParseError> {
+    let mut items = Vec::new();
+
+    for elem in scene_items(doc)? {
+        let Some(file) = elem["include"].as_str() else {
+            items.push(elem.clone());
+            continue;
+        };
+
+        let included = load_items(&dir.join(file), visited)?;
+        items.extend(
+            included
+                .into_iter()
+                .filter(|item| item["add"].as_str() != Some("camera")),
+        );
+    }
+
+    Ok(items)
+}
+
+/// Builds the camera and world from a parsed scene document. Nothing here depends on the text
+/// being YAML, only on the document's shape, so other formats (e.g. [JSON](crate::io::json)) are
+/// converted to a [Yaml] tree and built here too.
+pub(super) fn build_scene(doc: &Yaml) -> ParseResult<Camera, World> {
     let mut camera = None;
     let mut shapes: Vec<Shape> = Vec::new();
     let mut lights: Vec<Light> = Vec::new();
-
-    for elem in doc.as_vec().unwrap().iter() {
-        let hash = elem.as_hash().unwrap();
+    let mut background = None;
+    let mut defines = HashMap::new();
+
+    for elem in scene_items(doc)? {
+        let hash = elem.as_hash().ok_or_else(|| {
+            SceneParseError::Malformed(format!("scene items should be maps: {:?}", elem))
+        })?;
+
+        // "- define: name" blocks are only stored, to be referenced by later items
+        if hash.contains_key(&Yaml::from_str("define")) {
+            add_define(hash, &mut defines)?;
+            continue;
+        }
+        let hash = &resolve_defines(hash, &defines)?;
 
         // look for "- add: item" in the yaml file
         if let Some(item) = hash.get(&Yaml::from_str("add")) {
-            let t = item.as_str().unwrap();
+            let t = item.as_str().ok_or_else(|| {
+                SceneParseError::Malformed(format!("`add` should name an item type: {:?}", item))
+            })?;
 
             match t {
                 "camera" => {
-                    camera = make_camera(hash);
+                    camera = make_camera(hash)?;
                 }
                 "light" => {
-                    lights.push(make_light(hash).expect("could not parse lights"));
+                    lights.push(make_light(hash)?.ok_or_else(|| incomplete_item(t))?);
+                }
+                "sphere" | "plane" | "quad" => {
+                    shapes.push(make_shape(hash, t)?.ok_or_else(|| incomplete_item(t))?);
                 }
-                "sphere" | "plane" => {
-                    shapes.push(make_shape(hash, t).expect("could not parse shapes"));
+                "world" => {
+                    background = color_from_key(hash, "background")?;
                 }
-                _ => unimplemented!("item type {:?} was not recognized", t),
+                _ => return Err(SceneParseError::UnknownItem(t.to_string())),
             }
         }
     }
 
-    let world = World::new(shapes, lights);
+    let mut world = World::new(shapes, lights);
+    if let Some(background) = background {
+        world = world.with_background(background);
+    }
 
     Ok((camera, world))
 }
 
-/// Constructs a camera from the data in the current hash. Returns `None` if any of `hsize`,
-/// `vsize`, `fov`, `from`, `up`, or `to` are missing. TODO: This probably isn't desired, so there
-/// should be defaults in the future.
-fn make_camera(hash: &yaml::Hash) -> Option<Camera> {
-    let hsize = usize_from_key(hash, "hsize")?;
-    let vsize = usize_from_key(hash, "vsize")?;
-    let fov = float_from_key(hash, "fov")?;
+/// The items of a scene document, which has to be a list.
+fn scene_items(doc: &Yaml) -> Result<&[Yaml], SceneParseError> {
+    doc.as_vec()
+        .map(Vec::as_slice)
+        .ok_or_else(|| SceneParseError::Malformed("a scene should be a list of items".to_string()))
+}
+
+/// The error for an `- add: item` whose data couldn't be turned into the item, e.g. a light
+/// missing its position.
+fn incomplete_item(t: &str) -> SceneParseError {
+    SceneParseError::Malformed(format!("could not parse the `{}` item", t))
+}
+
+/// Stores the `value` of a `- define: name` block so that later items can refer to it by name. If
+/// the block has an `extend` key, the value is layered on top of the named define: maps (e.g.
+/// materials) are merged key by key, and lists (e.g. transforms) are appended to.
+///
+/// ```text
+/// - define: white-material
+///   value:
+///     color: [1.0, 1.0, 1.0]
+///     diffuse: 0.7
+///
+/// - define: shiny-white
+///   extend: white-material
+///   value:
+///     reflective: 0.3
+/// ```
+fn add_define(
+    hash: &yaml::Hash,
+    defines: &mut HashMap<String, Yaml>,
+) -> Result<(), SceneParseError> {
+    let name = hash[&Yaml::from_str("define")]
+        .as_str()
+        .ok_or_else(|| SceneParseError::Malformed("define names should be strings".to_string()))?;
+    let value = hash
+        .get(&Yaml::from_str("value"))
+        .ok_or_else(|| SceneParseError::Malformed(format!("define `{}` needs a `value`", name)))?
+        .clone();
+
+    let value = match hash.get(&Yaml::from_str("extend")).and_then(|e| e.as_str()) {
+        Some(base_name) => {
+            let base = lookup_define(base_name, defines)?;
+
+            match (base, value) {
+                (Yaml::Hash(base), Yaml::Hash(overrides)) => {
+                    let mut merged = base.clone();
+                    merged.extend(overrides);
+                    Yaml::Hash(merged)
+                }
+                (Yaml::Array(base), Yaml::Array(more)) => {
+                    Yaml::Array(base.iter().cloned().chain(more).collect())
+                }
+                (_, value) => value,
+            }
+        }
+        None => value,
+    };
+
+    defines.insert(name.to_string(), value);
+
+    Ok(())
+}
 
-    let from = point_from_key(hash, "from")?;
-    let to = point_from_key(hash, "to")?;
-    let up = vec3_from_key(hash, "up")?;
-    let aa = set_antialiasing(hash)?;
+/// Replaces references to defined names in an item's `material` and `transform` keys with the
+/// defined values. Individual entries of a transform list may also be names of defined
+/// transform lists.
+fn resolve_defines(
+    hash: &yaml::Hash,
+    defines: &HashMap<String, Yaml>,
+) -> Result<yaml::Hash, SceneParseError> {
+    let mut resolved = hash.clone();
+
+    if let Some(name) = hash
+        .get(&Yaml::from_str("material"))
+        .and_then(|m| m.as_str())
+    {
+        let material = lookup_define(name, defines)?.clone();
+        resolved.insert(Yaml::from_str("material"), material);
+    }
+
+    if let Some(tf) = hash.get(&Yaml::from_str("transform")) {
+        let tf = match tf {
+            Yaml::String(name) => lookup_define(name, defines)?,
+            tf => tf,
+        };
+
+        if let Some(tf_list) = tf.as_vec() {
+            let mut expanded = Vec::with_capacity(tf_list.len());
+
+            for t in tf_list {
+                match t {
+                    Yaml::String(name) => match lookup_define(name, defines)? {
+                        Yaml::Array(list) => expanded.extend(list.iter().cloned()),
+                        other => expanded.push(other.clone()),
+                    },
+                    t => expanded.push(t.clone()),
+                }
+            }
 
-    Some(
+            resolved.insert(Yaml::from_str("transform"), Yaml::Array(expanded));
+        }
+    }
+
+    Ok(resolved)
+}
+
+fn lookup_define<'a>(
+    name: &str,
+    defines: &'a HashMap<String, Yaml>,
+) -> Result<&'a Yaml, SceneParseError> {
+    defines
+        .get(name)
+        .ok_or_else(|| SceneParseError::Undefined(name.to_string()))
+}
+
+/// Constructs a camera from the data in the current hash. Returns `Ok(None)` if any of `hsize`,
+/// `vsize`, `fov`, `from`, `up`, or `to` are missing. `aa` is optional; see [set_antialiasing]. TODO: This probably isn't desired, so there
+/// should be defaults in the future.
+fn make_camera(hash: &yaml::Hash) -> Result<Option<Camera>, SceneParseError> {
+    let (Some(hsize), Some(vsize), Some(fov)) = (
+        usize_from_key(hash, "hsize"),
+        usize_from_key(hash, "vsize"),
+        float_from_key(hash, "fov"),
+    ) else {
+        return Ok(None);
+    };
+
+    let (Some(from), Some(to), Some(up)) = (
+        point_from_key(hash, "from")?,
+        point_from_key(hash, "to")?,
+        vec3_from_key(hash, "up")?,
+    ) else {
+        return Ok(None);
+    };
+
+    let Some(aa) = set_antialiasing(hash)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(
         Camera::new(hsize, vsize, fov)
             .with_antialiasing(aa.level)
             .with_aa_method(aa.method)
             .with_transform(&Matrix::view_transform(from, to, up)),
-    )
+    ))
 }
 
-fn set_antialiasing(hash: &yaml::Hash) -> Option<AntiAliasing> {
+/// Reads the camera's anti-aliasing settings, either as a hash (`aa: { level, method, tolerance }`)
+/// or as a plain integer (`aa: 5`), which is shorthand for stochastic anti-aliasing at that level.
+/// Multisampling also takes a `max_samples` cap. Without `aa`, the camera casts a single ray
+/// through each pixel's center. Asking for that with a level of 0 is easy to do by accident, so
+/// it's an [InvalidValue](SceneParseError::InvalidValue) error instead.
+fn set_antialiasing(hash: &yaml::Hash) -> Result<Option<AntiAliasing>, SceneParseError> {
     let default = AntiAliasing::default();
 
-    if let Some(aa) = hash.get(&Yaml::from_str("aa")) {
-        let aa_hash = aa
-            .as_hash()
-            .expect("could not parse `aa` properly in the YAML file");
-        let level = usize_from_key(aa_hash, "level").unwrap_or(default.level);
-        let etol = float_from_key(aa_hash, "tolerance").unwrap_or(default.error_tolerance);
-        let method = aa_hash.get(&Yaml::from_str("method"))?.as_str()?;
-
-        match method {
-            "random" | "stochastic" => Some(
-                default
-                    .with_method(AAMethod::Stochastic(Stochastic::default()))
-                    .with_level(level),
-            ),
-
-            "multisampling" | "msaa" => Some(
-                default
-                    .with_method(AAMethod::Multisampling(Multisampling::default()))
-                    .with_tolerance(etol)
-                    .with_level(level),
-            ),
-
-            _ => None,
+    if let Some(level) = usize_from_key(hash, "aa") {
+        return Ok(Some(
+            default
+                .with_method(AAMethod::Stochastic(Stochastic::default()))
+                .with_level(nonzero_level(level, "aa")?),
+        ));
+    }
+    let Some(aa) = hash.get(&Yaml::from_str("aa")) else {
+        return Ok(Some(default));
+    };
+
+    let aa_hash = aa
+        .as_hash()
+        .ok_or_else(|| SceneParseError::Malformed(format!("could not parse `aa`: {:?}", aa)))?;
+    let level = match usize_from_key(aa_hash, "level") {
+        Some(level) => nonzero_level(level, "level")?,
+        None => default.level,
+    };
+    let etol = float_from_key(aa_hash, "tolerance").unwrap_or(default.error_tolerance);
+    let Some(method) = aa_hash
+        .get(&Yaml::from_str("method"))
+        .and_then(|m| m.as_str())
+    else {
+        return Ok(None);
+    };
+
+    let aa = match method {
+        "random" | "stochastic" => default
+            .with_method(AAMethod::Stochastic(Stochastic::default()))
+            .with_level(level),
+
+        "grid" => default
+            .with_method(AAMethod::Grid(GridSupersampling::default()))
+            .with_level(level),
+
+        "adaptive" => {
+            let adaptive = default
+                .with_method(AAMethod::Adaptive(Adaptive::default()))
+                .with_level(level);
+
+            match float_from_key(aa_hash, "tolerance") {
+                Some(etol) => adaptive.with_tolerance(etol),
+                None => adaptive,
+            }
         }
-    } else {
-        None
+
+        "multisampling" | "msaa" => {
+            let ms = Multisampling::default();
+            let max_samples = usize_from_key(aa_hash, "max_samples").unwrap_or(ms.max_samples());
+
+            default
+                .with_method(AAMethod::Multisampling(ms.with_max_samples(max_samples)))
+                .with_tolerance(etol)
+                .with_level(level)
+        }
+
+        _ => return Ok(None),
+    };
+
+    Ok(Some(aa))
+}
+
+/// Checks that an anti-aliasing `level` under `key` isn't 0.
+fn nonzero_level(level: usize, key: &str) -> Result<usize, SceneParseError> {
+    if level == 0 {
+        return Err(SceneParseError::InvalidValue {
+            key: key.to_string(),
+            value: level.to_string(),
+        });
     }
+
+    Ok(level)
 }
 
-/// Constructs a shape from a hash and a "type" keyword. Returns `None` if the "type" isn't a
+/// Constructs a shape from a hash and a "type" keyword. Returns `Ok(None)` if the "type" isn't a
 /// recognized shape. TODO: refactor how `Shape` works with individual shape variants. Code right
 /// now is repetitive.
-fn make_shape(hash: &yaml::Hash, t: &str) -> Option<Shape> {
-    match t {
+fn make_shape(hash: &yaml::Hash, t: &str) -> Result<Option<Shape>, SceneParseError> {
+    let shape = match t {
         "sphere" => Some(
             Sphere::default()
-                .with_material(&make_material(hash))
-                .with_transform(&transform(hash))
+                .with_material(&make_material(hash)?)
+                .with_transform(&transform(hash)?)
                 .as_shape(),
         ),
         "plane" => Some(
             Plane::default()
-                .with_material(&make_material(hash))
-                .with_transform(&transform(hash))
+                .with_material(&make_material(hash)?)
+                .with_transform(&transform(hash)?)
                 .as_shape(),
         ),
+        "quad" => {
+            let default = Quad::default();
+
+            Some(
+                Quad::new(
+                    point_from_key(hash, "origin")?.unwrap_or(default.origin),
+                    vec3_from_key(hash, "u")?.unwrap_or(default.u),
+                    vec3_from_key(hash, "v")?.unwrap_or(default.v),
+                )
+                .with_material(&make_material(hash)?)
+                .with_transform(&transform(hash)?)
+                .as_shape(),
+            )
+        }
         _ => None,
-    }
-}
+    };
+
+    // every shape can be given a `name` to find it by later
+    let name = hash.get(&Yaml::from_str("name")).and_then(|n| n.as_str());
 
-/// Constructs a light from a hash. Returns `None` if the light type isn't recognized. There's only
-/// one light type as of now, but this makes it easier to add more in the future.
-fn make_light(hash: &yaml::Hash) -> Option<Light> {
-    let t = hash.get(&Yaml::from_str("type"))?.as_str()?;
+    Ok(match name {
+        Some(name) => shape.map(|s| s.with_name(name)),
+        None => shape,
+    })
+}
 
-    match t {
-        "point" => Some(Light::new_point_light(
-            point_from_key(hash, "at")?,
-            color_from_key(hash, "intensity")?,
-        )),
+/// Constructs a light from a hash. Returns `Ok(None)` if the light type isn't recognized or its
+/// position or intensity is missing. There's only one light type as of now, but this makes it
+/// easier to add more in the future.
+fn make_light(hash: &yaml::Hash) -> Result<Option<Light>, SceneParseError> {
+    let Some(t) = hash.get(&Yaml::from_str("type")).and_then(|t| t.as_str()) else {
+        return Ok(None);
+    };
+
+    let light = match t {
+        "point" => {
+            let at = point_from_key(hash, "at")?;
+            // a color temperature in kelvin can stand in for the intensity
+            let intensity = match color_from_key(hash, "intensity")? {
+                Some(intensity) => Some(intensity),
+                None => number_from_key(hash, "temperature").map(Color::from_kelvin),
+            };
+
+            let attenuated = hash
+                .get(&Yaml::from_str("attenuated"))
+                .and_then(|a| a.as_bool())
+                .unwrap_or(false);
+
+            at.zip(intensity).map(|(at, intensity)| {
+                if attenuated {
+                    Light::new_point_light_attenuated(at, intensity)
+                } else {
+                    Light::new_point_light(at, intensity)
+                }
+            })
+        }
         _ => None,
-    }
+    };
+
+    Ok(light)
 }
 
-/// Constructs a new material from a hash.
-fn make_material(hash: &yaml::Hash) -> Material {
-    let default = Material::default();
+/// Constructs a new material from a hash. A `preset` key (e.g. `preset: glass`) selects one of
+/// the [materials](crate::core::materials) presets as the starting point; any other keys override
+/// the preset's values.
+fn make_material(hash: &yaml::Hash) -> Result<Material, SceneParseError> {
+    let mat = hash.get(&Yaml::from_str("material"));
+    if let Some(mat) = mat.filter(|m| !m.is_null()) {
+        let mat_hash = mat.as_hash().ok_or_else(|| {
+            SceneParseError::Malformed(format!("`material` should be a map: {:?}", mat))
+        })?;
+        let color = color_from_key(mat_hash, "color")?;
+
+        let base = match mat_hash.get(&Yaml::from_str("preset")) {
+            Some(preset) => {
+                let invalid = || SceneParseError::InvalidValue {
+                    key: "preset".to_string(),
+                    value: format!("{:?}", preset),
+                };
+                let name = preset.as_str().ok_or_else(invalid)?;
+                materials::by_name(name, color.unwrap_or(Color::white())).ok_or_else(|| {
+                    SceneParseError::InvalidValue {
+                        key: "preset".to_string(),
+                        value: name.to_string(),
+                    }
+                })?
+            }
+            None => Material::default(),
+        };
+
+        let mut material = base
+            .clone()
+            .with_ambient(float_from_key(mat_hash, "ambient").unwrap_or(base.ambient))
+            .with_diffuse(float_from_key(mat_hash, "diffuse").unwrap_or(base.diffuse))
+            .with_specular(float_from_key(mat_hash, "specular").unwrap_or(base.specular))
+            .with_shininess(float_from_key(mat_hash, "shininess").unwrap_or(base.shininess))
+            .with_reflective(float_from_key(mat_hash, "reflective").unwrap_or(base.reflective))
+            .with_reflection_fuzz(
+                float_from_key(mat_hash, "reflection_fuzz").unwrap_or(base.reflection_fuzz),
+            )
+            .with_transparency(
+                float_from_key(mat_hash, "transparency").unwrap_or(base.transparency),
+            )
+            .with_refractive_index(
+                float_from_key(mat_hash, "refractive_index").unwrap_or(base.refractive_index),
+            )
+            .with_emission(&color_from_key(mat_hash, "emission")?.unwrap_or(base.emission))
+            .with_roughness(float_from_key(mat_hash, "roughness").unwrap_or(base.roughness))
+            .with_pattern_samples(
+                usize_from_key(mat_hash, "pattern_samples").unwrap_or(base.pattern_samples),
+            )
+            .with_specular_model(
+                match mat_hash
+                    .get(&Yaml::from_str("specular_model"))
+                    .and_then(|s| s.as_str())
+                {
+                    Some("phong") => SpecularModel::Phong,
+                    Some("blinn-phong") => SpecularModel::BlinnPhong,
+                    Some(other) => {
+                        return Err(SceneParseError::InvalidValue {
+                            key: "specular_model".to_string(),
+                            value: other.to_string(),
+                        })
+                    }
+                    None => base.specular_model,
+                },
+            )
+            .with_casts_shadow(match mat_hash.get(&Yaml::from_str("shadow")) {
+                Some(Yaml::Boolean(b)) => *b,
+                Some(other) => {
+                    return Err(SceneParseError::InvalidValue {
+                        key: "shadow".to_string(),
+                        value: format!("{:?}", other),
+                    })
+                }
+                None => base.casts_shadow,
+            });
 
-    if let Some(mat) = hash.get(&Yaml::from_str("material")) {
-        let mat_hash = mat.as_hash().unwrap();
+        if let Some(color) = color {
+            material = material.with_color(&color);
+        }
+        // a pattern takes precedence over `color`
+        if mat_hash.contains_key(&Yaml::from_str("pattern")) {
+            let pattern = make_pattern(mat_hash, "pattern")?.ok_or_else(|| {
+                SceneParseError::Malformed("could not parse the pattern".to_string())
+            })?;
+            material = material.with_pattern(&pattern);
+        }
 
-        Material::default()
-            .with_color(&color_from_key(mat_hash, "color").unwrap_or(default.color))
-            .with_pattern(&make_pattern(mat_hash, "pattern").expect("could not parse the pattern"))
-            .with_ambient(float_from_key(mat_hash, "ambient").unwrap_or(default.ambient))
-            .with_diffuse(float_from_key(mat_hash, "diffuse").unwrap_or(default.diffuse))
-            .with_specular(float_from_key(mat_hash, "specular").unwrap_or(default.specular))
-            .with_shininess(float_from_key(mat_hash, "shininess").unwrap_or(default.shininess))
-            .with_reflective(float_from_key(mat_hash, "reflective").unwrap_or(default.reflective))
+        Ok(material)
     } else {
-        default
+        Ok(Material::default())
     }
 }
 
-/// Parse a specified transformation. If no transform is specified, uses identity matrix. Probably
-/// the easiest idea is to have the data entered as:
+/// Parse a specified transformation. If no transform is specified, uses identity matrix. Each
+/// transformation can be written either as a sequence starting with its name, or as a map from
+/// its name to its arguments:
+///
+/// ```text
+/// - add: sphere
+///   transform:
+///     - [scale, x, y, z]
+///     - [rotate-z, 1.2731]
+///     - translate: [-0.25, 0.5, -0.25]
+///     - rotate-x: 1.57
+/// ```
 ///
-///  - add: sphere
-///    transform:
-///      - [scale, x, y, z]
-///      - [rotate-z, 1.2731]
-///      - [translate, -0.25, 0.5, -0.25]
-fn transform(hash: &yaml::Hash) -> Matrix<4> {
+/// Any other affine transform can be given as its 16 entries in row-major order, e.g.
+/// `[matrix, 1.0, 0.0, 0.0, 0.0, ...]`. A step with the wrong number of arguments is a
+/// [WrongArity](SceneParseError::WrongArity) error.
+fn transform(hash: &yaml::Hash) -> Result<Matrix<4>, SceneParseError> {
     if let Some(tf_list) = hash.get(&Yaml::from_str("transform")) {
-        let tf_array = tf_list.as_vec().unwrap();
+        let tf_array = tf_list.as_vec().ok_or_else(|| {
+            SceneParseError::Malformed("`transform` should be a list".to_string())
+        })?;
         let mut total_transformation = Matrix::identity();
 
         // transformations are applied in "reverse" order, but I don't think I want to put that in
         // here?
         for tf in tf_array.iter() {
-            let t = tf[0].as_str().unwrap();
-            match t {
-                "scale" => {
-                    let tm = Matrix::scaling(
-                        tf[1].as_f64().unwrap(),
-                        tf[2].as_f64().unwrap(),
-                        tf[3].as_f64().unwrap(),
-                    );
-                    total_transformation = total_transformation * tm
-                }
-                "rotate-x" => {
-                    let tm = Matrix::rotation(Axis::X, tf[1].as_f64().unwrap());
-                    total_transformation = total_transformation * tm
-                }
-                "rotate-y" => {
-                    let tm = Matrix::rotation(Axis::Y, tf[1].as_f64().unwrap());
-                    total_transformation = total_transformation * tm
-                }
-                "rotate-z" => {
-                    let tm = Matrix::rotation(Axis::Z, tf[1].as_f64().unwrap());
-                    total_transformation = total_transformation * tm
-                }
-                "translate" => {
-                    let tm = Matrix::translation(
-                        tf[1].as_f64().unwrap(),
-                        tf[2].as_f64().unwrap(),
-                        tf[3].as_f64().unwrap(),
-                    );
-                    total_transformation = total_transformation * tm
-                }
-                "shear" => {
-                    let tm = Matrix::shear(
-                        tf[1].as_f64().unwrap(),
-                        tf[2].as_f64().unwrap(),
-                        tf[3].as_f64().unwrap(),
-                        tf[4].as_f64().unwrap(),
-                        tf[5].as_f64().unwrap(),
-                        tf[6].as_f64().unwrap(),
-                    );
-                    total_transformation = total_transformation * tm
+            let (t, args) = transform_args(tf)?;
+            let arity = match t {
+                "rotate-x" | "rotate-y" | "rotate-z" => 1,
+                "scale" | "translate" => 3,
+                "shear" => 6,
+                "matrix" => 16,
+                _ => args.len(),
+            };
+            if args.len() != arity {
+                return Err(SceneParseError::WrongArity {
+                    key: t.to_string(),
+                    expected: arity,
+                    found: args.len(),
+                });
+            }
+
+            let tm = match t {
+                "scale" => Matrix::scaling(args[0], args[1], args[2]),
+                "rotate-x" => Matrix::rotation(Axis::X, args[0]),
+                "rotate-y" => Matrix::rotation(Axis::Y, args[0]),
+                "rotate-z" => Matrix::rotation(Axis::Z, args[0]),
+                "translate" => Matrix::translation(args[0], args[1], args[2]),
+                "shear" => Matrix::shear(args[0], args[1], args[2], args[3], args[4], args[5]),
+                "matrix" => {
+                    let mut m = Matrix::identity();
+                    for (i, a) in args.iter().enumerate() {
+                        m[i / 4][i % 4] = *a;
+                    }
+
+                    m
                 }
                 _ => {
                     eprintln!(
                         "unknown transformation specified: {:?}. Using identity matrix instead.",
                         t
                     );
-                    let tm = Matrix::identity();
-                    total_transformation = total_transformation * tm
+                    Matrix::identity()
                 }
-            }
+            };
+
+            total_transformation = total_transformation * tm
         }
 
-        total_transformation
+        Ok(total_transformation)
     } else {
-        Matrix::identity()
+        Ok(Matrix::identity())
     }
 }
 
+/// Splits a transformation step into its name and arguments. Steps are either a list
+/// (`[translate, 1, 2, 3]`) or a map with a single entry (`translate: [1, 2, 3]`).
+fn transform_args(tf: &Yaml) -> Result<(&str, Vec<f64>), SceneParseError> {
+    let malformed =
+        || SceneParseError::Malformed(format!("could not parse transformation {:?}", tf));
+    let (name, args) = match tf {
+        Yaml::Array(seq) => (seq.first().ok_or_else(malformed)?, &seq[1..]),
+        Yaml::Hash(map) if map.len() == 1 => {
+            let (name, value) = map.iter().next().ok_or_else(malformed)?;
+            match value.as_vec() {
+                Some(seq) => (name, &seq[..]),
+                None => (name, std::slice::from_ref(value)),
+            }
+        }
+        _ => return Err(malformed()),
+    };
+    let name = name.as_str().ok_or_else(malformed)?;
+    let args = args
+        .iter()
+        .map(|a| {
+            as_number(a).ok_or_else(|| SceneParseError::InvalidValue {
+                key: name.to_string(),
+                value: format!("{:?}", a),
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok((name, args))
+}
+
 /// Constructs a pattern from a hash and a keyword. The keyword argument is only to make blended
 /// patterns easier to implement. YAML should look like:
 ///
+/// ```text
 /// - add: sphere
 ///   material:
 ///     pattern:
@@ -258,117 +691,317 @@ fn transform(hash: &yaml::Hash) -> Matrix<4> {
 ///       colors:
 ///         - [1.0, 1.0, 1.0]
 ///         - [0.0, 0.0, 0.0]
-fn make_pattern(hash: &yaml::Hash, kw: &str) -> Option<Pattern> {
-    if let Some(pat) = hash.get(&Yaml::from_str(kw)) {
-        let pat_hash = pat.as_hash()?;
-        let t = pat_hash.get(&Yaml::from_str("type"))?.as_str()?;
-
-        let pat = match t {
-            "stripes" | "striped" => {
-                let stripe_colors = pat_hash
-                    .get(&Yaml::from_str("colors"))?
-                    .as_vec()?
-                    .iter()
-                    .map(|c| make_color(c).unwrap())
-                    .collect::<Vec<_>>();
-
-                Some(Pattern::new_stripes(stripe_colors).with_transform(&transform(pat_hash)))
-            }
-            "gradient" => {
-                let grad_colors = pat_hash
-                    .get(&Yaml::from_str("colors"))?
-                    .as_vec()?
-                    .iter()
-                    .map(|c| make_color(c).unwrap())
-                    .collect::<Vec<_>>();
-
-                Some(
-                    Pattern::new_gradient(grad_colors[0], grad_colors[1])
-                        .with_transform(&transform(pat_hash)),
-                )
-            }
-            "ring" | "rings" => {
-                let ring_colors = pat_hash
-                    .get(&Yaml::from_str("colors"))?
-                    .as_vec()?
-                    .iter()
-                    .map(|c| make_color(c).unwrap())
-                    .collect::<Vec<_>>();
-
-                Some(Pattern::new_rings(ring_colors).with_transform(&transform(pat_hash)))
-            }
-            "checkers" | "checkered" => {
-                let checker_colors = pat_hash
-                    .get(&Yaml::from_str("colors"))?
-                    .as_vec()?
-                    .iter()
-                    .map(|c| make_color(c).unwrap())
-                    .collect::<Vec<_>>();
-
-                Some(Pattern::new_checkers(checker_colors[0], checker_colors[1]))
-            }
-            "blend" | "blended" => {
-                let bh1 = pat_hash.get(&Yaml::from_str("pattern1"))?.as_hash()?;
-                let bh2 = pat_hash.get(&Yaml::from_str("pattern2"))?.as_hash()?;
+/// ```
+fn make_pattern(hash: &yaml::Hash, kw: &str) -> Result<Option<Pattern>, SceneParseError> {
+    let Some(pat_hash) = hash.get(&Yaml::from_str(kw)).and_then(|p| p.as_hash()) else {
+        return Ok(None);
+    };
+    let Some(t) = pat_hash
+        .get(&Yaml::from_str("type"))
+        .and_then(|t| t.as_str())
+    else {
+        return Ok(None);
+    };
+
+    let pat = match t {
+        "stripes" | "striped" => {
+            let Some(stripe_colors) = colors_from_key(pat_hash, "colors")? else {
+                return Ok(None);
+            };
+
+            let smooth = pat_hash
+                .get(&Yaml::from_str("smooth"))
+                .and_then(|s| s.as_bool())
+                .unwrap_or(false);
+            let stripes = if smooth {
+                Pattern::new_smooth_stripes(stripe_colors)
+            } else {
+                Pattern::new_stripes(stripe_colors)
+            };
+
+            Some(stripes.with_transform(&transform(pat_hash)?))
+        }
+        "gradient" => {
+            let Some(grad_colors) = colors_from_key(pat_hash, "colors")? else {
+                return Ok(None);
+            };
+
+            let ping_pong = pat_hash
+                .get(&Yaml::from_str("ping_pong"))
+                .and_then(|p| p.as_bool())
+                .unwrap_or(false);
+            let (from, to) = color_pair(&grad_colors, "colors")?;
+            let gradient = if ping_pong {
+                Pattern::new_ping_pong_gradient(from, to)
+            } else {
+                Pattern::new_gradient(from, to)
+            };
+
+            Some(gradient.with_transform(&transform(pat_hash)?))
+        }
+        "ring" | "rings" => {
+            let Some(ring_colors) = colors_from_key(pat_hash, "colors")? else {
+                return Ok(None);
+            };
 
-                let p1 = make_pattern(pat_hash, "pattern1")?.with_transform(&transform(bh1));
-                let p2 = make_pattern(pat_hash, "pattern2")?.with_transform(&transform(bh2));
+            Some(Pattern::new_rings(ring_colors).with_transform(&transform(pat_hash)?))
+        }
+        "checkers" | "checkered" => {
+            let Some(checker_colors) = colors_from_key(pat_hash, "colors")? else {
+                return Ok(None);
+            };
 
-                Some(Pattern::new_blended(p1, p2))
-            }
-            _ => None,
-        };
+            Some(Pattern::new_checkers(checker_colors).with_transform(&transform(pat_hash)?))
+        }
+        "blend" | "blended" => {
+            let (Some(bh1), Some(bh2)) = (
+                pat_hash
+                    .get(&Yaml::from_str("pattern1"))
+                    .and_then(|p| p.as_hash()),
+                pat_hash
+                    .get(&Yaml::from_str("pattern2"))
+                    .and_then(|p| p.as_hash()),
+            ) else {
+                return Ok(None);
+            };
+            let (Some(p1), Some(p2)) = (
+                make_pattern(pat_hash, "pattern1")?,
+                make_pattern(pat_hash, "pattern2")?,
+            ) else {
+                return Ok(None);
+            };
+
+            Some(
+                Pattern::new_blended(
+                    p1.with_transform(&transform(bh1)?),
+                    p2.with_transform(&transform(bh2)?),
+                )
+                .with_transform(&transform(pat_hash)?),
+            )
+        }
+        "nested_checkers" | "nested-checkers" => {
+            let (Some(p1), Some(p2)) = (
+                make_pattern(pat_hash, "pattern1")?,
+                make_pattern(pat_hash, "pattern2")?,
+            ) else {
+                return Ok(None);
+            };
+
+            Some(Pattern::new_checkers_nested(p1, p2).with_transform(&transform(pat_hash)?))
+        }
+        "texture_map" | "texture-map" => {
+            let (Some(mapping), Some(uv_pattern)) = (
+                uv_mapping_from_key(pat_hash, "mapping"),
+                make_uv_pattern(pat_hash)?,
+            ) else {
+                return Ok(None);
+            };
+
+            Some(
+                Pattern::new_texture_map(uv_pattern, mapping).with_transform(&transform(pat_hash)?),
+            )
+        }
+        "image" => {
+            let Some(file) = pat_hash
+                .get(&Yaml::from_str("file"))
+                .and_then(|f| f.as_str())
+            else {
+                return Ok(None);
+            };
+            let mapping = if pat_hash.contains_key(&Yaml::from_str("mapping")) {
+                let Some(mapping) = uv_mapping_from_key(pat_hash, "mapping") else {
+                    return Ok(None);
+                };
+
+                mapping
+            } else {
+                UvMapping::Spherical
+            };
+
+            let sampling = match pat_hash
+                .get(&Yaml::from_str("sampling"))
+                .and_then(|s| s.as_str())
+            {
+                Some("bilinear") => Sampling::Bilinear,
+                Some("nearest") | None => Sampling::Nearest,
+                Some(other) => {
+                    return Err(SceneParseError::InvalidValue {
+                        key: "sampling".to_string(),
+                        value: other.to_string(),
+                    })
+                }
+            };
+
+            Some(
+                Pattern::load_image_texture(file, mapping)
+                    .map_err(|source| SceneParseError::Texture {
+                        path: file.into(),
+                        source,
+                    })?
+                    .with_sampling(sampling)
+                    .with_transform(&transform(pat_hash)?),
+            )
+        }
+        _ => None,
+    };
 
-        pat
-    } else {
-        None
-    }
+    Ok(pat)
 }
 
-fn make_color(seq: &Yaml) -> Option<Color> {
-    let comps = seq.as_vec()?;
+/// Reads a UV mapping function by name, e.g. `mapping: spherical`.
+fn uv_mapping_from_key(hash: &yaml::Hash, key: &str) -> Option<UvMapping> {
+    match hash.get(&Yaml::from_str(key))?.as_str()? {
+        "spherical" | "sphere" => Some(UvMapping::Spherical),
+        "planar" | "plane" => Some(UvMapping::Planar),
+        "cylindrical" | "cylinder" => Some(UvMapping::Cylindrical),
+        _ => None,
+    }
+}
 
-    assert!(comps.len() == 3);
+/// Constructs a UV pattern from the `uv_pattern` key of a `texture_map` pattern. YAML should look
+/// like:
+///
+/// ```text
+/// pattern:
+///   type: texture_map
+///   mapping: spherical
+///   uv_pattern:
+///     type: checkers
+///     width: 16
+///     height: 8
+///     colors:
+///       - [0.0, 0.0, 0.0]
+///       - [1.0, 1.0, 1.0]
+/// ```
+fn make_uv_pattern(hash: &yaml::Hash) -> Result<Option<UvPattern>, SceneParseError> {
+    let Some(uv_hash) = hash
+        .get(&Yaml::from_str("uv_pattern"))
+        .and_then(|p| p.as_hash())
+    else {
+        return Ok(None);
+    };
+    let Some(t) = uv_hash
+        .get(&Yaml::from_str("type"))
+        .and_then(|t| t.as_str())
+    else {
+        return Ok(None);
+    };
+
+    let uv_pattern = match t {
+        "checkers" | "checkered" => {
+            let (Some(width), Some(height), Some(colors)) = (
+                number_from_key(uv_hash, "width"),
+                number_from_key(uv_hash, "height"),
+                colors_from_key(uv_hash, "colors")?,
+            ) else {
+                return Ok(None);
+            };
+
+            let (a, b) = color_pair(&colors, "colors")?;
+
+            Some(UvPattern::new_checkers(width, height, a, b))
+        }
+        _ => None,
+    };
 
-    Some(Color(
-        comps[0].as_f64()?,
-        comps[1].as_f64()?,
-        comps[2].as_f64()?,
-    ))
+    Ok(uv_pattern)
 }
 
-fn color_from_key(hash: &yaml::Hash, key: &str) -> Option<Color> {
-    let seq = hash.get(&Yaml::from_str(key))?;
+/// Reads a sequence of exactly three floats. Returns `Ok(None)` if `seq` isn't a sequence of
+/// floats, and a [SceneParseError::WrongArity] naming `key` if it has the wrong number of components.
+fn triple(seq: &Yaml, key: &str) -> Result<Option<(f64, f64, f64)>, SceneParseError> {
+    let Some(comps) = seq.as_vec() else {
+        return Ok(None);
+    };
+
+    if comps.len() != 3 {
+        return Err(SceneParseError::WrongArity {
+            key: key.to_string(),
+            expected: 3,
+            found: comps.len(),
+        });
+    }
+
+    let (Some(x), Some(y), Some(z)) = (comps[0].as_f64(), comps[1].as_f64(), comps[2].as_f64())
+    else {
+        return Ok(None);
+    };
 
-    make_color(seq)
+    Ok(Some((x, y, z)))
 }
 
-fn vec3_from_key(hash: &yaml::Hash, key: &str) -> Option<Vec3> {
-    let seq = hash.get(&Yaml::from_str(key))?;
+fn triple_from_key(
+    hash: &yaml::Hash,
+    key: &str,
+) -> Result<Option<(f64, f64, f64)>, SceneParseError> {
+    match hash.get(&Yaml::from_str(key)) {
+        Some(seq) => triple(seq, key),
+        None => Ok(None),
+    }
+}
 
-    let comps = seq.as_vec()?;
+/// Reads a color given either as an `[r, g, b]` triple or as a `"#rrggbb"` hex string.
+fn color(yaml: &Yaml, key: &str) -> Result<Option<Color>, SceneParseError> {
+    if let Some(code) = yaml.as_str() {
+        let hex = code.strip_prefix('#').unwrap_or(code);
+        let invalid = || SceneParseError::InvalidValue {
+            key: key.to_string(),
+            value: code.to_string(),
+        };
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
 
-    assert!(comps.len() == 3);
+        return u32::from_str_radix(hex, 16)
+            .map(|n| Some(Color::from_hex(n)))
+            .map_err(|_| invalid());
+    }
 
-    Some(Vec3(
-        comps[0].as_f64()?,
-        comps[1].as_f64()?,
-        comps[2].as_f64()?,
-    ))
+    Ok(triple(yaml, key)?.map(|(r, g, b)| Color(r, g, b)))
 }
 
-fn point_from_key(hash: &yaml::Hash, key: &str) -> Option<Point> {
-    let seq = hash.get(&Yaml::from_str(key))?;
+/// Reads a list of colors, e.g. the `colors` of a pattern.
+fn colors_from_key(hash: &yaml::Hash, key: &str) -> Result<Option<Vec<Color>>, SceneParseError> {
+    let Some(seqs) = hash.get(&Yaml::from_str(key)).and_then(|c| c.as_vec()) else {
+        return Ok(None);
+    };
+
+    let mut colors = Vec::with_capacity(seqs.len());
+    for seq in seqs {
+        match color(seq, key)? {
+            Some(c) => colors.push(c),
+            None => return Ok(None),
+        }
+    }
 
-    let comps = seq.as_vec()?;
+    Ok(Some(colors))
+}
 
-    assert!(comps.len() == 3);
+/// Splits a list of colors that has to hold exactly two, e.g. the ends of a gradient.
+fn color_pair(colors: &[Color], key: &str) -> Result<(Color, Color), SceneParseError> {
+    match colors {
+        [a, b] => Ok((*a, *b)),
+        _ => Err(SceneParseError::WrongArity {
+            key: key.to_string(),
+            expected: 2,
+            found: colors.len(),
+        }),
+    }
+}
 
-    Some(Point(
-        comps[0].as_f64()?,
-        comps[1].as_f64()?,
-        comps[2].as_f64()?,
-    ))
+fn color_from_key(hash: &yaml::Hash, key: &str) -> Result<Option<Color>, SceneParseError> {
+    match hash.get(&Yaml::from_str(key)) {
+        Some(c) => color(c, key),
+        None => Ok(None),
+    }
+}
+
+fn vec3_from_key(hash: &yaml::Hash, key: &str) -> Result<Option<Vec3>, SceneParseError> {
+    Ok(triple_from_key(hash, key)?.map(|(x, y, z)| Vec3(x, y, z)))
+}
+
+fn point_from_key(hash: &yaml::Hash, key: &str) -> Result<Option<Point>, SceneParseError> {
+    Ok(triple_from_key(hash, key)?.map(|(x, y, z)| Point(x, y, z)))
 }
 
 fn float_from_key(hash: &yaml::Hash, key: &str) -> Option<f64> {
@@ -377,6 +1010,16 @@ fn float_from_key(hash: &yaml::Hash, key: &str) -> Option<f64> {
     f.as_f64()
 }
 
+/// Like [float_from_key], but also accepts integers (e.g. `width: 16`).
+fn number_from_key(hash: &yaml::Hash, key: &str) -> Option<f64> {
+    as_number(hash.get(&Yaml::from_str(key))?)
+}
+
+/// Reads a real or an integer as an `f64`.
+fn as_number(n: &Yaml) -> Option<f64> {
+    n.as_f64().or_else(|| n.as_i64().map(|i| i as f64))
+}
+
 fn usize_from_key(hash: &yaml::Hash, key: &str) -> Option<usize> {
     let u = hash.get(&Yaml::from_str(key))?;
 
@@ -386,43 +1029,137 @@ fn usize_from_key(hash: &yaml::Hash, key: &str) -> Option<usize> {
 #[cfg(test)]
 mod yaml_tests {
     use super::*;
-    use crate::io::error::{ParseResult, YamlError};
+    use crate::core::world::Background;
 
-    type YamlResult<T> = Result<T, YamlError>;
+    type YamlResult<T> = Result<T, SceneParseError>;
 
-    fn parse_from_str(s: &str) -> ParseResult<Camera, World> {
-        let docs = YamlLoader::load_from_str(s)?;
-        let doc = &docs[0];
+    #[test]
+    fn can_parse_scenes_from_strings() -> YamlResult<()> {
+        let yaml = r#"
+---
+- add: camera
+  hsize: 40
+  vsize: 30
+  fov: 1.0
+  from: [0.0, 0.0, -5.0]
+  to: [0.0, 0.0, 0.0]
+  up: [0.0, 1.0, 0.0]
+  aa: 1
+- add: light
+  type: point
+  at: [-10.0, 10.0, -10.0]
+  intensity: [1.0, 1.0, 1.0]
+- add: sphere
+  transform:
+    - [translate, 0.0, 1.0, 0.0]
+"#;
+        let (camera, world) = parse_yaml_str(yaml)?;
+        let camera = camera.unwrap();
 
-        let mut camera = None;
-        let mut shapes: Vec<Shape> = Vec::new();
-        let mut lights: Vec<Light> = Vec::new();
+        assert_eq!((camera.hsize(), camera.vsize()), (40, 30));
+        assert_eq!(world.lights.len(), 1);
+        assert_eq!(world.lights[0].position(), Point(-10.0, 10.0, -10.0));
+        assert_eq!(
+            world.objects,
+            vec![Sphere::default()
+                .with_transform(&Matrix::translation(0.0, 1.0, 0.0))
+                .as_shape()]
+        );
 
-        for elem in doc.as_vec().unwrap().iter() {
-            let hash = elem.as_hash().unwrap();
+        Ok(())
+    }
 
-            // look for "- add: item" in the yaml file
-            if let Some(item) = hash.get(&Yaml::from_str("add")) {
-                let t = item.as_str().unwrap();
+    #[test]
+    fn can_parse_texture_map_patterns() -> YamlResult<()> {
+        let yaml = r#"
+---
+- add: sphere
+  material:
+    pattern:
+      type: texture_map
+      mapping: spherical
+      uv_pattern:
+        type: checkers
+        width: 16
+        height: 8
+        colors:
+          - [0.0, 0.0, 0.0]
+          - [1.0, 1.0, 1.0]
+"#;
+        let (_, w) = parse_yaml_str(yaml)?;
 
-                match t {
-                    "camera" => {
-                        camera = make_camera(hash);
-                    }
-                    "light" => {
-                        lights.push(make_light(hash).expect("could not parse lights"));
-                    }
-                    "sphere" | "plane" => {
-                        shapes.push(make_shape(hash, t).expect("could not parse shapes"));
-                    }
-                    _ => unimplemented!("item type {:?} was not recognized", t),
-                }
-            }
-        }
+        assert_eq!(
+            w.objects[0].material().pattern,
+            Pattern::new_texture_map(
+                UvPattern::new_checkers(16.0, 8.0, Color::black(), Color::white()),
+                UvMapping::Spherical
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_parse_image_patterns() -> YamlResult<()> {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/samples/textures/2x2.png");
+        let yaml = format!(
+            r#"
+---
+- add: sphere
+  material:
+    pattern:
+      type: image
+      file: {}
+      mapping: planar
+
+- add: sphere
+  material:
+    pattern:
+      type: image
+      file: {}
+      mapping: planar
+      sampling: bilinear
+"#,
+            path, path
+        );
+        let (_, w) = parse_yaml_str(&yaml)?;
+
+        assert_eq!(
+            w.objects[0].material().pattern,
+            Pattern::load_image_texture(path, UvMapping::Planar).unwrap()
+        );
+        assert_eq!(
+            w.objects[1].material().pattern,
+            Pattern::load_image_texture(path, UvMapping::Planar)
+                .unwrap()
+                .with_sampling(Sampling::Bilinear)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_parse_quads_from_yaml() -> YamlResult<()> {
+        let yaml = r#"
+---
+- add: quad
+  origin: [-1.0, -1.0, 5.0]
+  u: [2.0, 0.0, 0.0]
+  v: [0.0, 2.0, 0.0]
+"#;
+        let (_, w) = parse_yaml_str(yaml)?;
 
-        let world = World::new(shapes, lights);
+        assert_eq!(
+            w.objects[0],
+            Quad::new(
+                Point(-1.0, -1.0, 5.0),
+                Vec3(2.0, 0.0, 0.0),
+                Vec3(0.0, 2.0, 0.0)
+            )
+            .as_shape()
+        );
 
-        Ok((camera, world))
+        Ok(())
     }
 
     #[test]
@@ -437,11 +1174,11 @@ mod yaml_tests {
         - [1.0, 0.0, 0.0]
         - [0.0, 0.0, 1.0]
 "#;
-        let y = parse_from_str(yaml)?;
+        let y = parse_yaml_str(yaml)?;
         let yw = y.1;
 
         assert_eq!(
-            yw.objects[0].material().pattern.unwrap(),
+            yw.objects[0].material().pattern,
             Pattern::new_rings(vec![Color::red(), Color::blue()])
         );
 
@@ -449,52 +1186,308 @@ mod yaml_tests {
     }
 
     #[test]
-    fn can_parse_lights_from_yaml() -> YamlResult<()> {
+    fn can_parse_checkers_with_any_number_of_colors() -> YamlResult<()> {
         let yaml = r#"
 ---
-- add: light
-  type: point
-  intensity: [1.0, 1.0, 1.0]
-  at: [-5.0, 10.0, 0.0]
+- add: plane
+  material:
+    pattern:
+      type: checkers
+      colors:
+        - [1.0, 0.0, 0.0]
+        - [0.0, 1.0, 0.0]
+        - [0.0, 0.0, 1.0]
+      transform:
+        - [scale, 0.5, 0.5, 0.5]
 "#;
-        let yl = parse_from_str(yaml)?;
+        let y = parse_yaml_str(yaml)?;
 
         assert_eq!(
-            yl.1.lights[0],
-            Light::new_point_light(Point(-5.0, 10.0, 0.0), Color::white())
+            y.1.objects[0].material().pattern,
+            Pattern::new_checkers(vec![Color::red(), Color::green(), Color::blue()])
+                .with_transform(&Matrix::scaling(0.5, 0.5, 0.5))
         );
 
         Ok(())
     }
 
     #[test]
-    fn can_parse_spheres_from_yaml() -> YamlResult<()> {
+    fn can_parse_nested_checkers() -> YamlResult<()> {
         let yaml = r#"
 ---
-- add: sphere
+- add: plane
   material:
-   pattern:
-     type: stripes
-     colors:
-       - [1.0, 1.0, 1.0]
-       - [0.0, 0.0, 0.0]
-   ambient: 0.5
-   diffuse: 0.25
-   shininess: 0.08
+    pattern:
+      type: nested_checkers
+      pattern1:
+        type: stripes
+        colors:
+          - [1.0, 1.0, 1.0]
+          - [0.0, 0.0, 0.0]
+        transform:
+          - [scale, 0.25, 0.25, 0.25]
+      pattern2:
+        type: gradient
+        colors:
+          - [1.0, 0.0, 0.0]
+          - [0.0, 0.0, 1.0]
 "#;
-        let ys = parse_from_str(yaml)?;
+        let y = parse_yaml_str(yaml)?;
 
-        assert_eq!(ys.1.objects[0].material().ambient, 0.5);
         assert_eq!(
-            ys.1.objects[0].material().pattern,
-            Some(Pattern::new_stripes(vec![Color::white(), Color::black()]))
+            y.1.objects[0].material().pattern,
+            Pattern::new_checkers_nested(
+                Pattern::new_stripes(vec![Color::white(), Color::black()])
+                    .with_transform(&Matrix::scaling(0.25, 0.25, 0.25)),
+                Pattern::new_gradient(Color::red(), Color::blue()),
+            )
         );
 
         Ok(())
     }
 
     #[test]
-    fn can_make_materials_from_yaml() -> YamlResult<()> {
+    fn can_parse_hex_colors() -> YamlResult<()> {
+        let yaml = r##"
+---
+- add: sphere
+  material:
+    color: "#ff8800"
+
+- add: sphere
+  material:
+    pattern:
+      type: stripes
+      colors:
+        - "#ffffff"
+        - [0.0, 0.0, 0.0]
+"##;
+        let y = parse_yaml_str(yaml)?;
+
+        assert_eq!(
+            y.1.objects[0].material().color(),
+            Some(Color::from_hex(0xFF8800))
+        );
+        assert_eq!(
+            y.1.objects[1].material().pattern,
+            Pattern::new_stripes(vec![Color::white(), Color::black()])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_hex_colors_are_errors() {
+        for code in ["#GG0000", "#ff00", "+ff000"] {
+            let yaml = format!("---\n- add: sphere\n  material:\n    color: \"{}\"\n", code);
+            let err = parse_yaml_str(&yaml).err().expect("expected an error");
+
+            assert!(matches!(
+                err,
+                SceneParseError::InvalidValue { ref key, ref value } if key == "color" && value == code
+            ));
+        }
+    }
+
+    #[test]
+    fn can_parse_named_shapes() -> YamlResult<()> {
+        let yaml = r#"
+---
+- add: sphere
+  name: left
+  transform:
+    - [translate, -1.0, 0.0, 0.0]
+- add: sphere
+  name: right
+  transform:
+    - [translate, 1.0, 0.0, 0.0]
+- add: plane
+"#;
+        let (_, w) = parse_yaml_str(yaml)?;
+        let right = w.object_by_name("right").unwrap();
+
+        assert_eq!(right.name(), Some("right"));
+        assert_eq!(right.transform(), Matrix::translation(1.0, 0.0, 0.0));
+        assert_eq!(w.objects[2].name(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_parse_shadowless_materials() -> YamlResult<()> {
+        let yaml = r#"
+---
+- add: sphere
+  material:
+    shadow: false
+- add: sphere
+"#;
+        let y = parse_yaml_str(yaml)?;
+
+        assert!(!y.1.objects[0].material().casts_shadow);
+        assert!(y.1.objects[1].material().casts_shadow);
+
+        Ok(())
+    }
+
+    #[test]
+    fn shadow_must_be_a_bool() {
+        for value in ["\"no\"", "0"] {
+            let yaml = format!("---\n- add: sphere\n  material:\n    shadow: {}\n", value);
+            let err = parse_yaml_str(&yaml).err().expect("expected an error");
+
+            assert!(
+                matches!(err, SceneParseError::InvalidValue { ref key, .. } if key == "shadow")
+            );
+        }
+    }
+
+    #[test]
+    fn can_parse_emissive_materials() -> YamlResult<()> {
+        let yaml = r#"
+---
+- add: sphere
+  material:
+    emission: [0.5, 0.5, 0.5]
+"#;
+        let y = parse_yaml_str(yaml)?;
+
+        assert_eq!(y.1.objects[0].material().emission, Color(0.5, 0.5, 0.5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_parse_world_background() -> YamlResult<()> {
+        let yaml = r#"
+---
+- add: world
+  background: [0.2, 0.4, 0.8]
+"#;
+        let y = parse_yaml_str(yaml)?;
+
+        assert_eq!(y.1.background, Background::Solid(Color(0.2, 0.4, 0.8)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_parse_lights_from_yaml() -> YamlResult<()> {
+        let yaml = r#"
+---
+- add: light
+  type: point
+  intensity: [1.0, 1.0, 1.0]
+  at: [-5.0, 10.0, 0.0]
+"#;
+        let yl = parse_yaml_str(yaml)?;
+
+        assert_eq!(
+            yl.1.lights[0],
+            Light::new_point_light(Point(-5.0, 10.0, 0.0), Color::white())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn light_intensity_can_be_a_color_temperature() -> YamlResult<()> {
+        let yaml = r#"
+---
+- add: light
+  type: point
+  temperature: 3000
+  at: [-5.0, 10.0, 0.0]
+"#;
+        let (_, w) = parse_yaml_str(yaml)?;
+
+        assert_eq!(w.lights[0].intensity(), Color::from_kelvin(3000.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_parse_spheres_from_yaml() -> YamlResult<()> {
+        let yaml = r#"
+---
+- add: sphere
+  material:
+   pattern:
+     type: stripes
+     colors:
+       - [1.0, 1.0, 1.0]
+       - [0.0, 0.0, 0.0]
+   ambient: 0.5
+   diffuse: 0.25
+   shininess: 0.08
+"#;
+        let ys = parse_yaml_str(yaml)?;
+
+        assert_eq!(ys.1.objects[0].material().ambient, 0.5);
+        assert_eq!(
+            ys.1.objects[0].material().pattern,
+            Pattern::new_stripes(vec![Color::white(), Color::black()])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_parse_antialiasing_shorthand() -> YamlResult<()> {
+        let docs = YamlLoader::load_from_str("aa: 5")?;
+        let aa = set_antialiasing(docs[0].as_hash().unwrap())?.unwrap();
+
+        assert_eq!(aa.level, 5);
+        assert!(matches!(aa.method, AAMethod::Stochastic(_)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_parse_antialiasing_hash() -> YamlResult<()> {
+        let yaml = r#"
+aa:
+  level: 3
+  method: msaa
+  tolerance: 0.2
+  max_samples: 64
+"#;
+        let docs = YamlLoader::load_from_str(yaml)?;
+        let aa = set_antialiasing(docs[0].as_hash().unwrap())?.unwrap();
+
+        assert_eq!(aa.level, 3);
+        assert_eq!(aa.error_tolerance, 0.2);
+        assert!(matches!(aa.method, AAMethod::Multisampling(ref m) if m.max_samples() == 64));
+
+        Ok(())
+    }
+
+    #[test]
+    fn antialiasing_level_zero_is_an_error() -> YamlResult<()> {
+        for (yaml, key) in [("aa: 0", "aa"), ("aa: { level: 0, method: grid }", "level")] {
+            let docs = YamlLoader::load_from_str(yaml)?;
+            let err = set_antialiasing(docs[0].as_hash().unwrap())
+                .err()
+                .expect("expected an error");
+
+            assert!(matches!(
+                err,
+                SceneParseError::InvalidValue { key: ref k, ref value } if k == key && value == "0"
+            ));
+        }
+
+        // leaving `aa` out is how to turn anti-aliasing off
+        let docs = YamlLoader::load_from_str("hsize: 10")?;
+        assert_eq!(
+            set_antialiasing(docs[0].as_hash().unwrap())?.unwrap().level,
+            0
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_make_materials_from_yaml() -> YamlResult<()> {
         let yaml = r#"
 ---
 material:
@@ -511,11 +1504,11 @@ material:
         let doc = &docs[0];
 
         let hash = doc.as_hash().unwrap();
-        let mat = make_material(hash);
+        let mat = make_material(hash)?;
 
         assert_eq!(
             mat.pattern,
-            Some(Pattern::new_stripes(vec![Color::red(), Color::black()]))
+            Pattern::new_stripes(vec![Color::red(), Color::black()])
         );
         assert_eq!(mat.ambient, 0.5);
         assert_eq!(mat.diffuse, 0.25);
@@ -525,6 +1518,356 @@ material:
         Ok(())
     }
 
+    #[test]
+    fn material_presets_can_be_overridden() -> YamlResult<()> {
+        let yaml = r#"
+---
+- add: sphere
+  material:
+    preset: glass
+    reflective: 0.5
+
+- add: sphere
+  material:
+    preset: metal
+    color: [1.0, 0.0, 0.0]
+"#;
+        let (_, w) = parse_yaml_str(yaml)?;
+
+        assert_eq!(
+            w.objects[0].material(),
+            materials::glass().with_reflective(0.5)
+        );
+        assert_eq!(w.objects[1].material(), materials::metal(Color::red()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn transforms_can_be_written_as_maps() -> YamlResult<()> {
+        let yaml = r#"
+---
+- add: sphere
+  transform:
+    - [scale, 0.5, 2.0, 0.5]
+    - [rotate-x, 1.57]
+    - [shear, 1.0, 0.0, 0.0, 0.0, 0.0, 0.5]
+    - [translate, -0.5, 1.0, 0.5]
+
+- add: sphere
+  transform:
+    - scale: [0.5, 2.0, 0.5]
+    - rotate-x: 1.57
+    - shear: [1.0, 0.0, 0.0, 0.0, 0.0, 0.5]
+    - translate: [-0.5, 1.0, 0.5]
+"#;
+        let (_, w) = parse_yaml_str(yaml)?;
+        let expected = Matrix::scaling(0.5, 2.0, 0.5)
+            * Matrix::rotation(Axis::X, 1.57)
+            * Matrix::shear(1.0, 0.0, 0.0, 0.0, 0.0, 0.5)
+            * Matrix::translation(-0.5, 1.0, 0.5);
+
+        assert_eq!(w.objects[0].transform(), expected);
+        assert_eq!(w.objects[1].transform(), w.objects[0].transform());
+
+        Ok(())
+    }
+
+    #[test]
+    fn transform_args_can_be_integers() -> YamlResult<()> {
+        let yaml = r#"
+---
+- add: sphere
+  transform:
+    - translate: [1, 2, 3]
+    - [scale, 2, 2, 2]
+    - rotate-y: 0
+"#;
+        let (_, w) = parse_yaml_str(yaml)?;
+
+        assert_eq!(
+            w.objects[0].transform(),
+            Matrix::translation(1.0, 2.0, 3.0)
+                * Matrix::scaling(2.0, 2.0, 2.0)
+                * Matrix::rotation(Axis::Y, 0.0)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_transforms_are_errors() {
+        let parse = |step: &str| {
+            let yaml = format!("---\n- add: sphere\n  transform:\n    - {}\n", step);
+            parse_yaml_str(&yaml).err().expect("expected an error")
+        };
+
+        assert!(matches!(
+            parse("translate: [1, two, 3]"),
+            SceneParseError::InvalidValue { ref key, .. } if key == "translate"
+        ));
+        assert!(matches!(
+            parse("{ translate: [1, 2, 3], scale: [1, 1, 1] }"),
+            SceneParseError::Malformed(_)
+        ));
+        assert!(matches!(parse("{}"), SceneParseError::Malformed(_)));
+        assert!(matches!(parse("[]"), SceneParseError::Malformed(_)));
+        assert!(matches!(parse("[1, 2, 3]"), SceneParseError::Malformed(_)));
+        assert!(matches!(parse("3"), SceneParseError::Malformed(_)));
+    }
+
+    #[test]
+    fn defined_materials_can_be_reused() -> YamlResult<()> {
+        let yaml = r#"
+---
+- define: red-material
+  value:
+    color: [1.0, 0.0, 0.0]
+    diffuse: 0.7
+
+- define: shiny-red
+  extend: red-material
+  value:
+    reflective: 0.5
+
+- define: small
+  value:
+    - [scale, 0.5, 0.5, 0.5]
+
+- add: sphere
+  material: red-material
+  transform: small
+
+- add: sphere
+  material: red-material
+  transform:
+    - small
+    - [translate, 1.0, 0.0, 0.0]
+
+- add: plane
+  material: shiny-red
+"#;
+        let (_, w) = parse_yaml_str(yaml)?;
+        let red = Material::default()
+            .with_color(&Color::red())
+            .with_diffuse(0.7);
+
+        assert_eq!(w.objects[0].material(), red);
+        assert_eq!(w.objects[1].material(), red);
+        assert_eq!(w.objects[2].material(), red.with_reflective(0.5));
+
+        assert_eq!(w.objects[0].transform(), Matrix::scaling(0.5, 0.5, 0.5));
+        assert_eq!(
+            w.objects[1].transform(),
+            Matrix::scaling(0.5, 0.5, 0.5) * Matrix::translation(1.0, 0.0, 0.0)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn undefined_names_are_reported() {
+        let yaml = r#"
+---
+- add: sphere
+  material: missing
+"#;
+        let err = parse_yaml_str(yaml).err().expect("expected an error");
+
+        assert!(matches!(err, SceneParseError::Undefined(ref name) if name == "missing"));
+    }
+
+    #[test]
+    fn empty_input_is_reported() {
+        let err = parse_yaml_str("").err().expect("expected an error");
+
+        assert!(matches!(err, SceneParseError::Empty));
+    }
+
+    #[test]
+    fn unknown_items_are_reported() {
+        let err = parse_yaml_str("- add: teapot")
+            .err()
+            .expect("expected an error");
+
+        assert!(matches!(err, SceneParseError::UnknownItem(ref item) if item == "teapot"));
+    }
+
+    #[test]
+    fn unknown_specular_models_are_reported() {
+        let yaml = r#"
+---
+- add: sphere
+  material:
+    specular_model: gouraud
+"#;
+        let err = parse_yaml_str(yaml).err().expect("expected an error");
+
+        assert!(matches!(
+            err,
+            SceneParseError::InvalidValue { ref key, ref value }
+                if key == "specular_model" && value == "gouraud"
+        ));
+    }
+
+    #[test]
+    fn unknown_texture_sampling_is_reported() {
+        let yaml = r#"
+---
+- add: sphere
+  material:
+    pattern:
+      type: image
+      file: samples/textures/2x2.png
+      sampling: trilinear
+"#;
+        let err = parse_yaml_str(yaml).err().expect("expected an error");
+
+        assert!(matches!(
+            err,
+            SceneParseError::InvalidValue { ref key, ref value }
+                if key == "sampling" && value == "trilinear"
+        ));
+    }
+
+    #[test]
+    fn missing_textures_are_reported_with_their_path() {
+        let yaml = r#"
+---
+- add: sphere
+  material:
+    pattern:
+      type: image
+      file: samples/textures/missing.png
+"#;
+        let err = parse_yaml_str(yaml).err().expect("expected an error");
+
+        assert!(matches!(
+            err,
+            SceneParseError::Texture { ref path, .. } if path.ends_with("missing.png")
+        ));
+    }
+
+    #[test]
+    fn unknown_material_presets_are_reported() {
+        let yaml = r#"
+---
+- add: sphere
+  material:
+    preset: unobtainium
+- add: sphere
+  material:
+    preset: 3
+"#;
+        let err = parse_yaml_str(yaml).err().expect("expected an error");
+        assert!(matches!(
+            err,
+            SceneParseError::InvalidValue { ref key, ref value }
+                if key == "preset" && value == "unobtainium"
+        ));
+
+        let err = parse_yaml_str(&yaml.replace("unobtainium", "glass"))
+            .err()
+            .expect("expected an error");
+        assert!(matches!(err, SceneParseError::InvalidValue { ref key, .. } if key == "preset"));
+    }
+
+    #[test]
+    fn two_color_patterns_need_exactly_two_colors() {
+        let gradient = r#"
+---
+- add: sphere
+  material:
+    pattern:
+      type: gradient
+      colors:
+        - [1.0, 1.0, 1.0]
+"#;
+        let uv_checkers = r#"
+---
+- add: sphere
+  material:
+    pattern:
+      type: texture_map
+      mapping: spherical
+      uv_pattern:
+        type: checkers
+        width: 16
+        height: 8
+        colors:
+          - [1.0, 1.0, 1.0]
+          - [0.0, 0.0, 0.0]
+          - [1.0, 0.0, 0.0]
+"#;
+
+        for (yaml, found) in [(gradient, 1), (uv_checkers, 3)] {
+            let err = parse_yaml_str(yaml).err().expect("expected an arity error");
+
+            assert!(matches!(
+                err,
+                SceneParseError::WrongArity { expected: 2, found: f, .. } if f == found
+            ));
+        }
+    }
+
+    #[test]
+    fn matrix_transforms_need_sixteen_entries() {
+        let yaml = r#"
+---
+- add: sphere
+  transform:
+    - [matrix, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0]
+"#;
+        let err = parse_yaml_str(yaml).err().expect("expected an arity error");
+
+        assert!(matches!(
+            err,
+            SceneParseError::WrongArity { ref key, expected: 16, found: 12 } if key == "matrix"
+        ));
+    }
+
+    #[test]
+    fn scenes_must_be_lists_of_maps() {
+        let not_a_list = parse_yaml_str("add: sphere")
+            .err()
+            .expect("expected an error");
+        let not_a_map = parse_yaml_str("- sphere").err().expect("expected an error");
+
+        assert!(matches!(not_a_list, SceneParseError::Malformed(_)));
+        assert!(matches!(not_a_map, SceneParseError::Malformed(_)));
+    }
+
+    #[test]
+    fn included_files_are_merged_into_the_scene() -> YamlResult<()> {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/samples/includes/main.yml");
+        let (camera, world) = parse_yaml(path)?;
+
+        assert_eq!(camera.unwrap().hsize(), 40);
+        assert_eq!(world.lights.len(), 1);
+        assert_eq!(
+            world.objects,
+            vec![
+                Plane::default().as_shape(),
+                Sphere::default()
+                    .with_transform(&Matrix::translation(-1.0, 1.0, 0.0))
+                    .as_shape(),
+                Sphere::default()
+                    .with_transform(&Matrix::translation(1.0, 1.0, 0.0))
+                    .as_shape(),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn include_cycles_are_reported() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/samples/includes/cycle.yml");
+        let err = parse_yaml(path).err().expect("expected an error");
+
+        assert!(matches!(err, SceneParseError::IncludeCycle(ref p) if p.ends_with("cycle.yml")));
+    }
+
     #[test]
     fn can_make_points_from_yaml() -> YamlResult<()> {
         let yaml = r#"
@@ -536,11 +1879,39 @@ point: [0.0, 0.0, 0.0]
         let doc = &docs[0];
 
         let hash = doc.as_hash().unwrap();
-        let _pt = point_from_key(hash, "point").unwrap();
+        let pt = point_from_key(hash, "point")?;
+
+        assert_eq!(pt, Some(Point(0.0, 0.0, 0.0)));
 
         Ok(())
     }
 
+    #[test]
+    fn wrong_arity_names_the_offending_key() {
+        let yaml = r#"
+---
+- add: camera
+  hsize: 100
+  vsize: 100
+  fov: 0.785
+  from: [0.0, 1.5]
+  to: [0.0, 0.0, 0.0]
+  up: [0.0, 1.0, 0.0]
+"#;
+        let err = parse_yaml_str(yaml).err().expect("expected an arity error");
+
+        assert!(matches!(
+            err,
+            SceneParseError::WrongArity {
+                ref key,
+                expected: 3,
+                found: 2
+            } if key == "from"
+        ));
+        assert!(err.to_string().contains("`from`"));
+        assert!(err.to_string().contains("3"));
+    }
+
     #[test]
     fn can_load_yaml_from_str() -> YamlResult<()> {
         let yaml_test: &str = r#"
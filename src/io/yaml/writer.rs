@@ -0,0 +1,598 @@
+//! Writing scenes back out in the YAML format read by [parse_yaml](super::parse_yaml).
+use std::fmt::{self, Write};
+
+use crate::{
+    core::{
+        antialias::AAMethod,
+        camera::Camera,
+        light::LightType,
+        material::{Material, SpecularModel},
+        pattern::{Pattern, Sampling, UvMapping, UvPattern},
+        world::{Background, World},
+    },
+    math::{Matrix, Point, Vec3},
+    shape::Shape,
+    visuals::Color,
+};
+
+/// Serializes the camera and world as a YAML scene, so that scenes built in code can be saved and
+/// edited as text. Transforms are written as translate/rotate/scale steps where possible, and as a
+/// raw `matrix` step otherwise.
+///
+/// # Lossy round trips
+/// Some things can't be expressed in the YAML format yet, and are lost when the output is parsed
+/// back in:
+/// - area lights (disk and sphere lights)
+/// - portals and custom shapes
+/// - sky gradient and environment map backgrounds
+/// - perturbed patterns, and image textures that weren't loaded from a file
+///
+/// Each of these is left out with a `# skipped ...` comment in its place, so the loss is visible
+/// in the written file.
+///
+/// # Example
+/// ```ignore
+/// let yaml = to_yaml_string(&camera, &world);
+/// std::fs::write("scene.yml", yaml).unwrap();
+/// ```
+pub fn to_yaml_string(camera: &Camera, world: &World) -> String {
+    let mut out = String::new();
+
+    write_scene(&mut out, camera, world).expect("writing to a String can't fail");
+
+    out
+}
+
+fn write_scene(out: &mut String, camera: &Camera, world: &World) -> fmt::Result {
+    writeln!(out, "---")?;
+    write_camera(out, camera)?;
+
+    match &world.background {
+        Background::Solid(c) if *c == Color::black() => {}
+        Background::Solid(c) => {
+            writeln!(out, "\n- add: world")?;
+            writeln!(out, "  background: {}", color(*c))?;
+        }
+        Background::SkyGradient { .. } => {
+            writeln!(
+                out,
+                "\n# skipped the sky gradient background, which YAML can't describe"
+            )?;
+        }
+        Background::EnvironmentMap(_) => {
+            writeln!(
+                out,
+                "\n# skipped the environment map background, which YAML can't describe"
+            )?;
+        }
+    }
+
+    for light in world.lights.iter() {
+        match light.light_type {
+            LightType::PointLight(ref pl) => {
+                writeln!(out, "\n- add: light")?;
+                writeln!(out, "  type: point")?;
+                writeln!(out, "  at: {}", point(light.position()))?;
+                writeln!(out, "  intensity: {}", color(light.intensity()))?;
+                if pl.is_attenuated() {
+                    writeln!(out, "  attenuated: true")?;
+                }
+            }
+            LightType::DiskLight(_) | LightType::SphereLight(_) => {
+                writeln!(out, "\n# skipped an area light, which YAML can't describe")?;
+            }
+        }
+    }
+
+    for object in world.objects.iter() {
+        write_shape(out, object)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the camera, recovering `from`, `to`, and `up` from its view transform.
+fn write_camera(out: &mut String, camera: &Camera) -> fmt::Result {
+    let m = camera.transform();
+    let from = m.inverse().unwrap_or_else(Matrix::identity) * Point(0.0, 0.0, 0.0);
+
+    // The rows of a view transform are `left`, `true_up`, and `-forward`, where
+    // `left = forward x up`. `left` is only a unit vector if `up` was perpendicular to `forward`,
+    // so its length tells us the angle between the original `up` and `forward`.
+    let forward = Vec3(-m[2][0], -m[2][1], -m[2][2]);
+    let true_up = Vec3(m[1][0], m[1][1], m[1][2]);
+    let sin = true_up.magnitude().min(1.0);
+    let up = true_up.normalize() * sin + forward * (1.0 - sin * sin).sqrt();
+    let to = from + forward;
+
+    let aa = camera.antialiasing();
+    let method = match aa.method {
+        AAMethod::Stochastic(_) => "stochastic",
+        AAMethod::Multisampling(_) => "msaa",
+        AAMethod::Grid(_) => "grid",
+        AAMethod::Adaptive(_) => "adaptive",
+    };
+    let tolerance = match aa.method {
+        AAMethod::Adaptive(ref a) => a.error_tolerance(),
+        _ => aa.error_tolerance,
+    };
+
+    writeln!(out, "- add: camera")?;
+    writeln!(out, "  hsize: {}", camera.hsize())?;
+    writeln!(out, "  vsize: {}", camera.vsize())?;
+    writeln!(out, "  fov: {:?}", camera.fov())?;
+    writeln!(out, "  from: {}", point(from))?;
+    writeln!(out, "  to: {}", point(to))?;
+    writeln!(out, "  up: {}", vector(up))?;
+    // without `aa`, the camera doesn't anti-alias, and a level of 0 can't be written
+    if aa.level == 0 {
+        return Ok(());
+    }
+    writeln!(out, "  aa:")?;
+    writeln!(out, "    level: {}", aa.level)?;
+    writeln!(out, "    method: {}", method)?;
+    if let AAMethod::Multisampling(ref m) = aa.method {
+        writeln!(out, "    max_samples: {}", m.max_samples())?;
+    }
+    writeln!(out, "    tolerance: {:?}", tolerance)
+}
+
+fn write_shape(out: &mut String, shape: &Shape) -> fmt::Result {
+    match shape {
+        Shape::Sphere(_) => writeln!(out, "\n- add: sphere")?,
+        Shape::Plane(_) => writeln!(out, "\n- add: plane")?,
+        Shape::Quad(quad) => {
+            writeln!(out, "\n- add: quad")?;
+            writeln!(out, "  origin: {}", point(quad.origin))?;
+            writeln!(out, "  u: {}", vector(quad.u))?;
+            writeln!(out, "  v: {}", vector(quad.v))?;
+        }
+        Shape::Portal(_) => {
+            return writeln!(out, "\n# skipped a portal, which YAML can't describe");
+        }
+        Shape::Custom(_) => {
+            return writeln!(out, "\n# skipped a custom shape, which YAML can't describe");
+        }
+    }
+    if let Some(name) = shape.name() {
+        writeln!(out, "  name: {}", quoted(name))?;
+    }
+
+    write_transform(out, &shape.transform(), "  ")?;
+    write_material(out, &shape.material())
+}
+
+/// Writes a `transform` list (nothing at all for the identity), one step per line.
+fn write_transform(out: &mut String, m: &Matrix<4>, indent: &str) -> fmt::Result {
+    if *m == Matrix::identity() {
+        return Ok(());
+    }
+
+    writeln!(out, "{}transform:", indent)?;
+
+    let Some((t, angles, s)) = m.decompose_euler() else {
+        let entries = (0..16)
+            .map(|i| format!("{:?}", m[i / 4][i % 4]))
+            .collect::<Vec<_>>();
+        return writeln!(out, "{}  - [matrix, {}]", indent, entries.join(", "));
+    };
+
+    if t != Vec3(0.0, 0.0, 0.0) {
+        writeln!(
+            out,
+            "{}  - [translate, {:?}, {:?}, {:?}]",
+            indent, t.0, t.1, t.2
+        )?;
+    }
+    for (axis, angle) in [("z", angles.2), ("y", angles.1), ("x", angles.0)] {
+        if angle != 0.0 {
+            writeln!(out, "{}  - [rotate-{}, {:?}]", indent, axis, angle)?;
+        }
+    }
+    if s != Vec3(1.0, 1.0, 1.0) {
+        writeln!(
+            out,
+            "{}  - [scale, {:?}, {:?}, {:?}]",
+            indent, s.0, s.1, s.2
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Writes a `material` hash with only the values that differ from the default material.
+fn write_material(out: &mut String, m: &Material) -> fmt::Result {
+    let d = Material::default();
+    let mut lines = Vec::new();
+    let mut pattern = String::new();
+
+    match m.pattern {
+        Pattern::Solid(c) if c == Color::white() => {}
+        Pattern::Solid(c) => lines.push(format!("color: {}", color(c))),
+        ref p if describable(p) => write_pattern(&mut pattern, "pattern", p, "    ")?,
+        _ => lines.push("# skipped the pattern, which YAML can't describe yet".to_string()),
+    }
+    for (key, value, default) in [
+        ("ambient", m.ambient, d.ambient),
+        ("diffuse", m.diffuse, d.diffuse),
+        ("specular", m.specular, d.specular),
+        ("shininess", m.shininess, d.shininess),
+        ("reflective", m.reflective, d.reflective),
+        ("reflection_fuzz", m.reflection_fuzz, d.reflection_fuzz),
+        ("transparency", m.transparency, d.transparency),
+        ("refractive_index", m.refractive_index, d.refractive_index),
+        ("roughness", m.roughness, d.roughness),
+    ] {
+        if value != default {
+            lines.push(format!("{}: {:?}", key, value));
+        }
+    }
+    if m.emission != d.emission {
+        lines.push(format!("emission: {}", color(m.emission)));
+    }
+    if m.pattern_samples != d.pattern_samples {
+        lines.push(format!("pattern_samples: {}", m.pattern_samples));
+    }
+    if m.specular_model != d.specular_model {
+        lines.push(match m.specular_model {
+            SpecularModel::Phong => "specular_model: phong".to_string(),
+            SpecularModel::BlinnPhong => "specular_model: blinn-phong".to_string(),
+        });
+    }
+    if m.casts_shadow != d.casts_shadow {
+        lines.push(format!("shadow: {}", m.casts_shadow));
+    }
+
+    if !lines.is_empty() || !pattern.is_empty() {
+        writeln!(out, "  material:")?;
+        for line in lines {
+            writeln!(out, "    {}", line)?;
+        }
+        out.push_str(&pattern);
+    }
+
+    Ok(())
+}
+
+/// Whether `pattern`, and every pattern nested inside it, has a YAML form.
+fn describable(pattern: &Pattern) -> bool {
+    match pattern {
+        Pattern::Blended(b) => describable(&b.pattern1) && describable(&b.pattern2),
+        Pattern::NestedCheckers(nc) => describable(&nc.pattern1) && describable(&nc.pattern2),
+        Pattern::ImageTexture(it) => it.path.is_some(),
+        Pattern::Perturbed(_) => false,
+        _ => true,
+    }
+}
+
+/// Writes a `key:` hash describing `pattern`, with the hash itself indented by `indent`. Only
+/// call this for [describable] patterns.
+fn write_pattern(out: &mut String, key: &str, pattern: &Pattern, indent: &str) -> fmt::Result {
+    let inner = format!("{}  ", indent);
+    writeln!(out, "{}{}:", indent, key)?;
+
+    match pattern {
+        // there's no solid pattern type, but a single stripe colors everything the same
+        Pattern::Solid(c) => {
+            writeln!(out, "{}type: stripes", inner)?;
+            writeln!(out, "{}colors: {}", inner, colors(&[*c]))?;
+        }
+        Pattern::Stripes(sp) => {
+            writeln!(out, "{}type: stripes", inner)?;
+            writeln!(out, "{}colors: {}", inner, colors(&sp.colors))?;
+            if sp.smooth {
+                writeln!(out, "{}smooth: true", inner)?;
+            }
+        }
+        Pattern::Gradient(gp) => {
+            writeln!(out, "{}type: gradient", inner)?;
+            writeln!(out, "{}colors: {}", inner, colors(&[gp.color1, gp.color2]))?;
+            if gp.ping_pong {
+                writeln!(out, "{}ping_pong: true", inner)?;
+            }
+        }
+        Pattern::Rings(rp) => {
+            writeln!(out, "{}type: rings", inner)?;
+            writeln!(out, "{}colors: {}", inner, colors(&rp.colors))?;
+        }
+        Pattern::Checkers(cp) => {
+            writeln!(out, "{}type: checkers", inner)?;
+            writeln!(out, "{}colors: {}", inner, colors(&cp.colors))?;
+        }
+        Pattern::Blended(bp) => {
+            writeln!(out, "{}type: blend", inner)?;
+            write_pattern(out, "pattern1", &bp.pattern1, &inner)?;
+            write_pattern(out, "pattern2", &bp.pattern2, &inner)?;
+        }
+        Pattern::NestedCheckers(nc) => {
+            writeln!(out, "{}type: nested_checkers", inner)?;
+            write_pattern(out, "pattern1", &nc.pattern1, &inner)?;
+            write_pattern(out, "pattern2", &nc.pattern2, &inner)?;
+        }
+        Pattern::TextureMap(tm) => {
+            let UvPattern::Checkers(ref uv) = tm.uv_pattern;
+
+            writeln!(out, "{}type: texture_map", inner)?;
+            writeln!(out, "{}mapping: {}", inner, mapping(tm.mapping))?;
+            writeln!(out, "{}uv_pattern:", inner)?;
+            writeln!(out, "{}  type: checkers", inner)?;
+            writeln!(out, "{}  width: {:?}", inner, uv.width)?;
+            writeln!(out, "{}  height: {:?}", inner, uv.height)?;
+            writeln!(
+                out,
+                "{}  colors: {}",
+                inner,
+                colors(&[uv.color1, uv.color2])
+            )?;
+        }
+        Pattern::ImageTexture(it) => {
+            let path = it
+                .path
+                .as_ref()
+                .expect("only describable patterns are written");
+
+            writeln!(out, "{}type: image", inner)?;
+            writeln!(out, "{}file: {:?}", inner, path.display().to_string())?;
+            writeln!(out, "{}mapping: {}", inner, mapping(it.mapping))?;
+            if it.sampling == Sampling::Bilinear {
+                writeln!(out, "{}sampling: bilinear", inner)?;
+            }
+        }
+        Pattern::Perturbed(_) => unreachable!("perturbed patterns aren't describable"),
+    }
+
+    write_transform(out, &pattern.transform(), &inner)
+}
+
+fn mapping(m: UvMapping) -> &'static str {
+    match m {
+        UvMapping::Spherical => "spherical",
+        UvMapping::Planar => "planar",
+        UvMapping::Cylindrical => "cylindrical",
+    }
+}
+
+fn colors(cs: &[Color]) -> String {
+    let cs = cs.iter().map(|c| color(*c)).collect::<Vec<_>>();
+
+    format!("[{}]", cs.join(", "))
+}
+
+fn point(p: Point) -> String {
+    format!("[{:?}, {:?}, {:?}]", p.0, p.1, p.2)
+}
+
+fn vector(v: Vec3) -> String {
+    format!("[{:?}, {:?}, {:?}]", v.0, v.1, v.2)
+}
+
+fn color(c: Color) -> String {
+    format!("[{:?}, {:?}, {:?}]", c.0, c.1, c.2)
+}
+
+/// A YAML double-quoted scalar for `s`. Printable characters (unicode included) are written as-is;
+/// quotes, backslashes, and anything YAML doesn't allow unescaped are written as escapes.
+fn quoted(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\0' => out.push_str("\\0"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\u{85}' => out.push_str("\\N"),
+            '\u{2028}' => out.push_str("\\L"),
+            '\u{2029}' => out.push_str("\\P"),
+            '\u{0}'..='\u{1f}' | '\u{7f}'..='\u{9f}' => {
+                out.push_str(&format!("\\x{:02x}", c as u32))
+            }
+            '\u{feff}' | '\u{fffe}' | '\u{ffff}' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+
+    out
+}
+
+#[cfg(test)]
+mod writer_tests {
+    use super::*;
+    use crate::{
+        core::{light::Light, material::Material},
+        io::yaml::parse_yaml_str,
+        math::Axis,
+        shape::{Plane, Quad, Sphere},
+    };
+
+    fn assert_matrix_approx_eq(a: &Matrix<4>, b: &Matrix<4>) {
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((a[i][j] - b[i][j]).abs() < 1e-9, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    fn scene() -> (Camera, World) {
+        let camera = Camera::new(80, 60, 1.2)
+            .with_antialiasing(3)
+            .with_transform(&Matrix::view_transform(
+                Point(1.0, 1.5, -5.0),
+                Point(0.0, 1.0, 0.0),
+                Vec3(0.0, 1.0, 0.0),
+            ));
+
+        let objects = vec![
+            Plane::default()
+                .with_material(&Material::default().with_reflective(0.25))
+                .as_shape(),
+            Sphere::default()
+                .with_transform(
+                    &(Matrix::translation(-0.5, 1.0, 0.5)
+                        * Matrix::rotation(Axis::Y, 0.7)
+                        * Matrix::scaling(0.5, 0.75, 0.5)),
+                )
+                .with_material(
+                    &Material::default()
+                        .with_color(&Color(0.1, 1.0, 0.5))
+                        .with_diffuse(0.7)
+                        .with_roughness(0.3)
+                        .with_pattern_samples(4)
+                        .with_specular_model(SpecularModel::BlinnPhong)
+                        .with_emission(&Color(0.2, 0.2, 0.2))
+                        .with_casts_shadow(false),
+                )
+                .as_shape(),
+            Sphere::default()
+                .with_name("sheared \"egg\"")
+                .with_transform(&Matrix::shear(0.5, 0.0, 0.0, 0.0, 0.0, 0.25))
+                .as_shape(),
+            Quad::new(
+                Point(-1.0, 0.0, -1.0),
+                Vec3(2.0, 0.0, 0.0),
+                Vec3(0.0, 2.0, 0.0),
+            )
+            .as_shape(),
+        ];
+        let lights = vec![
+            Light::new_point_light(Point(-10.0, 10.0, -10.0), Color(1.0, 0.9, 0.8)),
+            Light::new_point_light_attenuated(Point(0.0, 5.0, 0.0), Color(20.0, 20.0, 20.0)),
+        ];
+
+        (
+            camera,
+            World::new(objects, lights).with_background(Color(0.1, 0.2, 0.3)),
+        )
+    }
+
+    #[test]
+    fn scenes_survive_a_round_trip() {
+        let (camera, world) = scene();
+        let yaml = to_yaml_string(&camera, &world);
+        let (parsed_camera, parsed_world) = parse_yaml_str(&yaml).unwrap();
+        let parsed_camera = parsed_camera.unwrap();
+
+        assert_eq!(parsed_camera.hsize(), camera.hsize());
+        assert_eq!(parsed_camera.vsize(), camera.vsize());
+        assert_eq!(parsed_camera.fov(), camera.fov());
+        assert_eq!(parsed_camera.antialiasing().level, 3);
+        assert_matrix_approx_eq(&parsed_camera.transform(), &camera.transform());
+
+        assert_eq!(parsed_world.lights, world.lights);
+        assert_eq!(parsed_world.background, world.background);
+        assert_eq!(parsed_world.objects.len(), world.objects.len());
+        for (parsed, original) in parsed_world.objects.iter().zip(world.objects.iter()) {
+            assert_eq!(parsed.name(), original.name());
+            assert_eq!(parsed.material(), original.material());
+            assert_matrix_approx_eq(&parsed.transform(), &original.transform());
+        }
+        assert!(matches!(parsed_world.objects[3], Shape::Quad(_)));
+    }
+
+    #[test]
+    fn patterns_survive_a_round_trip() {
+        let (white, black, red) = (Color::white(), Color::black(), Color(1.0, 0.0, 0.0));
+        let texture = concat!(env!("CARGO_MANIFEST_DIR"), "/samples/textures/2x2.png");
+        let patterns = [
+            Pattern::new_smooth_stripes(vec![white, black, red])
+                .with_transform(&Matrix::scaling(0.25, 0.25, 0.25)),
+            Pattern::new_ping_pong_gradient(white, red),
+            Pattern::new_rings(vec![white, black]),
+            Pattern::new_checkers(vec![white, black, red])
+                .with_transform(&Matrix::translation(0.5, 0.0, 0.0)),
+            Pattern::new_blended(
+                Pattern::new_stripes(vec![white, black])
+                    .with_transform(&Matrix::scaling(0.5, 1.0, 1.0)),
+                Pattern::new_gradient(black, red),
+            ),
+            Pattern::new_checkers_nested(
+                Pattern::new_rings(vec![red, black]),
+                Pattern::new_checkerboard(white, black),
+            )
+            .with_transform(&Matrix::scaling(2.0, 2.0, 2.0)),
+            Pattern::new_texture_map(
+                UvPattern::new_checkers(16.0, 8.0, white, red),
+                UvMapping::Cylindrical,
+            ),
+            Pattern::load_image_texture(texture, UvMapping::Planar)
+                .unwrap()
+                .with_sampling(Sampling::Bilinear),
+        ];
+        let objects = patterns
+            .iter()
+            .map(|p| {
+                Sphere::default()
+                    .with_material(&Material::default().with_pattern(p))
+                    .as_shape()
+            })
+            .collect();
+        let world = World::new(objects, vec![]);
+
+        let yaml = to_yaml_string(&Camera::new(10, 10, 1.0), &world);
+        let (_, parsed) = parse_yaml_str(&yaml).unwrap();
+
+        assert_eq!(parsed.objects.len(), patterns.len());
+        for (parsed, original) in parsed.objects.iter().zip(world.objects.iter()) {
+            assert_eq!(parsed.material(), original.material(), "in:\n{}", yaml);
+        }
+    }
+
+    #[test]
+    fn names_are_written_as_yaml_strings() {
+        let names = [
+            "plain",
+            "sheared \"egg\"",
+            "back\\slash",
+            "caf\u{e9} \u{1f95a}",
+            "tab\tnew\nline\r\u{7}\u{1b}\u{7f}\u{85}\u{2028}\u{feff}",
+            "# not a comment: [or, a, list]",
+        ];
+        let objects = names
+            .iter()
+            .map(|n| Sphere::default().with_name(n).as_shape())
+            .collect();
+        let world = World::new(objects, vec![]);
+
+        let yaml = to_yaml_string(&Camera::new(10, 10, 1.0), &world);
+        let (_, parsed) = parse_yaml_str(&yaml).unwrap();
+
+        let parsed_names = parsed.objects.iter().map(|o| o.name()).collect::<Vec<_>>();
+        assert_eq!(parsed_names, names.map(Some), "in:\n{}", yaml);
+    }
+
+    #[test]
+    fn undescribable_patterns_are_skipped() {
+        let perturbed = Pattern::new_perturbed(Pattern::new_rings(vec![Color::white()]), 0.5);
+        let world = World::new(
+            vec![Sphere::default()
+                .with_material(&Material::default().with_pattern(&perturbed))
+                .as_shape()],
+            vec![],
+        );
+        let yaml = to_yaml_string(&Camera::new(10, 10, 1.0), &world);
+
+        assert!(yaml.contains("# skipped the pattern"));
+        assert!(parse_yaml_str(&yaml).is_ok());
+    }
+
+    #[test]
+    fn transforms_are_written_as_steps_when_possible() {
+        let (camera, world) = scene();
+        let yaml = to_yaml_string(&camera, &world);
+
+        assert!(yaml.contains("    - [translate, -0.5, 1.0, 0.5]\n"));
+        assert!(yaml.contains("    - [rotate-y, "));
+        assert!(yaml.contains("    - [matrix, 1.0, 0.5, 0.0, 0.0, "));
+    }
+
+    #[test]
+    fn default_materials_are_left_out() {
+        let world = World::new(vec![Sphere::default().as_shape()], vec![]);
+        let yaml = to_yaml_string(&Camera::new(10, 10, 1.0), &world);
+
+        assert!(yaml.ends_with("- add: sphere\n"));
+    }
+}
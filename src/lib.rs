@@ -42,7 +42,7 @@
 //!
 //! let (cam, world) = parse_yaml("world.yml").unwrap();
 //!
-//! let canvas = cam.unwrap().render(&world).unwrap();
+//! let canvas = cam.unwrap().render_default_depth(&world).unwrap();
 //! canvas.export("render.png").unwrap();
 //! ```
 
@@ -1,22 +1,59 @@
 //! Mathematical structures for working with rays, vectors, matrices, etc.
 pub mod matrix;
+pub mod noise;
 pub mod point;
+pub mod sampling;
+pub mod transform;
 pub mod vec3;
 
 pub use crate::math::matrix::Axis;
 pub use crate::math::matrix::Matrix;
 pub use crate::math::point::Point;
+pub use crate::math::transform::Transform;
 pub use crate::math::vec3::Vec3;
 
 /// A trait that allows for the comparison of vectors and points.
 ///
 /// Tuples have a 4th dimension `w`. For points, `w = 1.0`; for vecs, `w = 0.0`.
+///
+/// The provided methods work on all four components, `w` included, so they match the usual
+/// vector operations for [Vec3] but not for [Point]: a point's "magnitude" is never less than 1.0.
+/// Use [spatial_distance] for the distance between two points.
 pub trait Tuple {
     fn new(x: f64, y: f64, z: f64) -> Self;
     fn x(&self) -> f64;
     fn y(&self) -> f64;
     fn z(&self) -> f64;
     fn w(&self) -> f64;
+
+    /// The components in `[x, y, z, w]` order.
+    fn to_array(&self) -> [f64; 4] {
+        [self.x(), self.y(), self.z(), self.w()]
+    }
+
+    /// The dot product of all four components.
+    fn dot(&self, other: &Self) -> f64
+    where
+        Self: Sized,
+    {
+        self.x() * other.x() + self.y() * other.y() + self.z() * other.z() + self.w() * other.w()
+    }
+
+    /// The length of the tuple as a 4D vector.
+    fn magnitude(&self) -> f64
+    where
+        Self: Sized,
+    {
+        self.dot(self).sqrt()
+    }
+}
+
+/// The distance between two tuples, ignoring `w`. For points, this is the usual distance between
+/// them.
+pub fn spatial_distance<T: Tuple>(a: &T, b: &T) -> f64 {
+    let (dx, dy, dz) = (a.x() - b.x(), a.y() - b.y(), a.z() - b.z());
+
+    (dx * dx + dy * dy + dz * dz).sqrt()
 }
 
 /// A truly terrible macro that should never be used, so I'm using it for tests. Could just be
@@ -33,7 +70,36 @@ macro_rules! assert_vpeq {
 
 #[cfg(test)]
 mod tuple_tests {
-    use crate::math::{Point, Vec3};
+    use crate::math::{spatial_distance, Point, Tuple, Vec3};
+
+    #[test]
+    fn vec_magnitude_matches_the_tuple_magnitude() {
+        let v = Vec3(1.0, 2.0, 3.0);
+
+        assert_eq!(v.magnitude(), 14.0_f64.sqrt());
+        assert_eq!(Tuple::magnitude(&v), v.magnitude());
+        assert_eq!(Tuple::dot(&v, &Vec3(1.0, 1.0, 1.0)), 6.0);
+    }
+
+    #[test]
+    fn point_tuple_operations_include_w() {
+        let p = Point(1.0, 2.0, 3.0);
+
+        assert_eq!(p.to_array(), [1.0, 2.0, 3.0, 1.0]);
+        assert_eq!(p.magnitude(), 15.0_f64.sqrt());
+    }
+
+    #[test]
+    fn spatial_distance_ignores_w() {
+        let (a, b) = (Point(1.0, 2.0, 3.0), Point(4.0, 6.0, 3.0));
+
+        assert_eq!(spatial_distance(&a, &b), 5.0);
+        assert_eq!(spatial_distance(&a, &b), (b - a).magnitude());
+        assert_eq!(
+            spatial_distance(&Vec3(0.0, 3.0, 4.0), &Vec3(0.0, 0.0, 0.0)),
+            5.0
+        );
+    }
 
     #[test]
     fn subtracting_two_points_gives_vec() {
@@ -33,6 +33,27 @@ impl<const D: usize> Matrix<D> {
         m
     }
 
+    /// Constructs a matrix from its rows, e.g. to write out a transform that the helpers below
+    /// don't cover.
+    pub fn from_rows(rows: [[f64; D]; D]) -> Self {
+        Self { data: rows }
+    }
+
+    /// Constructs a matrix from its columns. This is the transpose of [Matrix::from_rows].
+    pub fn from_columns(columns: [[f64; D]; D]) -> Self {
+        Self::from_rows(columns).transpose()
+    }
+
+    /// The `i`th row of the matrix.
+    pub fn row(&self, i: usize) -> [f64; D] {
+        self.data[i]
+    }
+
+    /// The `j`th column of the matrix.
+    pub fn column(&self, j: usize) -> [f64; D] {
+        self.data.map(|row| row[j])
+    }
+
     /// Transposes the given matrix.
     pub fn transpose(&self) -> Self {
         let mut m = Matrix::default();
@@ -44,6 +65,77 @@ impl<const D: usize> Matrix<D> {
 
         m
     }
+
+    /// Computes the determinant of the matrix. The matrix is invertible exactly when this is
+    /// nonzero. Matrices up to 4x4 (which is all the renderer uses) are expanded by cofactors,
+    /// which is exact for integer entries; larger ones are reduced by Gaussian elimination with
+    /// partial pivoting, which takes `O(D³)` time but can round slightly.
+    pub fn determinant(&self) -> f64 {
+        match D {
+            0 => 1.0,
+            1 => self[0][0],
+            2..=4 => self.expand(0, (1 << D) - 1),
+            _ => self.eliminate(),
+        }
+    }
+
+    /// The determinant by Gaussian elimination: the matrix is reduced to upper triangular form,
+    /// whose determinant is the product of its diagonal, flipping the sign for each row swap.
+    fn eliminate(&self) -> f64 {
+        let mut m = self.data;
+        let mut det = 1.0;
+
+        for col in 0..D {
+            // dividing by the largest available pivot keeps rounding errors small
+            let pivot = (col..D)
+                .max_by(|&a, &b| m[a][col].abs().total_cmp(&m[b][col].abs()))
+                .unwrap_or(col);
+            if m[pivot][col] == 0.0 {
+                return 0.0;
+            }
+            if pivot != col {
+                m.swap(pivot, col);
+                det = -det;
+            }
+            det *= m[col][col];
+
+            let pivot_row = m[col];
+            for row in m.iter_mut().skip(col + 1) {
+                let factor = row[col] / pivot_row[col];
+                for (x, p) in row.iter_mut().zip(pivot_row.iter()).skip(col) {
+                    *x -= factor * p;
+                }
+            }
+        }
+
+        det
+    }
+
+    /// The determinant of the submatrix made of rows `row..D` and the columns set in `cols`,
+    /// expanded along its first row. Skipping columns with a bitmask avoids allocating
+    /// submatrices, whose dimension can't be expressed with const generics anyway.
+    fn expand(&self, row: usize, cols: u64) -> f64 {
+        let active = (0..D).filter(|c| cols & (1 << c) != 0);
+
+        if cols.count_ones() == 2 {
+            let (c0, c1) = {
+                let mut a = active;
+                (a.next().unwrap(), a.next().unwrap())
+            };
+
+            return self[row][c0] * self[row + 1][c1] - self[row][c1] * self[row + 1][c0];
+        }
+
+        active
+            .enumerate()
+            .map(|(i, c)| {
+                let minor = self.expand(row + 1, cols & !(1 << c));
+                let cofactor = if i % 2 == 0 { minor } else { -minor };
+
+                self[row][c] * cofactor
+            })
+            .sum()
+    }
 }
 
 impl Matrix<4> {
@@ -132,17 +224,117 @@ impl Matrix<4> {
         }
     }
 
+    /// Interpolates between two transforms for animation, with `t = 0.0` giving `a` and `t = 1.0`
+    /// giving `b`. Each transform is split into translation, rotation, and scaling, which are
+    /// interpolated separately (the rotation along the shortest arc, using quaternions) and put
+    /// back together. Unlike blending the matrices entry by entry, a spinning object keeps its
+    /// shape throughout. Shearing can't be represented this way and is lost.
+    ///
+    /// A transform that scales some axis to zero (e.g. an object growing from nothing) has no
+    /// well-defined rotation, so the other transform's rotation is used throughout.
+    pub fn lerp(a: &Self, b: &Self, t: f64) -> Self {
+        let (ta, qa, sa) = a.decompose();
+        let (tb, qb, sb) = b.decompose();
+        let (qa, qb) = match (qa, qb) {
+            (Some(qa), Some(qb)) => (qa, qb),
+            (Some(q), None) | (None, Some(q)) => (q, q),
+            (None, None) => (IDENTITY_QUATERNION, IDENTITY_QUATERNION),
+        };
+        let mix = |x: f64, y: f64| x + (y - x) * t;
+
+        let translation = Self::translation(mix(ta.0, tb.0), mix(ta.1, tb.1), mix(ta.2, tb.2));
+        let rotation = quaternion_to_matrix(slerp(qa, qb, t));
+        let scaling = Self::scaling(mix(sa.0, sb.0), mix(sa.1, sb.1), mix(sa.2, sb.2));
+
+        translation * rotation * scaling
+    }
+
+    /// Splits an affine transform into its translation, rotation (as a unit quaternion), and
+    /// scale, such that the transform is `translation * rotation * scaling`. The rotation is `None`
+    /// if any axis is scaled to zero, since it can't be recovered then.
+    fn decompose(&self) -> (Vec3, Option<[f64; 4]>, Vec3) {
+        let translation = Vec3(self[0][3], self[1][3], self[2][3]);
+
+        let column = |j: usize| Vec3(self[0][j], self[1][j], self[2][j]);
+        let mut scale = [
+            column(0).magnitude(),
+            column(1).magnitude(),
+            column(2).magnitude(),
+        ];
+        if scale.contains(&0.0) {
+            return (translation, None, Vec3(scale[0], scale[1], scale[2]));
+        }
+        // a mirrored transform can't be a rotation, so move the mirroring into the scale
+        if column(0).cross(&column(1)).dot(&column(2)) < 0.0 {
+            scale[0] = -scale[0];
+        }
+
+        let mut rotation = [[0.0; 3]; 3];
+        for (j, s) in scale.iter().enumerate() {
+            for (i, row) in rotation.iter_mut().enumerate() {
+                row[j] = self[i][j] / s;
+            }
+        }
+
+        (
+            translation,
+            Some(matrix_to_quaternion(&rotation)),
+            Vec3(scale[0], scale[1], scale[2]),
+        )
+    }
+
+    /// Splits an affine transform into a translation, rotation angles around `x`, `y`, and `z`,
+    /// and a scale, such that the transform is
+    /// `translation * rotation_z * rotation_y * rotation_x * scaling`. Returns `None` if the
+    /// transform can't be written that way, e.g. because it has shearing.
+    pub fn decompose_euler(&self) -> Option<(Vec3, Vec3, Vec3)> {
+        if self[3] != [0.0, 0.0, 0.0, 1.0] {
+            return None;
+        }
+
+        let (translation, q, scale) = self.decompose();
+        let r = quaternion_to_matrix(q.unwrap_or(IDENTITY_QUATERNION));
+
+        let y = (-r[2][0]).clamp(-1.0, 1.0).asin();
+        let (x, z) = if r[2][0].abs() < 1.0 - 1e-12 {
+            (r[2][1].atan2(r[2][2]), r[1][0].atan2(r[0][0]))
+        } else {
+            // gimbal lock: x and z rotate around the same axis, so put it all in x
+            ((-r[1][2]).atan2(r[1][1]), 0.0)
+        };
+
+        let recomposed = Self::translation(translation.0, translation.1, translation.2)
+            * Self::rotation(Axis::Z, z)
+            * Self::rotation(Axis::Y, y)
+            * Self::rotation(Axis::X, x)
+            * Self::scaling(scale.0, scale.1, scale.2);
+        let size = self
+            .data
+            .iter()
+            .flatten()
+            .fold(1.0_f64, |m, v| m.max(v.abs()));
+        let matches = self
+            .data
+            .iter()
+            .flatten()
+            .zip(recomposed.data.iter().flatten())
+            .all(|(a, b)| (a - b).abs() < 1e-9 * size);
+
+        matches.then_some((translation, Vec3(x, y, z), scale))
+    }
+
     /// Computes the inverse of the matrix.
     pub fn inverse(&self) -> Option<Self> {
         if !self.is_invertible() {
             return None;
         }
         let mut inverse = Matrix::default();
+        let det = self.determinant();
 
         for row in 0..self.data.len() {
             for col in 0..self.data.len() {
                 let c = self.cofactor(row, col);
-                inverse[col][row] = c / self.determinant();
+                inverse[col][row] = c / det;
             }
         }
 
@@ -154,16 +346,6 @@ impl Matrix<4> {
         self.determinant() != 0.0
     }
 
-    /// Computes the determinant of the matrix.
-    fn determinant(&self) -> f64 {
-        let c1 = self[0][0] * self.cofactor(0, 0);
-        let c2 = self[0][1] * self.cofactor(0, 1);
-        let c3 = self[0][2] * self.cofactor(0, 2);
-        let c4 = self[0][3] * self.cofactor(0, 3);
-
-        c1 + c2 + c3 + c4
-    }
-
     /// Computes the cofactor of a 4x4 matrix for the given row/column.
     fn cofactor(&self, row: usize, col: usize) -> f64 {
         let sgn = (row + col) % 2;
@@ -207,16 +389,8 @@ impl Matrix<4> {
     }
 }
 
+#[cfg(test)]
 impl Matrix<3> {
-    /// Computes the determinant of a 3x3 matrix.
-    pub fn determinant(&self) -> f64 {
-        let c1 = self.cofactor(0, 0);
-        let c2 = self.cofactor(0, 1);
-        let c3 = self.cofactor(0, 2);
-
-        self[0][0] * c1 + self[0][1] * c2 + self[0][2] * c3
-    }
-
     /// Computes the cofactor of a 3x3 matrix for the given row and column.
     fn cofactor(&self, row: usize, col: usize) -> f64 {
         let sgn = (row + col) % 2;
@@ -260,13 +434,105 @@ impl Matrix<3> {
     }
 }
 
-impl Matrix<2> {
-    /// Computes the determinant of a 2x2 matrix.
-    fn determinant(&self) -> f64 {
-        self[0][0] * self[1][1] - self[0][1] * self[1][0]
+/// Converts a rotation matrix to a unit quaternion `[w, x, y, z]`, branching on the largest
+/// component to keep the division well-conditioned.
+fn matrix_to_quaternion(m: &[[f64; 3]; 3]) -> [f64; 4] {
+    let trace = m[0][0] + m[1][1] + m[2][2];
+
+    if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        [
+            0.25 * s,
+            (m[2][1] - m[1][2]) / s,
+            (m[0][2] - m[2][0]) / s,
+            (m[1][0] - m[0][1]) / s,
+        ]
+    } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+        let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+        [
+            (m[2][1] - m[1][2]) / s,
+            0.25 * s,
+            (m[0][1] + m[1][0]) / s,
+            (m[0][2] + m[2][0]) / s,
+        ]
+    } else if m[1][1] > m[2][2] {
+        let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+        [
+            (m[0][2] - m[2][0]) / s,
+            (m[0][1] + m[1][0]) / s,
+            0.25 * s,
+            (m[1][2] + m[2][1]) / s,
+        ]
+    } else {
+        let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+        [
+            (m[1][0] - m[0][1]) / s,
+            (m[0][2] + m[2][0]) / s,
+            (m[1][2] + m[2][1]) / s,
+            0.25 * s,
+        ]
     }
 }
 
+/// The quaternion `[w, x, y, z]` for no rotation at all.
+const IDENTITY_QUATERNION: [f64; 4] = [1.0, 0.0, 0.0, 0.0];
+
+/// Converts a unit quaternion `[w, x, y, z]` to a 4x4 rotation matrix.
+fn quaternion_to_matrix(q: [f64; 4]) -> Matrix<4> {
+    let [w, x, y, z] = q;
+
+    Matrix {
+        data: [
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+                0.0,
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+                0.0,
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+    }
+}
+
+/// Spherical linear interpolation between two unit quaternions, along the shorter arc.
+fn slerp(q0: [f64; 4], q1: [f64; 4], t: f64) -> [f64; 4] {
+    let mut dot = (0..4).map(|i| q0[i] * q1[i]).sum::<f64>();
+    let mut q1 = q1;
+    // q and -q are the same rotation; pick the one that's closer
+    if dot < 0.0 {
+        q1 = q1.map(|c| -c);
+        dot = -dot;
+    }
+
+    let q = if dot > 0.9995 {
+        // nearly identical rotations: a normalized linear blend is accurate and avoids dividing
+        // by sin(θ) ≈ 0
+        [0, 1, 2, 3].map(|i| q0[i] + (q1[i] - q0[i]) * t)
+    } else {
+        let theta = dot.acos();
+        let (w0, w1) = (
+            ((1.0 - t) * theta).sin() / theta.sin(),
+            (t * theta).sin() / theta.sin(),
+        );
+        [0, 1, 2, 3].map(|i| q0[i] * w0 + q1[i] * w1)
+    };
+
+    let norm = q.iter().map(|c| c * c).sum::<f64>().sqrt();
+    q.map(|c| c / norm)
+}
+
 impl<const D: usize> ops::Index<usize> for Matrix<D> {
     type Output = [f64; D];
 
@@ -355,6 +621,34 @@ impl ops::Neg for Matrix<4> {
     }
 }
 
+impl<const D: usize> ops::Mul<f64> for Matrix<D> {
+    type Output = Self;
+    fn mul(self, rhs: f64) -> Self::Output {
+        let mut res = Self::default();
+        for i in 0..D {
+            for j in 0..D {
+                res[i][j] = self[i][j] * rhs;
+            }
+        }
+
+        res
+    }
+}
+
+impl<const D: usize> ops::Add for Matrix<D> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut res = Self::default();
+        for i in 0..D {
+            for j in 0..D {
+                res[i][j] = self[i][j] + rhs[i][j];
+            }
+        }
+
+        res
+    }
+}
+
 impl<const D: usize> Default for Matrix<D> {
     fn default() -> Self {
         Self {
@@ -712,6 +1006,73 @@ mod matrix_tests {
         assert_eq!(a.determinant(), -4071.0);
     }
 
+    #[test]
+    fn determinant_of_4x4_matches_cofactor_expansion() {
+        let a = Matrix {
+            data: [
+                [-2.0, -8.0, 3.0, 5.0],
+                [-3.0, 1.0, 7.0, 3.0],
+                [1.0, 2.0, -9.0, 6.0],
+                [-6.0, 7.0, 7.0, -9.0],
+            ],
+        };
+        let expanded = (0..4).map(|j| a[0][j] * a.cofactor(0, j)).sum::<f64>();
+
+        assert_eq!(a.cofactor(0, 0), 690.0);
+        assert_eq!(a.determinant(), expanded);
+    }
+
+    #[test]
+    fn determinant_of_5x5() {
+        let a = Matrix {
+            data: [
+                [2.0, -1.0, 0.0, 3.0, 1.0],
+                [1.0, 4.0, -2.0, 0.0, 5.0],
+                [0.0, 3.0, 1.0, -1.0, 2.0],
+                [-3.0, 0.0, 2.0, 4.0, -1.0],
+                [1.0, 2.0, -1.0, 0.0, 3.0],
+            ],
+        };
+
+        assert!((a.determinant() - 44.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn determinant_of_large_matrices_is_fast() {
+        // an upper triangular matrix, with its rows reversed so that every column needs a pivot
+        let mut a = Matrix::<24>::default();
+        for i in 0..24 {
+            for j in i..24 {
+                a[23 - i][j] = if i == j { 2.0 } else { 1.0 };
+            }
+        }
+
+        // 12 row swaps to undo the reversal
+        assert!((a.determinant() - 2f64.powi(24)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn determinant_of_singular_5x5() {
+        // the second row is twice the first
+        let a = Matrix {
+            data: [
+                [1.0, 2.0, 3.0, 4.0, 5.0],
+                [2.0, 4.0, 6.0, 8.0, 10.0],
+                [0.0, 1.0, 0.0, 1.0, 0.0],
+                [3.0, -1.0, 2.0, 0.0, 1.0],
+                [5.0, 5.0, 5.0, 5.0, 5.0],
+            ],
+        };
+
+        assert_eq!(a.determinant(), 0.0);
+    }
+
+    #[test]
+    fn determinant_of_tiny_matrices() {
+        assert_eq!(Matrix::<1> { data: [[-3.0]] }.determinant(), -3.0);
+        assert_eq!(Matrix::<0>::identity().determinant(), 1.0);
+    }
+
     #[test]
     fn determinant_of_3x3() {
         let a = Matrix {
@@ -896,6 +1257,125 @@ mod matrix_tests {
         assert!(a != c);
     }
 
+    fn assert_matrix_approx_eq(a: &Matrix<4>, b: &Matrix<4>) {
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((a[i][j] - b[i][j]).abs() < 1e-9, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn lerp_returns_the_endpoints() {
+        let a = Matrix::translation(1.0, 2.0, 3.0)
+            * Matrix::rotation(Axis::Y, 0.3)
+            * Matrix::scaling(2.0, 1.0, 0.5);
+        let b = Matrix::translation(-4.0, 0.0, 1.0)
+            * Matrix::rotation(Axis::X, 2.5)
+            * Matrix::scaling(1.0, 3.0, 1.0);
+
+        assert_matrix_approx_eq(&Matrix::lerp(&a, &b, 0.0), &a);
+        assert_matrix_approx_eq(&Matrix::lerp(&a, &b, 1.0), &b);
+    }
+
+    #[test]
+    fn lerp_between_rotations_gives_the_half_angle() {
+        let a = Matrix::rotation(Axis::Z, 0.0);
+        let b = Matrix::rotation(Axis::Z, PI / 2.0);
+
+        assert_matrix_approx_eq(
+            &Matrix::lerp(&a, &b, 0.5),
+            &Matrix::rotation(Axis::Z, PI / 4.0),
+        );
+    }
+
+    #[test]
+    fn lerp_interpolates_translation_rotation_and_scale_separately() {
+        let a = Matrix::translation(0.0, 0.0, 0.0) * Matrix::scaling(1.0, 1.0, 1.0);
+        let b = Matrix::translation(4.0, 2.0, 0.0)
+            * Matrix::rotation(Axis::Y, PI / 2.0)
+            * Matrix::scaling(3.0, 3.0, 3.0);
+        let expected = Matrix::translation(2.0, 1.0, 0.0)
+            * Matrix::rotation(Axis::Y, PI / 4.0)
+            * Matrix::scaling(2.0, 2.0, 2.0);
+
+        assert_matrix_approx_eq(&Matrix::lerp(&a, &b, 0.5), &expected);
+    }
+
+    #[test]
+    fn lerp_from_a_zero_scale_uses_the_other_rotation() {
+        let a = Matrix::scaling(0.0, 0.0, 0.0);
+        let b = Matrix::translation(2.0, 0.0, 0.0)
+            * Matrix::rotation(Axis::Y, PI / 3.0)
+            * Matrix::scaling(2.0, 2.0, 2.0);
+        let expected = Matrix::translation(1.0, 0.0, 0.0)
+            * Matrix::rotation(Axis::Y, PI / 3.0)
+            * Matrix::scaling(1.0, 1.0, 1.0);
+
+        let halfway = Matrix::lerp(&a, &b, 0.5);
+        assert!(halfway.data.iter().flatten().all(|v| v.is_finite()));
+        assert_matrix_approx_eq(&halfway, &expected);
+        assert_matrix_approx_eq(&Matrix::lerp(&a, &b, 0.0), &a);
+        assert_matrix_approx_eq(&Matrix::lerp(&b, &a, 1.0), &a);
+    }
+
+    #[test]
+    fn decomposing_into_euler_angles() {
+        let t = Matrix::translation(1.0, -2.0, 3.0)
+            * Matrix::rotation(Axis::Z, 0.3)
+            * Matrix::rotation(Axis::Y, -1.1)
+            * Matrix::rotation(Axis::X, 2.0)
+            * Matrix::scaling(2.0, 0.5, 4.0);
+        let (translation, angles, scale) = t.decompose_euler().unwrap();
+
+        assert!(translation.approx_eq(&Vec3(1.0, -2.0, 3.0), 1e-9));
+        assert!(scale.approx_eq(&Vec3(2.0, 0.5, 4.0), 1e-9));
+        assert_matrix_approx_eq(
+            &(Matrix::translation(translation.0, translation.1, translation.2)
+                * Matrix::rotation(Axis::Z, angles.2)
+                * Matrix::rotation(Axis::Y, angles.1)
+                * Matrix::rotation(Axis::X, angles.0)
+                * Matrix::scaling(scale.0, scale.1, scale.2)),
+            &t,
+        );
+    }
+
+    #[test]
+    fn sheared_transforms_have_no_euler_decomposition() {
+        let t = Matrix::shear(1.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+
+        assert_eq!(t.decompose_euler(), None);
+    }
+
+    #[test]
+    fn matrices_can_be_scaled() {
+        let a = Matrix {
+            data: [[1.0, -2.0, 3.0], [0.5, 0.0, -4.0], [7.0, 8.0, 9.0]],
+        };
+        let expected = Matrix {
+            data: [[2.0, -4.0, 6.0], [1.0, 0.0, -8.0], [14.0, 16.0, 18.0]],
+        };
+
+        assert_eq!(a * 2.0, expected);
+    }
+
+    #[test]
+    fn matrices_can_be_added() {
+        let a = Matrix::translation(1.0, 2.0, 3.0);
+        let b = Matrix::scaling(2.0, 3.0, 4.0);
+        let expected = Matrix {
+            data: [
+                [3.0, 0.0, 0.0, 1.0],
+                [0.0, 4.0, 0.0, 2.0],
+                [0.0, 0.0, 5.0, 3.0],
+                [0.0, 0.0, 0.0, 2.0],
+            ],
+        };
+
+        assert_eq!(a + b, expected);
+        assert_eq!(a * 0.5 + a * 0.5, a);
+    }
+
     #[test]
     fn matrix_product_works() {
         let a = Matrix {
@@ -943,4 +1423,21 @@ mod matrix_tests {
 
         assert_eq!(a * b, prod);
     }
+
+    #[test]
+    fn matrices_round_trip_through_rows_and_columns() {
+        let rows = [
+            [1.0, 2.0, 3.0, 4.0],
+            [5.5, 6.5, 7.5, 8.5],
+            [9.0, 8.0, 7.0, 6.0],
+            [-5.0, -4.0, -3.0, -2.0],
+        ];
+        let m = Matrix::from_rows(rows);
+        let columns = [0, 1, 2, 3].map(|j| m.column(j));
+
+        assert_eq!([0, 1, 2, 3].map(|i| m.row(i)), rows);
+        assert_eq!(m.column(1), [2.0, 6.5, 8.0, -4.0]);
+        assert_eq!(Matrix::from_columns(columns), m);
+        assert_eq!(Matrix::from_columns(rows), m.transpose());
+    }
 }
@@ -0,0 +1,163 @@
+//! Deterministic gradient noise for procedural patterns.
+//!
+//! An implementation of Ken Perlin's "improved" noise. The permutation table is shuffled with a
+//! small seeded generator, so the same seed always produces the same noise.
+use super::{Point, Tuple};
+
+/// The seed used by [Perlin::default].
+pub const DEFAULT_SEED: u64 = 0x5eed;
+
+/// A seeded 3D Perlin noise generator. Noise values lie (approximately) in `[-1.0, 1.0]` and are
+/// exactly 0.0 at integer lattice points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Perlin {
+    perm: Box<[u8; 512]>,
+}
+
+impl Perlin {
+    /// Creates a new generator whose permutation table is shuffled using `seed`.
+    pub fn new(seed: u64) -> Self {
+        let mut table = [0u8; 256];
+        for (i, t) in table.iter_mut().enumerate() {
+            *t = i as u8;
+        }
+
+        // Fisher-Yates shuffle driven by splitmix64
+        let mut state = seed;
+        for i in (1..table.len()).rev() {
+            let j = (splitmix64(&mut state) % (i as u64 + 1)) as usize;
+            table.swap(i, j);
+        }
+
+        let mut perm = Box::new([0u8; 512]);
+        for (i, p) in perm.iter_mut().enumerate() {
+            *p = table[i % 256];
+        }
+
+        Self { perm }
+    }
+
+    /// Samples the noise at the given point.
+    pub fn noise(&self, pt: &Point) -> f64 {
+        let (x, y, z) = (pt.x(), pt.y(), pt.z());
+
+        // unit cube containing the point
+        let xi = x.floor() as i64 & 255;
+        let yi = y.floor() as i64 & 255;
+        let zi = z.floor() as i64 & 255;
+
+        // relative position of the point in the cube
+        let x = x - x.floor();
+        let y = y - y.floor();
+        let z = z - z.floor();
+
+        let u = fade(x);
+        let v = fade(y);
+        let w = fade(z);
+
+        let p = |i: i64| self.perm[i as usize] as i64;
+        let a = p(xi) + yi;
+        let aa = p(a) + zi;
+        let ab = p(a + 1) + zi;
+        let b = p(xi + 1) + yi;
+        let ba = p(b) + zi;
+        let bb = p(b + 1) + zi;
+
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, grad(p(aa), x, y, z), grad(p(ba), x - 1.0, y, z)),
+                lerp(
+                    u,
+                    grad(p(ab), x, y - 1.0, z),
+                    grad(p(bb), x - 1.0, y - 1.0, z),
+                ),
+            ),
+            lerp(
+                v,
+                lerp(
+                    u,
+                    grad(p(aa + 1), x, y, z - 1.0),
+                    grad(p(ba + 1), x - 1.0, y, z - 1.0),
+                ),
+                lerp(
+                    u,
+                    grad(p(ab + 1), x, y - 1.0, z - 1.0),
+                    grad(p(bb + 1), x - 1.0, y - 1.0, z - 1.0),
+                ),
+            ),
+        )
+    }
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+
+    z ^ (z >> 31)
+}
+
+/// Smoothstep-like easing curve `6t^5 - 15t^4 + 10t^3`.
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+/// Picks one of 12 gradient directions from the low bits of the hash and dots it with `(x, y, z)`.
+fn grad(hash: i64, x: f64, y: f64, z: f64) -> f64 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+#[cfg(test)]
+mod noise_tests {
+    use super::*;
+
+    #[test]
+    fn noise_is_zero_on_lattice_points() {
+        let perlin = Perlin::default();
+
+        assert_eq!(perlin.noise(&Point(0.0, 0.0, 0.0)), 0.0);
+        assert_eq!(perlin.noise(&Point(3.0, -2.0, 7.0)), 0.0);
+    }
+
+    #[test]
+    fn noise_is_reproducible_for_a_seed() {
+        let pt = Point(0.3, 1.7, -2.2);
+
+        assert_eq!(Perlin::new(42).noise(&pt), Perlin::new(42).noise(&pt));
+        assert_ne!(Perlin::new(42).noise(&pt), Perlin::new(43).noise(&pt));
+    }
+
+    #[test]
+    fn noise_stays_in_range() {
+        let perlin = Perlin::default();
+
+        for i in 0..1000 {
+            let f = i as f64 * 0.137;
+            let n = perlin.noise(&Point(f, f * 0.5, -f * 1.3));
+            assert!((-1.0..=1.0).contains(&n));
+        }
+    }
+}
@@ -6,7 +6,25 @@ use std::ops;
 #[derive(Debug, PartialEq, Clone, Copy, Default)]
 pub struct Point(pub f64, pub f64, pub f64);
 
-impl Point {}
+impl Point {
+    /// Checks whether every coordinate is within `eps` of the other point's.
+    pub fn approx_eq(&self, other: &Point, eps: f64) -> bool {
+        (self.x() - other.x()).abs() <= eps
+            && (self.y() - other.y()).abs() <= eps
+            && (self.z() - other.z()).abs() <= eps
+    }
+
+    /// Linearly interpolates from `a` (at `t = 0.0`) to `b` (at `t = 1.0`). Values of `t` outside
+    /// `[0.0, 1.0]` extrapolate along the line through both points.
+    pub fn lerp(a: Point, b: Point, t: f64) -> Point {
+        a + (b - a) * t
+    }
+
+    /// The point halfway between `a` and `b`.
+    pub fn midpoint(a: Point, b: Point) -> Point {
+        Point::lerp(a, b, 0.5)
+    }
+}
 
 impl Tuple for Point {
     fn new(x: f64, y: f64, z: f64) -> Self {
@@ -109,4 +127,29 @@ mod point_tests {
         let f = 2.0;
         assert_eq!(p / f, Point(0.5, -1.0, 1.5));
     }
+
+    #[test]
+    fn lerping_between_points() {
+        let (a, b) = (Point(1.0, -2.0, 3.0), Point(5.0, 2.0, -1.0));
+
+        assert_eq!(Point::lerp(a, b, 0.0), a);
+        assert_eq!(Point::lerp(a, b, 1.0), b);
+        assert_eq!(Point::lerp(a, b, 0.5), Point::midpoint(a, b));
+        assert_eq!(Point::lerp(a, b, 0.25), Point(2.0, -1.0, 2.0));
+    }
+
+    #[test]
+    fn midpoint_of_two_points() {
+        let mid = Point::midpoint(Point(0.0, 0.0, 0.0), Point(2.0, 4.0, 6.0));
+
+        assert_eq!(mid, Point(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn approximately_equal_points() {
+        let p = Point(1.0, -2.0, 3.0);
+
+        assert!(p.approx_eq(&Point(1.0 + 1e-7, -2.0 - 1e-7, 3.0), 1e-6));
+        assert!(!p.approx_eq(&Point(1.0, -2.0, 3.1), 1e-6));
+    }
 }
@@ -0,0 +1,120 @@
+//! Mappings from the unit square to other domains, for turning uniform random numbers into
+//! uniformly distributed sample points.
+use std::f64::consts::{FRAC_PI_4, PI};
+
+use super::Vec3;
+
+/// The golden angle, `π(3 - √5)`. Stepping around a circle by it spreads samples evenly without
+/// any randomness.
+pub(crate) const GOLDEN_ANGLE: f64 = PI * (3.0 - 2.236_067_977_499_79);
+
+/// Maps `(u, v)` in `[0, 1)²` onto the unit disk using Shirley and Chiu's concentric mapping.
+/// Unlike the naive polar mapping, it keeps the samples evenly spread (equal areas in the square
+/// map to equal areas on the disk) without bunching them up at the center.
+pub fn concentric_disk(u: f64, v: f64) -> (f64, f64) {
+    // remap to [-1, 1]²
+    let a = 2.0 * u - 1.0;
+    let b = 2.0 * v - 1.0;
+
+    if a == 0.0 && b == 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let (r, theta) = if a.abs() > b.abs() {
+        (a, FRAC_PI_4 * (b / a))
+    } else {
+        (b, 2.0 * FRAC_PI_4 - FRAC_PI_4 * (a / b))
+    };
+
+    (r * theta.cos(), r * theta.sin())
+}
+
+/// Maps `(u, v)` in `[0, 1)²` to a unit vector within `half_angle` radians of `axis` (which must
+/// be normalized), uniformly distributed over that cone of directions.
+pub fn uniform_cone(axis: &Vec3, half_angle: f64, u: f64, v: f64) -> Vec3 {
+    let cos_theta = 1.0 - u * (1.0 - half_angle.cos());
+    let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+    let phi = 2.0 * PI * v;
+    let (a, b) = orthonormal_basis(axis);
+
+    a * (phi.cos() * sin_theta) + b * (phi.sin() * sin_theta) + *axis * cos_theta
+}
+
+/// Two unit vectors perpendicular to `n` and to each other.
+pub fn orthonormal_basis(n: &Vec3) -> (Vec3, Vec3) {
+    let helper = if n.0.abs() < 0.9 {
+        Vec3(1.0, 0.0, 0.0)
+    } else {
+        Vec3(0.0, 1.0, 0.0)
+    };
+    let u = n.cross(&helper).normalize();
+    let v = n.cross(&u);
+
+    (u, v)
+}
+
+#[cfg(test)]
+mod sampling_tests {
+    use super::*;
+
+    /// An evenly spaced `n`×`n` grid of points on the unit square, mapped onto the disk.
+    fn grid_samples(n: usize) -> Vec<(f64, f64)> {
+        (0..n)
+            .flat_map(|i| (0..n).map(move |j| (i, j)))
+            .map(|(i, j)| concentric_disk((i as f64 + 0.5) / n as f64, (j as f64 + 0.5) / n as f64))
+            .collect()
+    }
+
+    #[test]
+    fn concentric_samples_lie_in_the_unit_disk() {
+        for (x, y) in grid_samples(64) {
+            assert!(x * x + y * y <= 1.0 + 1e-12);
+        }
+    }
+
+    #[test]
+    fn concentric_samples_are_uniform_over_the_disk() {
+        let samples = grid_samples(200);
+        let n = samples.len() as f64;
+
+        // for a uniform distribution, the fraction within radius r is r²...
+        for r in [0.25, 0.5, 0.75] {
+            let inside = samples
+                .iter()
+                .filter(|(x, y)| x * x + y * y < r * r)
+                .count();
+            assert!((inside as f64 / n - r * r).abs() < 0.01);
+        }
+
+        // ...and every quadrant gets the same share
+        for (sx, sy) in [(1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)] {
+            let count = samples
+                .iter()
+                .filter(|(x, y)| x * sx > 0.0 && y * sy > 0.0)
+                .count();
+            assert!((count as f64 / n - 0.25).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn cone_samples_stay_within_the_cone() {
+        let axis = Vec3(1.0, 2.0, -2.0).normalize();
+        let half_angle = 0.2;
+
+        for i in 0..16 {
+            for j in 0..16 {
+                let d = uniform_cone(&axis, half_angle, i as f64 / 16.0, j as f64 / 16.0);
+
+                assert!((d.magnitude() - 1.0).abs() < 1e-10);
+                assert!(d.dot(&axis) >= half_angle.cos() - 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn zero_width_cone_returns_the_axis() {
+        let axis = Vec3(0.0, 1.0, 0.0);
+
+        assert!((uniform_cone(&axis, 0.0, 0.3, 0.7) - axis).magnitude() < 1e-10);
+    }
+}
@@ -0,0 +1,108 @@
+//! Building transformation matrices one step at a time, in the order the steps are applied.
+use super::{Axis, Matrix};
+
+/// Composes a transformation from a sequence of steps, written in the order they happen to a
+/// point. Multiplying matrices by hand needs them in reverse:
+/// `Transform::new().rotate_x(r).scale(5.0, 5.0, 5.0).build()` is the same matrix as
+/// `Matrix::scaling(5.0, 5.0, 5.0) * Matrix::rotation(Axis::X, r)`.
+///
+/// # Example
+/// ```
+/// use rtc::math::{transform::Transform, Point};
+///
+/// let t = Transform::new()
+///     .scale(2.0, 2.0, 2.0)
+///     .translate(1.0, 0.0, 0.0)
+///     .build();
+///
+/// assert_eq!(t * Point(1.0, 1.0, 1.0), Point(3.0, 2.0, 2.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    matrix: Matrix<4>,
+}
+
+impl Transform {
+    /// Starts from the identity transformation.
+    pub fn new() -> Self {
+        Self {
+            matrix: Matrix::identity(),
+        }
+    }
+
+    /// Applies `m` after every step so far.
+    pub fn then(mut self, m: Matrix<4>) -> Self {
+        self.matrix = m * self.matrix;
+        self
+    }
+
+    /// Moves by `(x, y, z)`.
+    pub fn translate(self, x: f64, y: f64, z: f64) -> Self {
+        self.then(Matrix::translation(x, y, z))
+    }
+
+    /// Scales by `x`, `y`, and `z` along each axis, about the origin.
+    pub fn scale(self, x: f64, y: f64, z: f64) -> Self {
+        self.then(Matrix::scaling(x, y, z))
+    }
+
+    /// Rotates by `rads` radians about the x axis.
+    pub fn rotate_x(self, rads: f64) -> Self {
+        self.then(Matrix::rotation(Axis::X, rads))
+    }
+
+    /// Rotates by `rads` radians about the y axis.
+    pub fn rotate_y(self, rads: f64) -> Self {
+        self.then(Matrix::rotation(Axis::Y, rads))
+    }
+
+    /// Rotates by `rads` radians about the z axis.
+    pub fn rotate_z(self, rads: f64) -> Self {
+        self.then(Matrix::rotation(Axis::Z, rads))
+    }
+
+    /// See [Matrix::shear] for what each argument moves in proportion to what.
+    pub fn shear(self, xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Self {
+        self.then(Matrix::shear(xy, xz, yx, yz, zx, zy))
+    }
+
+    /// The composed transformation matrix.
+    pub fn build(self) -> Matrix<4> {
+        self.matrix
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod transform_tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::math::Point;
+
+    #[test]
+    fn steps_are_applied_in_the_order_written() {
+        let t = Transform::new()
+            .rotate_x(PI / 2.0)
+            .scale(5.0, 5.0, 5.0)
+            .translate(10.0, 5.0, 7.0)
+            .build();
+        let manual = Matrix::translation(10.0, 5.0, 7.0)
+            * Matrix::scaling(5.0, 5.0, 5.0)
+            * Matrix::rotation(Axis::X, PI / 2.0);
+
+        assert_eq!(t, manual);
+        assert_eq!(t * Point(1.0, 0.0, 1.0), Point(15.0, 0.0, 7.0));
+    }
+
+    #[test]
+    fn empty_transform_is_the_identity() {
+        assert_eq!(Transform::new().build(), Matrix::identity());
+        assert_eq!(Transform::default(), Transform::new());
+    }
+}
@@ -9,9 +9,7 @@ pub struct Vec3(pub f64, pub f64, pub f64);
 impl Vec3 {
     /// Computes the magnitude of a vector.
     pub fn magnitude(&self) -> f64 {
-        f64::sqrt(
-            self.x() * self.x() + self.y() * self.y() + self.z() * self.z() + self.w() * self.w(),
-        )
+        Tuple::magnitude(self)
     }
 
     /// Normalizes a vector, producing a unit vector.
@@ -21,7 +19,7 @@ impl Vec3 {
 
     /// Computes the dot product of two vectors.
     pub fn dot(&self, other: &Vec3) -> f64 {
-        self.x() * other.x() + self.y() * other.y() + self.z() * other.z() + self.w() * other.w()
+        Tuple::dot(self, other)
     }
 
     /// Computes the cross product of two vectors.
@@ -33,8 +31,34 @@ impl Vec3 {
         )
     }
 
+    /// Reflects the vector about the normal `other`, which must already be a unit vector. This
+    /// is [Vec3::reflect_unchecked]; shading always passes normalized normals, so it skips the
+    /// extra work. Use [Vec3::reflect_normalized] if the normal might not be unit length.
     pub fn reflect(&self, other: &Vec3) -> Self {
-        *self - other * 2.0 * self.dot(other)
+        self.reflect_unchecked(other)
+    }
+
+    /// Reflects the vector about `normal`, assuming without checking that it's a unit vector. A
+    /// longer or shorter normal gives a vector that isn't a reflection at all.
+    pub fn reflect_unchecked(&self, normal: &Vec3) -> Self {
+        *self - normal * 2.0 * self.dot(normal)
+    }
+
+    /// Reflects the vector about `normal`, normalizing it first so its length doesn't matter.
+    pub fn reflect_normalized(&self, normal: &Vec3) -> Self {
+        self.reflect_unchecked(&normal.normalize())
+    }
+
+    /// Linearly interpolates from `a` (at `t = 0.0`) to `b` (at `t = 1.0`).
+    pub fn lerp(a: Vec3, b: Vec3, t: f64) -> Vec3 {
+        a + (b - a) * t
+    }
+
+    /// Checks whether every component is within `eps` of the other vector's.
+    pub fn approx_eq(&self, other: &Vec3, eps: f64) -> bool {
+        (self.x() - other.x()).abs() <= eps
+            && (self.y() - other.y()).abs() <= eps
+            && (self.z() - other.z()).abs() <= eps
     }
 }
 
@@ -141,6 +165,15 @@ impl ops::Index<usize> for Vec3 {
 mod vec_tests {
     use super::*;
 
+    #[test]
+    fn lerping_between_vectors() {
+        let (a, b) = (Vec3(1.0, 0.0, -2.0), Vec3(3.0, 4.0, 2.0));
+
+        assert_eq!(Vec3::lerp(a, b, 0.0), a);
+        assert_eq!(Vec3::lerp(a, b, 1.0), b);
+        assert_eq!(Vec3::lerp(a, b, 0.5), Vec3(2.0, 2.0, 0.0));
+    }
+
     #[test]
     fn reflecting_at_45_degs() {
         let v = Vec3(1.0, -1.0, 0.0);
@@ -150,6 +183,16 @@ mod vec_tests {
         assert_eq!(r, Vec3(1.0, 1.0, 0.0));
     }
 
+    #[test]
+    fn reflecting_off_a_non_unit_normal() {
+        let v = Vec3(1.0, -1.0, 0.0);
+        let n = Vec3(0.0, 3.0, 0.0);
+
+        assert_eq!(v.reflect_normalized(&n), Vec3(1.0, 1.0, 0.0));
+        assert_ne!(v.reflect_unchecked(&n), Vec3(1.0, 1.0, 0.0));
+        assert_eq!(v.reflect(&n), v.reflect_unchecked(&n));
+    }
+
     #[test]
     fn reflecting_off_slanted_surface() {
         let v = Vec3(0.0, -1.0, 0.0);
@@ -228,4 +271,13 @@ mod vec_tests {
         assert_eq!(v1.cross(&v2), Vec3(-1.0, 2.0, -1.0));
         assert_eq!(v2.cross(&v1), Vec3(1.0, -2.0, 1.0));
     }
+
+    #[test]
+    fn approximately_equal_vectors() {
+        let v = Vec3(0.0, 0.97014, -0.24254);
+        let n = Vec3(0.0, 1.0, -0.25).normalize();
+
+        assert!(v.approx_eq(&n, 1e-4));
+        assert!(!v.approx_eq(&Vec3(0.0, 1.0, 0.0), 1e-4));
+    }
 }
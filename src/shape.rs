@@ -1,20 +1,47 @@
 //! An enumeration of intersectable shapes.
+use std::sync::Arc;
+
 use crate::{
-    core::{material::Material, Intersectable, IntersectionList},
-    math::Matrix,
+    core::{material::Material, Intersectable, Intersection, IntersectionList, Ray},
+    math::{Matrix, Point, Tuple, Vec3},
 };
 
 pub mod plane;
+pub mod portal;
+pub mod quad;
 pub mod sphere;
 
 pub use plane::Plane;
+pub use portal::Portal;
+pub use quad::Quad;
 pub use sphere::Sphere;
 
 /// A catalogue of shapes to render.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Clone)]
 pub enum Shape {
     Sphere(Sphere),
     Plane(Plane),
+    Quad(Quad),
+    Portal(Portal),
+
+    /// Geometry defined outside this crate. It works in world space: it's handed world-space rays
+    /// and points, and handles its own transform. The `object` of each intersection it returns is
+    /// replaced by this shape, so any shape will do there. Two custom shapes are only equal if
+    /// they share the same allocation.
+    Custom(Arc<dyn Intersectable + Send + Sync>),
+}
+
+impl PartialEq for Shape {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Sphere(a), Self::Sphere(b)) => a == b,
+            (Self::Plane(a), Self::Plane(b)) => a == b,
+            (Self::Quad(a), Self::Quad(b)) => a == b,
+            (Self::Portal(a), Self::Portal(b)) => a == b,
+            (Self::Custom(a), Self::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
 }
 
 impl Shape {
@@ -22,6 +49,45 @@ impl Shape {
         match *self {
             Self::Sphere(ref sphere) => sphere.material.clone(),
             Self::Plane(ref plane) => plane.material.clone(),
+            Self::Quad(ref quad) => quad.material.clone(),
+            // portals don't have a surface of their own
+            Self::Portal(_) => Material::default(),
+            Self::Custom(ref custom) => custom.material(),
+        }
+    }
+
+    /// Whether the shape blocks light from reaching other surfaces. Portals never do, and other
+    /// shapes can opt out through their [material](Material::with_casts_shadow).
+    pub(crate) fn casts_shadow(&self) -> bool {
+        match *self {
+            Self::Sphere(ref sphere) => sphere.material.casts_shadow,
+            Self::Plane(ref plane) => plane.material.casts_shadow,
+            Self::Quad(ref quad) => quad.material.casts_shadow,
+            Self::Portal(_) => false,
+            Self::Custom(ref custom) => custom.material().casts_shadow,
+        }
+    }
+
+    /// The shape's name, if it was given one.
+    pub fn name(&self) -> Option<&str> {
+        match *self {
+            Self::Sphere(ref sphere) => sphere.name.as_deref(),
+            Self::Plane(ref plane) => plane.name.as_deref(),
+            Self::Quad(ref quad) => quad.name.as_deref(),
+            Self::Portal(ref portal) => portal.name.as_deref(),
+            Self::Custom(_) => None,
+        }
+    }
+
+    /// Names the shape, whichever kind it is. See [Shape::name]. Custom shapes can't be named and
+    /// are returned unchanged.
+    pub fn with_name(self, name: &str) -> Self {
+        match self {
+            Self::Sphere(sphere) => sphere.with_name(name).into(),
+            Self::Plane(plane) => plane.with_name(name).into(),
+            Self::Quad(quad) => quad.with_name(name).into(),
+            Self::Portal(portal) => portal.with_name(name).into(),
+            Self::Custom(_) => self,
         }
     }
 
@@ -30,22 +96,136 @@ impl Shape {
         match *self {
             Self::Sphere(ref sphere) => sphere.transform,
             Self::Plane(ref plane) => plane.transform,
+            Self::Quad(ref quad) => quad.transform,
+            Self::Portal(ref portal) => portal.transform,
+            Self::Custom(ref custom) => custom.transform(),
+        }
+    }
+
+    /// Whether the shape can be drawn at all. Shapes whose transform can't be inverted (e.g. a
+    /// scaling by zero) or contains NaN can't be intersected, so they silently vanish from renders;
+    /// this makes it possible to catch them beforehand.
+    pub fn is_renderable(&self) -> bool {
+        self.transform()
+            .inverse()
+            .is_some_and(|inv| (0..4).all(|i| inv[i].iter().all(|x| x.is_finite())))
+    }
+
+    /// Computes the world-space, axis-aligned bounding box of the shape as its `(min, max)`
+    /// corners. Returns `None` for unbounded shapes like planes.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let (min, max) = match *self {
+            Shape::Sphere(ref sphere) => sphere.local_bounds(),
+            Shape::Plane(ref plane) => plane.local_bounds(),
+            Shape::Quad(ref quad) => quad.local_bounds(),
+            Shape::Portal(ref portal) => portal.local_bounds(),
+            // already in world space
+            Shape::Custom(ref custom) => return custom.bounds(),
+        }?;
+        let transform = self.transform();
+
+        let corners = [
+            Point(min.x(), min.y(), min.z()),
+            Point(min.x(), min.y(), max.z()),
+            Point(min.x(), max.y(), min.z()),
+            Point(min.x(), max.y(), max.z()),
+            Point(max.x(), min.y(), min.z()),
+            Point(max.x(), min.y(), max.z()),
+            Point(max.x(), max.y(), min.z()),
+            Point(max.x(), max.y(), max.z()),
+        ]
+        .map(|c| transform * c);
+
+        Some(
+            corners
+                .iter()
+                .skip(1)
+                .fold((corners[0], corners[0]), |(lo, hi), c| {
+                    (
+                        Point(lo.x().min(c.x()), lo.y().min(c.y()), lo.z().min(c.z())),
+                        Point(hi.x().max(c.x()), hi.y().max(c.y()), hi.z().max(c.z())),
+                    )
+                }),
+        )
+    }
+
+    /// Computes the normal vector at the given point in world-space coordinates. The point is
+    /// converted to object space, the shape computes its normal there, and the normal is converted
+    /// back to world space, so individual shapes only ever deal with object space. Returns `None`
+    /// if the shape's transform can't be inverted.
+    pub fn normal_at(&self, world_pt: Point) -> Option<Vec3> {
+        if let Shape::Custom(ref custom) = *self {
+            return custom.normal_at(world_pt);
+        }
+
+        let inv = self.transform().inverse()?;
+        let object_pt = inv * world_pt;
+
+        let object_normal = match *self {
+            Shape::Sphere(ref sphere) => sphere.local_normal_at(object_pt),
+            Shape::Plane(ref plane) => plane.local_normal_at(object_pt),
+            Shape::Quad(ref quad) => quad.local_normal_at(object_pt),
+            Shape::Portal(ref portal) => portal.local_normal_at(object_pt),
+            Shape::Custom(_) => unreachable!("custom shapes compute their own world-space normals"),
+        };
+        // The bottom row of the transposed inverse carries the translation into the `w` component;
+        // clear it so only the linear part (rotation, scaling, shear) affects the normal.
+        let mut normal_matrix = inv.transpose();
+        normal_matrix[3] = [0.0, 0.0, 0.0, 1.0];
+        let world_normal = normal_matrix * object_normal;
+
+        Some(world_normal.normalize())
+    }
+
+    /// Checks whether the ray hits the shape at some `t` in `[0, max_t)`. Cheaper than
+    /// [intersect](Intersectable::intersect) since no intersection list is built or sorted, which
+    /// is all a shadow ray needs to know.
+    pub fn intersects_before(&self, r: Ray, max_t: f64) -> bool {
+        let in_range = |t: f64| t.is_finite() && t.is_sign_positive() && t < max_t;
+
+        match *self {
+            Shape::Sphere(ref sphere) => sphere
+                .hit_times(r)
+                .is_some_and(|(t1, t2)| in_range(t1) || in_range(t2)),
+            Shape::Plane(ref plane) => plane.hit_time(r).is_some_and(in_range),
+            Shape::Quad(ref quad) => quad.hit_time(r).is_some_and(in_range),
+            Shape::Portal(ref portal) => portal.hit_time(r).is_some_and(in_range),
+            Shape::Custom(ref custom) => custom
+                .intersect(r)
+                .is_some_and(|xs| xs.data.iter().any(|ix| in_range(ix.t))),
         }
     }
 }
 
 impl Intersectable for Shape {
-    fn intersect(&self, r: crate::core::Ray) -> Option<IntersectionList> {
+    fn intersect(&self, r: Ray) -> Option<IntersectionList> {
         match *self {
             Shape::Sphere(ref sphere) => sphere.intersect(r),
             Shape::Plane(ref plane) => plane.intersect(r),
+            Shape::Quad(ref quad) => quad.intersect(r),
+            Shape::Portal(ref portal) => portal.intersect(r),
+            Shape::Custom(ref custom) => custom.intersect(r).map(|xs| IntersectionList {
+                data: xs
+                    .into_iter()
+                    .map(|ix| Intersection::new(ix.t, self.clone()))
+                    .collect(),
+            }),
         }
     }
 
-    fn normal_at(&self, world_pt: crate::math::Point) -> Option<crate::math::Vec3> {
-        match *self {
-            Shape::Sphere(ref sphere) => sphere.normal_at_world_pt(world_pt),
-            Shape::Plane(ref plane) => plane.normal_at_world_pt(world_pt),
-        }
+    fn normal_at(&self, world_pt: Point) -> Option<Vec3> {
+        Shape::normal_at(self, world_pt)
+    }
+
+    fn material(&self) -> Material {
+        Shape::material(self)
+    }
+
+    fn transform(&self) -> Matrix<4> {
+        Shape::transform(self)
+    }
+
+    fn bounds(&self) -> Option<(Point, Point)> {
+        Shape::bounds(self)
     }
 }
@@ -1,6 +1,9 @@
+use std::sync::Arc;
+
 use crate::{
-    core::{material::Material, Intersection, IntersectionList, Ray, EPS},
+    core::{material::Material, pattern::Pattern, Intersection, IntersectionList, Ray, EPS},
     math::{Matrix, Point, Tuple, Vec3},
+    visuals::Color,
 };
 
 use super::Shape;
@@ -9,9 +12,20 @@ use super::Shape;
 pub struct Plane {
     pub transform: Matrix<4>,
     pub material: Material,
+    pub name: Option<Arc<str>>,
 }
 
 impl Plane {
+    /// The usual floor: the `y = 0` plane with a black and white checkerboard and no specular
+    /// highlight.
+    pub fn checkerboard_floor() -> Self {
+        Self::default().with_material(
+            &Material::default()
+                .with_pattern(&Pattern::new_checkerboard(Color::white(), Color::black()))
+                .with_specular(0.0),
+        )
+    }
+
     /// Applies the given transformation matrix to the plane.
     pub fn with_transform(mut self, m: &Matrix<4>) -> Self {
         self.transform = *m;
@@ -24,34 +38,43 @@ impl Plane {
         self
     }
 
+    /// Names the plane so it can be found again, e.g. with
+    /// [World::object_by_name](crate::core::World::object_by_name).
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
     /// Small helper function just to make things a bit less tedious.
     pub fn as_shape(&self) -> Shape {
         Shape::from(self)
     }
 
     /// Planes in `xz`-space always have `Vec3(0.0, 1.0, 0.0)` as normal vector.
-    pub(super) fn normal_at_world_pt(&self, _world_pt: Point) -> Option<Vec3> {
-        if let Some(inv) = self.transform.inverse() {
-            let object_normal = Vec3(0.0, 1.0, 0.0);
-            let world_normal = inv.transpose() * object_normal;
+    pub(super) fn local_normal_at(&self, _object_pt: Point) -> Vec3 {
+        Vec3(0.0, 1.0, 0.0)
+    }
 
-            Some(world_normal.normalize())
-        } else {
-            None
-        }
+    /// Planes extend forever, so they have no bounding box.
+    pub(super) fn local_bounds(&self) -> Option<(Point, Point)> {
+        None
     }
 
     /// Checks if the ray intersects with the plane and stores the intersection data in a `Vec`.
     pub(super) fn intersect(&self, r: Ray) -> Option<IntersectionList> {
+        let i1 = Intersection::new(self.hit_time(r)?, Shape::from(self));
+
+        Some(IntersectionList::new(vec![i1]))
+    }
+
+    /// The `t` value where the ray crosses the plane, without building an [IntersectionList].
+    pub(super) fn hit_time(&self, r: Ray) -> Option<f64> {
         let tr = r.transform(self.transform.inverse()?);
 
         if tr.direction.y().abs() < EPS {
             None
         } else {
-            let t = -tr.origin.y() / tr.direction.y();
-            let i1 = Intersection::new(t, Shape::from(self));
-
-            Some(IntersectionList::new(vec![i1]))
+            Some(-tr.origin.y() / tr.direction.y())
         }
     }
 }
@@ -61,6 +84,7 @@ impl Default for Plane {
         Self {
             transform: Matrix::identity(),
             material: Material::default(),
+            name: None,
         }
     }
 }
@@ -125,12 +149,26 @@ mod plane_tests {
     #[test]
     fn normal_of_plane_is_constant_everywhere() {
         let p = Plane::default();
-        let n1 = p.normal_at_world_pt(Point(0.0, 0.0, 0.0)).unwrap();
-        let n2 = p.normal_at_world_pt(Point(10.0, 0.0, -10.0)).unwrap();
-        let n3 = p.normal_at_world_pt(Point(-5.0, 0.0, 150.0)).unwrap();
+        let n1 = p.as_shape().normal_at(Point(0.0, 0.0, 0.0)).unwrap();
+        let n2 = p.as_shape().normal_at(Point(10.0, 0.0, -10.0)).unwrap();
+        let n3 = p.as_shape().normal_at(Point(-5.0, 0.0, 150.0)).unwrap();
 
         assert_eq!(n1, Vec3(0.0, 1.0, 0.0));
         assert_eq!(n2, Vec3(0.0, 1.0, 0.0));
         assert_eq!(n3, Vec3(0.0, 1.0, 0.0));
     }
+
+    #[test]
+    fn checkerboard_floor_is_a_checkered_ground_plane() {
+        let floor = Plane::checkerboard_floor();
+        let n = floor.as_shape().normal_at(Point(3.0, 0.0, -2.0)).unwrap();
+
+        assert_eq!(n, Vec3(0.0, 1.0, 0.0));
+        assert_eq!(floor.transform, Matrix::identity());
+        assert_eq!(
+            floor.material.pattern,
+            Pattern::new_checkerboard(Color::white(), Color::black())
+        );
+        assert_eq!(floor.material.specular, 0.0);
+    }
 }
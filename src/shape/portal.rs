@@ -0,0 +1,154 @@
+//! A flat window which teleports rays to another part of the scene.
+use std::sync::Arc;
+
+use crate::{
+    core::{Intersection, IntersectionList, Ray, EPS},
+    math::{Matrix, Point, Tuple, Vec3},
+};
+
+use super::Shape;
+
+/// A square window spanning `[-1, 1]` in `x` and `z` (before transformation). Portals have no
+/// surface of their own: a ray hitting one continues from the same point with `link` applied to
+/// it, so the portal shows whatever can be seen from the linked location. Portals don't cast
+/// shadows.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Portal {
+    pub transform: Matrix<4>,
+    pub link: Matrix<4>,
+    pub name: Option<Arc<str>>,
+}
+
+impl Portal {
+    /// Constructs a portal which moves rays passing through it by `link`.
+    pub fn new(link: Matrix<4>) -> Self {
+        Self {
+            link,
+            ..Default::default()
+        }
+    }
+
+    /// Applies the given transformation matrix to the portal.
+    pub fn with_transform(mut self, m: &Matrix<4>) -> Self {
+        self.transform = *m;
+        self
+    }
+
+    /// Sets the transformation applied to rays passing through the portal, e.g. a translation to
+    /// the paired location.
+    pub fn with_link(mut self, m: &Matrix<4>) -> Self {
+        self.link = *m;
+        self
+    }
+
+    /// Names the portal so it can be found again, e.g. with
+    /// [World::object_by_name](crate::core::World::object_by_name).
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Small helper function just to make things a bit less tedious.
+    pub fn as_shape(&self) -> Shape {
+        Shape::from(self)
+    }
+
+    /// Continues a ray which hit the portal at `point` travelling along `direction`. The new ray
+    /// starts just past the linked location so it doesn't immediately hit a portal there.
+    pub(crate) fn teleport(&self, point: Point, direction: Vec3) -> Ray {
+        let direction = (self.link * direction).normalize();
+        let origin = self.link * point + direction * EPS;
+
+        Ray::new(origin, direction)
+    }
+
+    /// Like a plane, the normal is the same everywhere.
+    pub(super) fn local_normal_at(&self, _object_pt: Point) -> Vec3 {
+        Vec3(0.0, 1.0, 0.0)
+    }
+
+    /// The flat square from `(-1, 0, -1)` to `(1, 0, 1)`.
+    pub(super) fn local_bounds(&self) -> Option<(Point, Point)> {
+        Some((Point(-1.0, 0.0, -1.0), Point(1.0, 0.0, 1.0)))
+    }
+
+    pub(super) fn intersect(&self, r: Ray) -> Option<IntersectionList> {
+        Some(IntersectionList::new(vec![Intersection::new(
+            self.hit_time(r)?,
+            Shape::from(self),
+        )]))
+    }
+
+    /// The `t` value where the ray passes through the portal, without building an
+    /// [IntersectionList].
+    pub(super) fn hit_time(&self, r: Ray) -> Option<f64> {
+        let tr = r.transform(self.transform.inverse()?);
+
+        if tr.direction.y().abs() < EPS {
+            return None;
+        }
+
+        let t = -tr.origin.y() / tr.direction.y();
+        let p = tr.position(t);
+
+        if p.x().abs() > 1.0 || p.z().abs() > 1.0 {
+            return None;
+        }
+
+        Some(t)
+    }
+}
+
+impl Default for Portal {
+    fn default() -> Self {
+        Self {
+            transform: Matrix::identity(),
+            link: Matrix::identity(),
+            name: None,
+        }
+    }
+}
+
+impl From<Portal> for Shape {
+    fn from(p: Portal) -> Self {
+        Self::Portal(p)
+    }
+}
+
+impl From<&Portal> for Shape {
+    fn from(p: &Portal) -> Self {
+        Self::Portal((*p).clone())
+    }
+}
+
+#[cfg(test)]
+mod portal_tests {
+    use super::*;
+
+    #[test]
+    fn ray_intersecting_portal() {
+        let p = Portal::default();
+        let r = Ray::new(Point(0.5, 2.0, -0.5), Vec3(0.0, -1.0, 0.0));
+        let xs = p.intersect(r).unwrap();
+
+        assert_eq!(xs.data.len(), 1);
+        assert_eq!(xs[0].t, 2.0);
+    }
+
+    #[test]
+    fn ray_missing_portal() {
+        let p = Portal::default();
+        let r = Ray::new(Point(1.5, 2.0, 0.0), Vec3(0.0, -1.0, 0.0));
+
+        assert!(p.intersect(r).is_none());
+    }
+
+    #[test]
+    fn teleported_rays_emerge_at_the_linked_location() {
+        let p = Portal::new(Matrix::translation(10.0, 0.0, 0.0));
+        let r = p.teleport(Point(0.5, 0.0, 0.0), Vec3(0.0, -1.0, 0.0));
+
+        assert!((r.origin - Point(10.5, 0.0, 0.0)).magnitude() <= EPS + 1e-12);
+        assert_eq!(r.direction, Vec3(0.0, -1.0, 0.0));
+    }
+}
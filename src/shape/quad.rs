@@ -0,0 +1,200 @@
+//! A bounded, flat parallelogram. Handy for walls and floors.
+use std::sync::Arc;
+
+use crate::{
+    core::{material::Material, Intersection, IntersectionList, Ray, EPS},
+    math::{Matrix, Point, Tuple, Vec3},
+};
+
+use super::Shape;
+
+/// A parallelogram with one corner at `origin` and edges `u` and `v`. The normal points along
+/// `u × v`. The default quad is the unit square in the `xz`-plane with its normal pointing up the
+/// `y`-axis.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Quad {
+    pub origin: Point,
+    pub u: Vec3,
+    pub v: Vec3,
+    pub transform: Matrix<4>,
+    pub material: Material,
+    pub name: Option<Arc<str>>,
+}
+
+impl Quad {
+    /// Constructs a quad from a corner and two edge vectors.
+    pub fn new(origin: Point, u: Vec3, v: Vec3) -> Self {
+        Self {
+            origin,
+            u,
+            v,
+            ..Default::default()
+        }
+    }
+
+    /// Applies the given transformation matrix to the quad.
+    pub fn with_transform(mut self, m: &Matrix<4>) -> Self {
+        self.transform = *m;
+        self
+    }
+
+    /// Assigns the given material to the associated quad.
+    pub fn with_material(mut self, m: &Material) -> Self {
+        self.material = (*m).clone();
+        self
+    }
+
+    /// Names the quad so it can be found again, e.g. with
+    /// [World::object_by_name](crate::core::World::object_by_name).
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Small helper function just to make things a bit less tedious.
+    pub fn as_shape(&self) -> Shape {
+        Shape::from(self)
+    }
+
+    /// Quads are flat, so the normal is `u × v` everywhere.
+    pub(super) fn local_normal_at(&self, _object_pt: Point) -> Vec3 {
+        self.u.cross(&self.v)
+    }
+
+    /// The smallest box containing all four corners of the parallelogram.
+    pub(super) fn local_bounds(&self) -> Option<(Point, Point)> {
+        let corners = [
+            self.origin,
+            self.origin + self.u,
+            self.origin + self.v,
+            self.origin + self.u + self.v,
+        ];
+        let min = corners.iter().fold(corners[0], |acc, &c| {
+            Point(acc.x().min(c.x()), acc.y().min(c.y()), acc.z().min(c.z()))
+        });
+        let max = corners.iter().fold(corners[0], |acc, &c| {
+            Point(acc.x().max(c.x()), acc.y().max(c.y()), acc.z().max(c.z()))
+        });
+
+        Some((min, max))
+    }
+
+    /// Intersects the ray with the plane containing the quad, then rejects the hit if it lies
+    /// outside the parallelogram.
+    pub(super) fn intersect(&self, r: Ray) -> Option<IntersectionList> {
+        Some(IntersectionList::new(vec![Intersection::new(
+            self.hit_time(r)?,
+            Shape::from(self),
+        )]))
+    }
+
+    /// The `t` value where the ray hits the quad, without building an [IntersectionList].
+    pub(super) fn hit_time(&self, r: Ray) -> Option<f64> {
+        let tr = r.transform(self.transform.inverse()?);
+        let n = self.u.cross(&self.v);
+        let denom = n.dot(&tr.direction);
+
+        if denom.abs() < EPS {
+            return None;
+        }
+
+        let t = n.dot(&(self.origin - tr.origin)) / denom;
+
+        // express the hit in terms of the edge vectors: hit = origin + alpha * u + beta * v
+        let planar_hit = tr.position(t) - self.origin;
+        let w = n / n.dot(&n);
+        let alpha = w.dot(&planar_hit.cross(&self.v));
+        let beta = w.dot(&self.u.cross(&planar_hit));
+
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        Some(t)
+    }
+}
+
+impl Default for Quad {
+    fn default() -> Self {
+        Self {
+            origin: Point(0.0, 0.0, 0.0),
+            u: Vec3(0.0, 0.0, 1.0),
+            v: Vec3(1.0, 0.0, 0.0),
+            transform: Matrix::identity(),
+            material: Material::default(),
+            name: None,
+        }
+    }
+}
+
+impl From<Quad> for Shape {
+    fn from(q: Quad) -> Self {
+        Self::Quad(q)
+    }
+}
+
+impl From<&Quad> for Shape {
+    fn from(q: &Quad) -> Self {
+        Self::Quad((*q).clone())
+    }
+}
+
+#[cfg(test)]
+mod quad_tests {
+    use super::*;
+
+    #[test]
+    fn ray_intersecting_quad() {
+        let q = Quad::new(
+            Point(-1.0, -1.0, 0.0),
+            Vec3(2.0, 0.0, 0.0),
+            Vec3(0.0, 2.0, 0.0),
+        );
+        let r = Ray::new(Point(0.5, 0.5, -5.0), Vec3(0.0, 0.0, 1.0));
+        let xs = q.intersect(r).unwrap();
+
+        assert_eq!(xs.data.len(), 1);
+        assert_eq!(xs[0].t, 5.0);
+        assert_eq!(xs[0].object, q.as_shape());
+    }
+
+    #[test]
+    fn ray_missing_quad_is_rejected() {
+        let q = Quad::new(
+            Point(-1.0, -1.0, 0.0),
+            Vec3(2.0, 0.0, 0.0),
+            Vec3(0.0, 2.0, 0.0),
+        );
+
+        // these rays hit the plane containing the quad, but outside of the parallelogram
+        let misses = [
+            Point(1.5, 0.0, -5.0),
+            Point(-1.5, 0.0, -5.0),
+            Point(0.0, 1.5, -5.0),
+            Point(0.0, -1.5, -5.0),
+        ];
+
+        for origin in misses {
+            let r = Ray::new(origin, Vec3(0.0, 0.0, 1.0));
+            assert!(q.intersect(r).is_none());
+        }
+    }
+
+    #[test]
+    fn ray_parallel_to_quad() {
+        let q = Quad::default();
+        let r = Ray::new(Point(0.5, 1.0, 0.5), Vec3(1.0, 0.0, 0.0));
+
+        assert!(q.intersect(r).is_none());
+    }
+
+    #[test]
+    fn normal_of_quad_is_constant_everywhere() {
+        let q = Quad::default();
+        let n1 = q.as_shape().normal_at(Point(0.0, 0.0, 0.0)).unwrap();
+        let n2 = q.as_shape().normal_at(Point(0.5, 0.0, 0.5)).unwrap();
+
+        assert_eq!(n1, Vec3(0.0, 1.0, 0.0));
+        assert_eq!(n2, Vec3(0.0, 1.0, 0.0));
+    }
+}
@@ -1,4 +1,6 @@
 //! A fundamental object for rendering.
+use std::sync::Arc;
+
 use crate::{
     core::{material::Material, Intersection, IntersectionList, Ray},
     math::{Matrix, Point, Vec3},
@@ -12,6 +14,7 @@ use super::Shape;
 pub struct Sphere {
     pub transform: Matrix<4>,
     pub material: Material,
+    pub name: Option<Arc<str>>,
 }
 
 impl Sphere {
@@ -27,28 +30,40 @@ impl Sphere {
         self
     }
 
+    /// Names the sphere so it can be found again, e.g. with
+    /// [World::object_by_name](crate::core::World::object_by_name).
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
     /// Small helper function just to make things a bit less tedious.
     pub fn as_shape(&self) -> Shape {
         Shape::from(self)
     }
 
-    /// Computes the normal vector of the sphere at the given _world_ point.
-    ///
-    /// Relies on the inverse of the transform matrix applied to the sphere. Returns [`None`] if
-    /// the inverse doesn't exist.
-    pub(in crate::shape) fn normal_at_world_pt(&self, world_pt: Point) -> Option<Vec3> {
-        if let Some(inv) = self.transform.inverse() {
-            let object_pt = inv * world_pt;
-            let object_normal = object_pt - Point(0.0, 0.0, 0.0);
-            let world_normal = inv.transpose() * object_normal;
+    /// Computes the normal vector of the unit sphere at the given _object_ point. Converting to
+    /// and from world space is handled by [Shape].
+    pub(in crate::shape) fn local_normal_at(&self, object_pt: Point) -> Vec3 {
+        object_pt - Point(0.0, 0.0, 0.0)
+    }
 
-            Some(world_normal.normalize())
-        } else {
-            None
-        }
+    /// The unit sphere fits snugly in the cube from `(-1, -1, -1)` to `(1, 1, 1)`.
+    pub(in crate::shape) fn local_bounds(&self) -> Option<(Point, Point)> {
+        Some((Point(-1.0, -1.0, -1.0), Point(1.0, 1.0, 1.0)))
     }
 
     pub(in crate::shape) fn intersect(&self, r: Ray) -> Option<IntersectionList> {
+        let (t1, t2) = self.hit_times(r)?;
+        let i1 = Intersection::new(t1, Shape::from(self));
+        let i2 = Intersection::new(t2, Shape::from(self));
+
+        Some(IntersectionList { data: vec![i1, i2] })
+    }
+
+    /// The `t` values where the ray enters and leaves the sphere, without building an
+    /// [IntersectionList].
+    pub(in crate::shape) fn hit_times(&self, r: Ray) -> Option<(f64, f64)> {
         let tr = r.transform(self.transform.inverse()?);
         let sphere_to_ray = tr.origin - Point(0.0, 0.0, 0.0); // assuming every sphere is centered at the world origin
 
@@ -64,10 +79,8 @@ impl Sphere {
 
         let t1 = (-b - f64::sqrt(discrim)) / (2.0 * a);
         let t2 = (-b + f64::sqrt(discrim)) / (2.0 * a);
-        let i1 = Intersection::new(t1, Shape::from(self));
-        let i2 = Intersection::new(t2, Shape::from(self));
 
-        Some(IntersectionList { data: vec![i1, i2] })
+        Some((t1, t2))
     }
 }
 
@@ -76,6 +89,7 @@ impl Default for Sphere {
         Self {
             transform: Matrix::identity(),
             material: Material::default(),
+            name: None,
         }
     }
 }
@@ -97,7 +111,7 @@ impl From<&Sphere> for Shape {
 #[cfg(test)]
 mod sphere_tests {
     use super::*;
-    use crate::{core::IntersectionList, math::Vec3};
+    use crate::{core::IntersectionList, math::Vec3, shape::Plane};
     use std::f64::consts::FRAC_1_SQRT_2;
     use std::f64::consts::PI;
 
@@ -127,20 +141,38 @@ mod sphere_tests {
             * Matrix::rotation(crate::math::matrix::Axis::Z, PI / 5.0);
         let s = Sphere::default().with_transform(&m);
         let n = s
-            .normal_at_world_pt(Point(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2))
+            .as_shape()
+            .normal_at(Point(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2))
             .unwrap();
-        let v = Vec3(0.0, 0.97014, -0.24254);
+        assert!(n.approx_eq(&Vec3(0.0, 0.97014, -0.24254), 1e-4));
+    }
 
-        for i in 0..3 {
-            assert!((n[i] - v[i]).abs() < 1e-4);
-        }
+    #[test]
+    fn normal_of_sheared_and_translated_sphere() {
+        let m = Matrix::translation(2.0, 3.0, 4.0) * Matrix::shear(1.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        let s = Sphere::default().with_transform(&m);
+        let n = s
+            .as_shape()
+            .normal_at(m * Point(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0))
+            .unwrap();
+
+        assert!((n.magnitude() - 1.0).abs() < 1e-10);
+        assert!((n - Vec3(1.0, 0.0, 0.0)).magnitude() < 1e-10);
+    }
+
+    #[test]
+    fn local_normal_ignores_the_transform() {
+        let s = Sphere::default().with_transform(&Matrix::scaling(2.0, 3.0, 4.0));
+
+        assert_eq!(s.local_normal_at(Point(0.0, 1.0, 0.0)), Vec3(0.0, 1.0, 0.0));
     }
 
     #[test]
     fn normal_of_translated_sphere() {
         let s = Sphere::default().with_transform(&Matrix::translation(0.0, 1.0, 0.0));
         let n = s
-            .normal_at_world_pt(Point(0.0, 1.70711, -FRAC_1_SQRT_2))
+            .as_shape()
+            .normal_at(Point(0.0, 1.70711, -FRAC_1_SQRT_2))
             .unwrap();
         let want = Vec3(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2);
 
@@ -157,7 +189,7 @@ mod sphere_tests {
             3.0_f64.sqrt() / 3.0,
             3.0_f64.sqrt() / 3.0,
         );
-        let n = s.normal_at_world_pt(p).unwrap();
+        let n = s.as_shape().normal_at(p).unwrap();
         let v = Vec3(
             3.0_f64.sqrt() / 3.0,
             3.0_f64.sqrt() / 3.0,
@@ -170,7 +202,7 @@ mod sphere_tests {
     #[test]
     fn normal_at_point_on_z_axis() {
         let s = Sphere::default();
-        let n = s.normal_at_world_pt(Point(0.0, 0.0, 1.0)).unwrap();
+        let n = s.as_shape().normal_at(Point(0.0, 0.0, 1.0)).unwrap();
 
         assert_eq!(n, Vec3(0.0, 0.0, 1.0));
     }
@@ -178,7 +210,7 @@ mod sphere_tests {
     #[test]
     fn normal_at_point_on_y_axis() {
         let s = Sphere::default();
-        let n = s.normal_at_world_pt(Point(0.0, 1.0, 0.0)).unwrap();
+        let n = s.as_shape().normal_at(Point(0.0, 1.0, 0.0)).unwrap();
 
         assert_eq!(n, Vec3(0.0, 1.0, 0.0));
     }
@@ -186,7 +218,7 @@ mod sphere_tests {
     #[test]
     fn normal_at_point_on_x_axis() {
         let s = Sphere::default();
-        let n = s.normal_at_world_pt(Point(1.0, 0.0, 0.0)).unwrap();
+        let n = s.as_shape().normal_at(Point(1.0, 0.0, 0.0)).unwrap();
 
         assert_eq!(n, Vec3(1.0, 0.0, 0.0));
     }
@@ -234,7 +266,7 @@ mod sphere_tests {
         let i2 = Intersection::new(7.0, s.as_shape());
         let i3 = Intersection::new(-3.0, s.as_shape());
         let i4 = Intersection::new(2.0, s.as_shape());
-        let mut xs = IntersectionList::new(vec![i1, i2, i3, i4.clone()]);
+        let xs = IntersectionList::new(vec![i1, i2, i3, i4.clone()]);
 
         assert_eq!(xs.hit().unwrap(), &i4);
     }
@@ -244,7 +276,7 @@ mod sphere_tests {
         let s = Sphere::default();
         let i1 = Intersection::new(-2.0, s.as_shape());
         let i2 = Intersection::new(-1.0, s.as_shape());
-        let mut xs = IntersectionList::new(vec![i2, i1]);
+        let xs = IntersectionList::new(vec![i2, i1]);
 
         assert!(xs.hit().is_none());
     }
@@ -254,7 +286,7 @@ mod sphere_tests {
         let s = Sphere::default();
         let i1 = Intersection::new(-1.0, s.as_shape());
         let i2 = Intersection::new(1.0, s.as_shape());
-        let mut xs = IntersectionList::new(vec![i2.clone(), i1]);
+        let xs = IntersectionList::new(vec![i2.clone(), i1]);
 
         assert_eq!(xs.hit().unwrap(), &i2);
     }
@@ -264,11 +296,92 @@ mod sphere_tests {
         let s = Sphere::default();
         let i1 = Intersection::new(1.0, s.as_shape());
         let i2 = Intersection::new(2.0, s.as_shape());
-        let mut xs = IntersectionList::new(vec![i1.clone(), i2]);
+        let xs = IntersectionList::new(vec![i1.clone(), i2]);
 
         assert_eq!(*xs.hit().unwrap(), i1);
     }
 
+    #[test]
+    fn filtering_intersections() {
+        let s1 = Sphere::default().as_shape();
+        let s2 = Sphere::default()
+            .with_transform(&Matrix::translation(0.0, 0.0, 3.0))
+            .as_shape();
+        let xs = IntersectionList::new(vec![
+            Intersection::new(4.0, s1.clone()),
+            Intersection::new(-2.0, s1.clone()),
+            Intersection::new(1.0, s2.clone()),
+            Intersection::new(6.0, s2.clone()),
+            Intersection::new(-0.5, s2.clone()),
+        ]);
+
+        assert_eq!(xs.count(), 5);
+        assert_eq!(xs.with_object(&s1).count(), 2);
+        assert_eq!(xs.with_object(&s2).count(), 3);
+        assert!(xs.with_object(&s1).all(|ix| ix.object == s1));
+
+        let ts = xs.hits().map(|ix| ix.t).collect::<Vec<_>>();
+        assert_eq!(ts, vec![1.0, 4.0, 6.0]);
+        assert_eq!(xs.hits().next(), xs.hit());
+    }
+
+    #[test]
+    fn degenerate_transforms_are_not_renderable() {
+        let zero = Sphere::default()
+            .with_transform(&Matrix::scaling(0.0, 0.0, 0.0))
+            .as_shape();
+        let nan = Plane::default()
+            .with_transform(&Matrix::translation(f64::NAN, 0.0, 0.0))
+            .as_shape();
+
+        assert!(!zero.is_renderable());
+        assert!(!nan.is_renderable());
+        assert!(Sphere::default().as_shape().is_renderable());
+        assert!(Sphere::default()
+            .with_transform(&Matrix::scaling(2.0, 0.5, 1.0))
+            .as_shape()
+            .is_renderable());
+    }
+
+    #[test]
+    fn merging_sorted_lists() {
+        let s1 = Sphere::default().as_shape();
+        let s2 = Plane::default().as_shape();
+        let a = IntersectionList::new(vec![
+            Intersection::new(-1.0, s1.clone()),
+            Intersection::new(2.0, s1.clone()),
+            Intersection::new(5.0, s1.clone()),
+        ]);
+        let b = IntersectionList::new(vec![
+            Intersection::new(0.5, s2.clone()),
+            Intersection::new(3.0, s2.clone()),
+        ]);
+
+        let merged = a.clone().merge(b.clone());
+        let ts = merged.data.iter().map(|ix| ix.t).collect::<Vec<_>>();
+        assert_eq!(ts, vec![-1.0, 0.5, 2.0, 3.0, 5.0]);
+        assert_eq!(merged.with_object(&s1).count(), 3);
+        assert_eq!(merged.with_object(&s2).count(), 2);
+
+        let mut extended = b;
+        extended.extend(a);
+        assert_eq!(extended, merged);
+    }
+
+    #[test]
+    fn hit_does_not_reorder_a_sorted_list() {
+        let s = Sphere::default().as_shape();
+        let xs = IntersectionList::new(vec![
+            Intersection::new(2.0, s.clone()),
+            Intersection::new(-1.0, s.clone()),
+            Intersection::new(0.5, s),
+        ]);
+        let before = xs.clone();
+
+        assert_eq!(xs.hit().unwrap().t, 0.5);
+        assert_eq!(xs, before);
+    }
+
     #[test]
     fn intersection_sets_object() {
         let r = Ray::new(Point(0.0, 0.0, -5.0), Vec3(0.0, 0.0, 1.0));
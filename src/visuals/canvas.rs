@@ -1,21 +1,29 @@
 //! A canvas is an explicitly defined region on which the renderer can act.
 //!
 //! The `write_pixel` and `read_pixel` methods allow for direct manipulation/reading of pixel data.
-use image::RgbImage;
-
 use super::Color;
+use crate::io::error::{CanvasError, PpmError};
+
+use image::{ImageFormat, ImageOutputFormat, ImageResult};
+use std::{
+    fs::File,
+    io::{BufWriter, Seek, Write},
+};
 
-#[derive(Clone, Debug)]
+/// Pixels are stored as (linear, unclamped) colors in row-major order, so post-processing like
+/// tone mapping can still recover detail from overly bright areas. Colors are only clamped and
+/// quantized to 8 bits when exporting.
+#[derive(Clone, Debug, PartialEq)]
 pub struct Canvas {
     pub width: u32,
     pub height: u32,
-    pub pixels: RgbImage,
+    pub pixels: Vec<Color>,
 }
 
 impl Canvas {
     /// Constructs a new, blank canvas.
     pub fn new(width: u32, height: u32) -> Self {
-        let pixels = RgbImage::new(width, height);
+        let pixels = vec![Color::black(); width as usize * height as usize];
 
         Self {
             width,
@@ -24,60 +32,445 @@ impl Canvas {
         }
     }
 
+    /// Parses a plain-text (`P3`) PPM image into a canvas. Comments (anything following a `#` on a
+    /// line) and arbitrary whitespace are ignored, and color values are scaled by the maximum
+    /// value given in the header.
+    pub fn from_ppm(s: &str) -> Result<Canvas, PpmError> {
+        let mut tokens = s
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or(""))
+            .flat_map(str::split_whitespace);
+
+        match tokens.next() {
+            Some("P3") => {}
+            Some(magic) => return Err(PpmError::InvalidMagic(magic.to_string())),
+            None => return Err(PpmError::InvalidMagic(String::new())),
+        }
+
+        let mut header = |field: &'static str| -> Result<u32, PpmError> {
+            tokens
+                .next()
+                .and_then(|t| t.parse::<u32>().ok())
+                .ok_or(PpmError::InvalidHeader(field))
+        };
+        let width = header("width")?;
+        let height = header("height")?;
+        let max_value = header("max value")?;
+        if max_value == 0 {
+            return Err(PpmError::InvalidHeader("max value"));
+        }
+
+        let values = tokens
+            .map(|t| {
+                t.parse::<u32>()
+                    .map(|v| v as f64 / max_value as f64)
+                    .map_err(|_| PpmError::InvalidValue(t.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let count = width as usize * height as usize;
+        let expected = count.saturating_mul(3);
+        if values.len() < expected {
+            return Err(PpmError::MissingValues {
+                expected,
+                found: values.len(),
+            });
+        }
+
+        let mut canvas = Canvas::new(width, height);
+        for (i, rgb) in values.chunks_exact(3).take(count).enumerate() {
+            let x = (i % width as usize) as u32;
+            let y = (i / width as usize) as u32;
+            canvas.write_pixel(x, y, Color(rgb[0], rgb[1], rgb[2]));
+        }
+
+        Ok(canvas)
+    }
+
     /// Draws the given color to the pixel located at `(x, y)`.
     pub fn write_pixel(&mut self, x: u32, y: u32, c: Color) {
-        let (r, g, b) = scale_colors(&c);
-        let c = image::Rgb([r, g, b]);
+        let idx = self.index(x, y);
+
+        self.pixels[idx] = c;
+    }
 
-        self.pixels.put_pixel(x, y, c);
+    /// Reads the color at `(x, y)`. This is exactly the color that was last written there, since
+    /// colors are only quantized when exporting.
+    pub fn read_pixel(&self, x: u32, y: u32) -> Color {
+        self.pixels[self.index(x, y)]
     }
 
-    pub(crate) fn read_pixel(&self, x: u32, y: u32) -> Color {
-        let p = self.pixels.get_pixel(x, y);
+    fn index(&self, x: u32, y: u32) -> usize {
+        assert!(
+            x < self.width && y < self.height,
+            "pixel ({}, {}) is outside of the {}x{} canvas",
+            x,
+            y,
+            self.width,
+            self.height
+        );
 
-        let r = p[0] as f64 / 255.0;
-        let g = p[1] as f64 / 255.0;
-        let b = p[2] as f64 / 255.0;
-        Color(r, g, b)
+        y as usize * self.width as usize + x as usize
     }
 
-    /// Exports the formatted file as described by the `path` input.
-    pub fn export(&self, path: &str) -> image::ImageResult<()> {
-        let mut img = image::RgbImage::new(self.width as u32, self.height as u32);
+    /// Produces a new canvas scaled so that the 95th-percentile luminance maps to roughly 0.9. This
+    /// brightens dim renders and tones down blown-out ones without any manual tuning. A completely
+    /// black canvas is returned unchanged. Pixels that aren't finite (e.g. NaN from a degenerate
+    /// hit) are left out when picking the exposure.
+    pub fn auto_expose(&self) -> Canvas {
+        const PERCENTILE: f64 = 0.95;
+        const TARGET: f64 = 0.9;
+
+        let mut lums = self
+            .pixels
+            .iter()
+            .map(|c| c.luminance())
+            .filter(|l| l.is_finite())
+            .collect::<Vec<_>>();
+        lums.sort_unstable_by(f64::total_cmp);
+
+        let mut exposed = self.clone();
+        if lums.is_empty() {
+            return exposed;
+        }
+
+        let idx = ((lums.len() - 1) as f64 * PERCENTILE).round() as usize;
+        let reference = lums[idx];
+        if reference <= 0.0 {
+            return exposed;
+        }
+
+        let scale = TARGET / reference;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                exposed.write_pixel(x, y, self.read_pixel(x, y) * scale);
+            }
+        }
+
+        exposed
+    }
+
+    /// Applies the Reinhard tone-mapping operator `c / (1 + c)` to every channel. Bright colors are
+    /// compressed to fit below 1.0 instead of being clipped to white when exported, while dim
+    /// colors are left almost untouched.
+    pub fn tone_map_reinhard(&mut self) {
+        let reinhard = |c: f64| c / (1.0 + c);
+
+        for c in self.pixels.iter_mut() {
+            *c = Color(reinhard(c.r()), reinhard(c.g()), reinhard(c.b()));
+        }
+    }
+
+    /// Produces a grayscale copy of the canvas, replacing every pixel with its Rec. 709 luminance.
+    pub fn to_grayscale(&self) -> Canvas {
+        let mut gray = self.clone();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let l = self.read_pixel(x, y).luminance();
+                gray.write_pixel(x, y, Color(l, l, l));
+            }
+        }
+
+        gray
+    }
+
+    /// Produces a new canvas with a tone curve applied to each color channel. The curve is given
+    /// as `(input, output)` control points and interpolated linearly between them; inputs outside
+    /// the control points take the output of the nearest endpoint. Control points with a NaN input
+    /// are ignored, and an empty `lut` leaves the canvas unchanged.
+    ///
+    /// ```
+    /// # use rtc::visuals::Canvas;
+    /// let canvas = Canvas::new(10, 10);
+    /// // lift the shadows a little
+    /// let graded = canvas.apply_lut(&[(0.0, 0.05), (0.5, 0.55), (1.0, 1.0)]);
+    /// ```
+    pub fn apply_lut(&self, lut: &[(f64, f64)]) -> Canvas {
+        let mut points = lut
+            .iter()
+            .copied()
+            .filter(|p| !p.0.is_nan())
+            .collect::<Vec<_>>();
+        points.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut graded = self.clone();
+        if points.is_empty() {
+            return graded;
+        }
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let c = self.read_pixel(x, y);
+                let mapped = Color(
+                    apply_curve(&points, c.r()),
+                    apply_curve(&points, c.g()),
+                    apply_curve(&points, c.b()),
+                );
+                graded.write_pixel(x, y, mapped);
+            }
+        }
+
+        graded
+    }
+
+    /// Produces a white-on-black image of the edges in the canvas, found by running a Sobel filter
+    /// over the luminance. Pixels where the gradient's magnitude exceeds `threshold` are white.
+    /// Pixels past the border are treated as copies of the nearest edge pixel, so the border of
+    /// the image itself isn't an edge.
+    pub fn detect_edges(&self, threshold: f64) -> Canvas {
+        let lum = |x: i64, y: i64| {
+            let x = x.clamp(0, self.width as i64 - 1) as u32;
+            let y = y.clamp(0, self.height as i64 - 1) as u32;
+
+            self.read_pixel(x, y).luminance()
+        };
+
+        let mut edges = Canvas::new(self.width, self.height);
+        for y in 0..self.height as i64 {
+            for x in 0..self.width as i64 {
+                let gx = (lum(x + 1, y - 1) + 2.0 * lum(x + 1, y) + lum(x + 1, y + 1))
+                    - (lum(x - 1, y - 1) + 2.0 * lum(x - 1, y) + lum(x - 1, y + 1));
+                let gy = (lum(x - 1, y + 1) + 2.0 * lum(x, y + 1) + lum(x + 1, y + 1))
+                    - (lum(x - 1, y - 1) + 2.0 * lum(x, y - 1) + lum(x + 1, y - 1));
+
+                if gx.hypot(gy) > threshold {
+                    edges.write_pixel(x as u32, y as u32, Color::white());
+                }
+            }
+        }
+
+        edges
+    }
+
+    /// Shrinks the canvas by averaging each `factor`x`factor` block of pixels into one (a box
+    /// filter). Rendering at, say, twice the final size and downsampling by 2 gives clean
+    /// anti-aliasing without any of the ray-level [methods](crate::core::antialias::AAMethod).
+    /// Errors unless both dimensions are multiples of `factor`.
+    pub fn downsample(&self, factor: u32) -> Result<Canvas, CanvasError> {
+        if factor == 0 || !self.width.is_multiple_of(factor) || !self.height.is_multiple_of(factor)
+        {
+            return Err(CanvasError::NotDivisible {
+                width: self.width,
+                height: self.height,
+                factor,
+            });
+        }
+
+        let mut small = Canvas::new(self.width / factor, self.height / factor);
+        let area = (factor * factor) as f64;
+        for y in 0..small.height {
+            for x in 0..small.width {
+                let mut total = Color::black();
+                for j in 0..factor {
+                    for i in 0..factor {
+                        total = total + self.read_pixel(x * factor + i, y * factor + j);
+                    }
+                }
+
+                small.write_pixel(x, y, total / area);
+            }
+        }
+
+        Ok(small)
+    }
+
+    /// Mixes two canvases pixel by pixel as `self * (1 - alpha) + other * alpha`, e.g. to lay a
+    /// reflection-only pass over a base render. Errors if the canvases are different sizes.
+    pub fn blend(&self, other: &Canvas, alpha: f64) -> Result<Canvas, CanvasError> {
+        self.check_size(other)?;
+
+        let mut blended = self.clone();
+        for (c, o) in blended.pixels.iter_mut().zip(other.pixels.iter()) {
+            *c = *c * (1.0 - alpha) + *o * alpha;
+        }
+
+        Ok(blended)
+    }
+
+    /// Like [Canvas::blend], but each pixel's `alpha` comes from the luminance of the same pixel
+    /// in `mask`: where the mask is white `other` shows, and where it's black `self` does. Errors
+    /// if the three canvases aren't all the same size.
+    pub fn overlay(&self, other: &Canvas, mask: &Canvas) -> Result<Canvas, CanvasError> {
+        self.check_size(other)?;
+        self.check_size(mask)?;
+
+        let mut composite = self.clone();
+        for ((c, o), m) in composite
+            .pixels
+            .iter_mut()
+            .zip(other.pixels.iter())
+            .zip(mask.pixels.iter())
+        {
+            let alpha = m.luminance();
+            *c = *c * (1.0 - alpha) + *o * alpha;
+        }
+
+        Ok(composite)
+    }
+
+    /// Copies out the `w`x`h` region whose top-left corner is at `(x, y)`. Errors if the region
+    /// doesn't fit within the canvas.
+    pub fn crop(&self, x: u32, y: u32, w: u32, h: u32) -> Result<Canvas, CanvasError> {
+        let fits = x.checked_add(w).is_some_and(|x1| x1 <= self.width)
+            && y.checked_add(h).is_some_and(|y1| y1 <= self.height);
+        if !fits {
+            return Err(CanvasError::OutOfBounds {
+                origin: (x, y),
+                width: w,
+                height: h,
+                size: (self.width, self.height),
+            });
+        }
+
+        let mut cropped = Canvas::new(w, h);
+        for j in 0..h {
+            for i in 0..w {
+                cropped.write_pixel(i, j, self.read_pixel(x + i, y + j));
+            }
+        }
+
+        Ok(cropped)
+    }
+
+    /// Scales the canvas to `new_w`x`new_h` pixels, giving each new pixel the color of the nearest
+    /// original pixel. Blocky, but keeps hard edges and exact colors.
+    pub fn resize(&self, new_w: u32, new_h: u32) -> Canvas {
+        let mut resized = Canvas::new(new_w, new_h);
+        if self.pixels.is_empty() {
+            return resized;
+        }
+
+        // maps the center of a new pixel back onto the original canvas
+        let nearest = |i: u32, new: u32, old: u32| {
+            let pos = (i as f64 + 0.5) * old as f64 / new as f64;
+            (pos as u32).min(old - 1)
+        };
+        for y in 0..new_h {
+            for x in 0..new_w {
+                let (sx, sy) = (
+                    nearest(x, new_w, self.width),
+                    nearest(y, new_h, self.height),
+                );
+                resized.write_pixel(x, y, self.read_pixel(sx, sy));
+            }
+        }
+
+        resized
+    }
+
+    /// Like [Canvas::resize], but blends the four nearest original pixels for a smoother result.
+    pub fn resize_bilinear(&self, new_w: u32, new_h: u32) -> Canvas {
+        let mut resized = Canvas::new(new_w, new_h);
+        if self.pixels.is_empty() {
+            return resized;
+        }
+
+        // the two original pixels on either side of a new pixel's center, and how far between
+        // them it lies
+        let neighbors = |i: u32, new: u32, old: u32| {
+            let pos =
+                ((i as f64 + 0.5) * old as f64 / new as f64 - 0.5).clamp(0.0, (old - 1) as f64);
+            let lo = pos.floor() as u32;
+
+            (lo, (lo + 1).min(old - 1), pos - lo as f64)
+        };
+        for y in 0..new_h {
+            let (y0, y1, ty) = neighbors(y, new_h, self.height);
+            for x in 0..new_w {
+                let (x0, x1, tx) = neighbors(x, new_w, self.width);
+                let top = self.read_pixel(x0, y0) * (1.0 - tx) + self.read_pixel(x1, y0) * tx;
+                let bottom = self.read_pixel(x0, y1) * (1.0 - tx) + self.read_pixel(x1, y1) * tx;
+
+                resized.write_pixel(x, y, top * (1.0 - ty) + bottom * ty);
+            }
+        }
+
+        resized
+    }
+
+    fn check_size(&self, other: &Canvas) -> Result<(), CanvasError> {
+        if (self.width, self.height) == (other.width, other.height) {
+            Ok(())
+        } else {
+            Err(CanvasError::SizeMismatch {
+                expected: (self.width, self.height),
+                found: (other.width, other.height),
+            })
+        }
+    }
+
+    /// Quantizes the canvas into an 8-bit RGB image.
+    pub fn to_rgb_image(&self) -> image::RgbImage {
+        let mut img = image::RgbImage::new(self.width, self.height);
 
         for (x, y, pix) in img.enumerate_pixels_mut() {
-            let color = &self.read_pixel(x, y);
-            let (r, g, b) = clamped_color_channels(color);
+            let (r, g, b) = self.read_pixel(x, y).to_rgb8();
             *pix = image::Rgb([r, g, b]);
         }
 
-        img.save(path)
+        img
+    }
+
+    /// Exports the formatted file as described by the `path` input.
+    pub fn export(&self, path: &str) -> ImageResult<()> {
+        self.to_rgb_image().save(path)
+    }
+
+    /// Exports the canvas in the given format, regardless of the extension on `path`.
+    pub fn export_format(&self, path: &str, format: ImageFormat) -> ImageResult<()> {
+        self.to_rgb_image().save_with_format(path, format)
     }
-}
 
-fn clamped_color_channels(color: &Color) -> (u8, u8, u8) {
-    let r = color.r();
-    let g = color.g();
-    let b = color.b();
+    /// Exports the canvas as a JPEG with the given quality, from 1 (smallest) to 100 (best).
+    pub fn export_jpeg(&self, path: &str, quality: u8) -> ImageResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
 
-    let ir = (256.0 * r.clamp(0.0, 0.999)) as i32;
-    let ig = (256.0 * g.clamp(0.0, 0.999)) as i32;
-    let ib = (256.0 * b.clamp(0.0, 0.999)) as i32;
+        self.write_to(&mut writer, ImageOutputFormat::Jpeg(quality))
+    }
 
-    (ir as u8, ig as u8, ib as u8)
+    /// Encodes the canvas in the given format into any seekable writer, e.g. an in-memory
+    /// [Cursor](std::io::Cursor).
+    pub fn write_to<W, F>(&self, writer: &mut W, format: F) -> ImageResult<()>
+    where
+        W: Write + Seek,
+        F: Into<ImageOutputFormat>,
+    {
+        self.to_rgb_image().write_to(writer, format)
+    }
 }
 
-/// Scales each color channel to be between 0 and 255.
-fn scale_colors(color: &Color) -> (u8, u8, u8) {
-    let r = color.r();
-    let g = color.g();
-    let b = color.b();
+/// Evaluates a piecewise-linear curve given by control points sorted by input.
+fn apply_curve(points: &[(f64, f64)], v: f64) -> f64 {
+    let (first, last) = (points[0], points[points.len() - 1]);
+
+    if v <= first.0 {
+        return first.1;
+    }
+    if v >= last.0 {
+        return last.1;
+    }
 
-    let ir = (256.0 * r.clamp(0.0, 0.999)) as i32;
-    let ig = (256.0 * g.clamp(0.0, 0.999)) as i32;
-    let ib = (256.0 * b.clamp(0.0, 0.999)) as i32;
+    let i = points.partition_point(|p| p.0 <= v);
+    let ((x0, y0), (x1, y1)) = (points[i - 1], points[i]);
 
-    (ir as u8, ig as u8, ib as u8)
+    y0 + (y1 - y0) * (v - x0) / (x1 - x0)
+}
+
+// shared by the tests of anything that works with canvases, e.g. the GIF encoder
+#[cfg(test)]
+impl Canvas {
+    /// A canvas with every pixel set to `color`.
+    pub(crate) fn filled(width: u32, height: u32, color: Color) -> Self {
+        let mut canvas = Self::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                canvas.write_pixel(x, y, color);
+            }
+        }
+
+        canvas
+    }
 }
 
 #[cfg(test)]
@@ -93,6 +486,389 @@ mod canvas_tests {
         assert_eq!(canvas.read_pixel(3, 2), Color::black());
     }
 
+    #[test]
+    fn reading_pixels_is_lossless() {
+        let mut canvas = Canvas::new(2, 2);
+        canvas.write_pixel(1, 1, Color(0.4999, 0.0, 0.0));
+        canvas.write_pixel(0, 1, Color(2.5, -0.1, 0.0));
+
+        assert_eq!(canvas.read_pixel(1, 1), Color(0.4999, 0.0, 0.0));
+        assert_eq!(canvas.read_pixel(0, 1), Color(2.5, -0.1, 0.0));
+    }
+
+    #[test]
+    fn reading_ppm_with_bad_header_fails() {
+        let ppm = "P32\n1 1\n255\n0 0 0\n";
+
+        assert_eq!(
+            Canvas::from_ppm(ppm).unwrap_err(),
+            PpmError::InvalidMagic("P32".to_string())
+        );
+        assert_eq!(
+            Canvas::from_ppm("P3\n1\n").unwrap_err(),
+            PpmError::InvalidHeader("height")
+        );
+    }
+
+    #[test]
+    fn reading_ppm_ignores_comments() {
+        let ppm = "P3
+# this is a comment
+2 1
+# this, too
+255
+# another comment
+255 255 255
+# oh, no, comments in the pixel data!
+255 0 255
+";
+        let canvas = Canvas::from_ppm(ppm).unwrap();
+
+        assert_eq!(canvas.read_pixel(0, 0), Color::white());
+        assert_eq!(canvas.read_pixel(1, 0), Color(1.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn reading_ppm_scales_by_max_value() {
+        let ppm = "P3
+2 2
+100
+100 100 100  50 50 50
+ 75 50  25    0  0  0
+";
+        let canvas = Canvas::from_ppm(ppm).unwrap();
+        let c = canvas.read_pixel(0, 1);
+
+        assert_eq!(canvas.width, 2);
+        assert_eq!(canvas.height, 2);
+        assert_eq!(canvas.read_pixel(0, 0), Color::white());
+        assert_eq!(c, Color(0.75, 0.5, 0.25));
+    }
+
+    #[test]
+    fn reading_ppm_reports_missing_values() {
+        let ppm = "P3\n2 1\n255\n255 255 255\n";
+
+        assert_eq!(
+            Canvas::from_ppm(ppm).unwrap_err(),
+            PpmError::MissingValues {
+                expected: 6,
+                found: 3
+            }
+        );
+    }
+
+    #[test]
+    fn reading_ppm_with_huge_dimensions_does_not_overflow() {
+        let ppm = "P3\n65536 65536\n255\n0 0 0\n";
+
+        assert_eq!(
+            Canvas::from_ppm(ppm).unwrap_err(),
+            PpmError::MissingValues {
+                expected: 65536 * 65536 * 3,
+                found: 3
+            }
+        );
+    }
+
+    #[test]
+    fn grayscale_pixels_hold_the_luminance() {
+        let mut canvas = Canvas::new(3, 1);
+        canvas.write_pixel(0, 0, Color::red());
+        canvas.write_pixel(1, 0, Color(0.2, 0.6, 0.9));
+        canvas.write_pixel(2, 0, Color::white());
+
+        let gray = canvas.to_grayscale();
+
+        for x in 0..3 {
+            let c = gray.read_pixel(x, 0);
+            let expected = canvas.read_pixel(x, 0).luminance();
+
+            assert_eq!(c, Color(expected, expected, expected));
+        }
+    }
+
+    #[test]
+    fn reinhard_compresses_bright_colors() {
+        let mut canvas = Canvas::new(2, 1);
+        canvas.write_pixel(0, 0, Color(5.0, 5.0, 5.0));
+        canvas.write_pixel(1, 0, Color(0.02, 0.02, 0.02));
+
+        canvas.tone_map_reinhard();
+        let bright = canvas.read_pixel(0, 0);
+        let dim = canvas.read_pixel(1, 0);
+
+        assert!(bright.r() < 1.0);
+        assert!((bright.r() - 5.0 / 6.0).abs() < 1e-10);
+        assert!((dim.r() - 0.02).abs() < 1e-3);
+    }
+
+    #[test]
+    fn identity_lut_leaves_canvas_unchanged() {
+        let canvas = Canvas::filled(3, 2, Color(0.2, 0.4, 0.8));
+        let graded = canvas.apply_lut(&[(0.0, 0.0), (1.0, 1.0)]);
+
+        assert_eq!(graded.pixels, canvas.pixels);
+    }
+
+    #[test]
+    fn inverting_lut_inverts_each_channel() {
+        let canvas = Canvas::filled(3, 2, Color(0.25, 0.5, 0.875));
+        let graded = canvas.apply_lut(&[(1.0, 0.0), (0.0, 1.0)]);
+
+        assert_eq!(
+            graded.pixels,
+            Canvas::filled(3, 2, Color(0.75, 0.5, 0.125)).pixels
+        );
+    }
+
+    #[test]
+    fn lut_points_with_nan_inputs_are_ignored() {
+        let canvas = Canvas::filled(3, 2, Color(0.2, 0.4, 0.8));
+        let graded = canvas.apply_lut(&[(0.0, 0.0), (f64::NAN, 0.5), (1.0, 1.0)]);
+
+        assert_eq!(graded.pixels, canvas.pixels);
+    }
+
+    #[test]
+    fn auto_exposure_brightens_dim_renders() {
+        let canvas = Canvas::filled(4, 4, Color(0.2, 0.2, 0.2));
+        let exposed = canvas.auto_expose();
+        let lum = exposed.read_pixel(1, 1).luminance();
+
+        assert!(lum > canvas.read_pixel(1, 1).luminance());
+        assert!((lum - 0.9).abs() < 0.01);
+    }
+
+    #[test]
+    fn auto_exposure_tones_down_bright_renders() {
+        let canvas = Canvas::filled(4, 4, Color::white());
+        let exposed = canvas.auto_expose();
+        let lum = exposed.read_pixel(1, 1).luminance();
+
+        assert!(lum < canvas.read_pixel(1, 1).luminance());
+        assert!((lum - 0.9).abs() < 0.01);
+    }
+
+    #[test]
+    fn auto_exposure_ignores_nan_pixels() {
+        let mut canvas = Canvas::filled(4, 4, Color(0.2, 0.2, 0.2));
+        canvas.write_pixel(0, 0, Color(f64::NAN, 0.0, 0.0));
+        let exposed = canvas.auto_expose();
+
+        assert!((exposed.read_pixel(1, 1).luminance() - 0.9).abs() < 0.01);
+    }
+
+    #[test]
+    fn auto_exposure_leaves_black_canvas_alone() {
+        let canvas = Canvas::new(4, 4);
+
+        assert_eq!(canvas.auto_expose().read_pixel(0, 0), Color::black());
+    }
+
+    #[test]
+    fn detecting_edges_along_a_color_boundary() {
+        let mut canvas = Canvas::new(8, 6);
+        for y in 0..6 {
+            for x in 4..8 {
+                canvas.write_pixel(x, y, Color::white());
+            }
+        }
+        let edges = canvas.detect_edges(0.5);
+
+        for y in 0..6 {
+            // the pixels on either side of the boundary light up...
+            assert_eq!(edges.read_pixel(3, y), Color::white());
+            assert_eq!(edges.read_pixel(4, y), Color::white());
+            // ...while the flat areas stay dark
+            for x in [0, 1, 2, 5, 6, 7] {
+                assert_eq!(edges.read_pixel(x, y), Color::black());
+            }
+        }
+    }
+
+    #[test]
+    fn downsampling_averages_blocks() {
+        let mut canvas = Canvas::new(4, 4);
+        let blocks = [
+            [Color(1.0, 0.0, 0.0), Color(0.0, 1.0, 0.0)],
+            [Color(0.0, 0.0, 1.0), Color(0.5, 0.5, 0.5)],
+        ];
+        for y in 0..4 {
+            for x in 0..4 {
+                canvas.write_pixel(x, y, blocks[y as usize / 2][x as usize / 2]);
+            }
+        }
+        // one odd pixel out in the top-left block
+        canvas.write_pixel(0, 0, Color(0.0, 0.0, 0.0));
+
+        let small = canvas.downsample(2).unwrap();
+
+        assert_eq!((small.width, small.height), (2, 2));
+        assert_eq!(small.read_pixel(0, 0), Color(0.75, 0.0, 0.0));
+        assert_eq!(small.read_pixel(1, 0), blocks[0][1]);
+        assert_eq!(small.read_pixel(0, 1), blocks[1][0]);
+        assert_eq!(small.read_pixel(1, 1), blocks[1][1]);
+    }
+
+    #[test]
+    fn downsampling_needs_whole_blocks() {
+        let canvas = Canvas::new(5, 4);
+
+        assert_eq!(
+            canvas.downsample(2),
+            Err(CanvasError::NotDivisible {
+                width: 5,
+                height: 4,
+                factor: 2
+            })
+        );
+        assert!(canvas.downsample(0).is_err());
+        assert_eq!(canvas.downsample(1), Ok(canvas.clone()));
+    }
+
+    #[test]
+    fn blending_two_canvases() {
+        let red = Canvas::filled(3, 2, Color::red());
+        let blue = Canvas::filled(3, 2, Color::blue());
+        let blended = red.blend(&blue, 0.5).unwrap();
+
+        assert!(blended.pixels.iter().all(|&c| c == Color(0.5, 0.0, 0.5)));
+        assert_eq!(red.blend(&blue, 0.0).unwrap(), red);
+    }
+
+    #[test]
+    fn blending_canvases_of_different_sizes_is_an_error() {
+        let a = Canvas::new(3, 2);
+        let b = Canvas::new(2, 3);
+
+        assert_eq!(
+            a.blend(&b, 0.5),
+            Err(CanvasError::SizeMismatch {
+                expected: (3, 2),
+                found: (2, 3)
+            })
+        );
+    }
+
+    #[test]
+    fn overlaying_through_a_mask() {
+        let red = Canvas::filled(2, 1, Color::red());
+        let blue = Canvas::filled(2, 1, Color::blue());
+        let mut mask = Canvas::new(2, 1);
+        mask.write_pixel(1, 0, Color::white());
+
+        let composite = red.overlay(&blue, &mask).unwrap();
+
+        assert_eq!(composite.read_pixel(0, 0), Color::red());
+        assert!(composite.read_pixel(1, 0).approx_eq(&Color::blue(), 1e-9));
+    }
+
+    /// A canvas where every pixel has a different color, so it's easy to tell them apart.
+    fn numbered(width: u32, height: u32) -> Canvas {
+        let mut canvas = Canvas::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                canvas.write_pixel(x, y, Color(x as f64, y as f64, 0.0));
+            }
+        }
+
+        canvas
+    }
+
+    #[test]
+    fn cropping_a_region() {
+        let canvas = numbered(5, 4);
+        let cropped = canvas.crop(1, 2, 3, 2).unwrap();
+
+        assert_eq!((cropped.width, cropped.height), (3, 2));
+        for y in 0..2 {
+            for x in 0..3 {
+                assert_eq!(cropped.read_pixel(x, y), canvas.read_pixel(x + 1, y + 2));
+            }
+        }
+    }
+
+    #[test]
+    fn cropping_outside_the_canvas_is_an_error() {
+        let canvas = Canvas::new(5, 4);
+
+        assert_eq!(
+            canvas.crop(3, 0, 3, 1),
+            Err(CanvasError::OutOfBounds {
+                origin: (3, 0),
+                width: 3,
+                height: 1,
+                size: (5, 4)
+            })
+        );
+        assert!(canvas.crop(0, u32::MAX, 1, 2).is_err());
+        assert_eq!(canvas.crop(0, 0, 5, 4), Ok(canvas.clone()));
+    }
+
+    #[test]
+    fn resizing_with_nearest_neighbor_duplicates_pixels() {
+        let canvas = numbered(2, 2);
+        let resized = canvas.resize(4, 4);
+
+        for y in 0..4 {
+            for x in 0..4 {
+                assert_eq!(resized.read_pixel(x, y), canvas.read_pixel(x / 2, y / 2));
+            }
+        }
+        assert_eq!(resized.resize(2, 2), canvas);
+    }
+
+    #[test]
+    fn resizing_bilinearly_blends_neighbors() {
+        let canvas = numbered(2, 1);
+        let resized = canvas.resize_bilinear(4, 1);
+
+        // the outer pixels clamp to the edges, the inner ones blend a quarter of the way across
+        let reds = (0..4)
+            .map(|x| resized.read_pixel(x, 0).r())
+            .collect::<Vec<_>>();
+        assert_eq!(reds, vec![0.0, 0.25, 0.75, 1.0]);
+    }
+
+    #[test]
+    fn writing_png_to_a_buffer() {
+        let canvas = Canvas::new(4, 3);
+        let mut buf = std::io::Cursor::new(Vec::new());
+        canvas.write_to(&mut buf, ImageFormat::Png).unwrap();
+
+        assert!(buf
+            .get_ref()
+            .starts_with(&[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n']));
+    }
+
+    #[test]
+    fn writing_jpeg_to_a_buffer() {
+        let canvas = Canvas::new(4, 3);
+        let mut buf = std::io::Cursor::new(Vec::new());
+        canvas
+            .write_to(&mut buf, ImageOutputFormat::Jpeg(90))
+            .unwrap();
+
+        assert_eq!(
+            image::guess_format(buf.get_ref()).unwrap(),
+            ImageFormat::Jpeg
+        );
+    }
+
+    #[test]
+    #[ignore = "I don't want to save a file every time I run this test."]
+    fn can_save_canvas_files_in_other_formats() {
+        let canvas = Canvas::new(40, 20);
+        canvas
+            .export_format("/tmp/test_bmp.png", ImageFormat::Bmp)
+            .unwrap();
+        canvas.export_jpeg("/tmp/test.jpg", 80).unwrap();
+
+        let bytes = std::fs::read("/tmp/test_bmp.png").unwrap();
+        assert_eq!(image::guess_format(&bytes).unwrap(), ImageFormat::Bmp);
+    }
+
     #[test]
     #[ignore = "I don't want to save a file every time I run this test."]
     fn can_save_canvas_files() {
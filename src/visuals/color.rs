@@ -43,6 +43,73 @@ impl Color {
     pub fn blue() -> Color {
         Self(0.0, 0.0, 1.0)
     }
+
+    /// Creates a color from a `0xRRGGBB` hex code, e.g. `Color::from_hex(0xFF8800)` for orange.
+    pub fn from_hex(code: u32) -> Self {
+        Self::from_rgb8((code >> 16) as u8, (code >> 8) as u8, code as u8)
+    }
+
+    /// The color of a blackbody at `kelvin` degrees, e.g. 6500.0 for daylight or 3000.0 for a
+    /// warm incandescent bulb, with the brightest channel at (or near) full intensity. Uses Tanner
+    /// Helland's curve fit to the Planckian locus, which is good to within a few percent between
+    /// 1000K and 40000K; temperatures outside that range are clamped to it.
+    pub fn from_kelvin(kelvin: f64) -> Self {
+        let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+
+        let r = if t <= 66.0 {
+            255.0
+        } else {
+            329.698727446 * (t - 60.0).powf(-0.1332047592)
+        };
+        let g = if t <= 66.0 {
+            99.4708025861 * t.ln() - 161.1195681661
+        } else {
+            288.1221695283 * (t - 60.0).powf(-0.0755148492)
+        };
+        let b = if t >= 66.0 {
+            255.0
+        } else if t <= 19.0 {
+            0.0
+        } else {
+            138.5177312231 * (t - 10.0).ln() - 305.0447927307
+        };
+
+        Self(r / 255.0, g / 255.0, b / 255.0).clamp()
+    }
+
+    /// Creates a color from 8-bit channels, where 255 is full intensity.
+    pub fn from_rgb8(r: u8, g: u8, b: u8) -> Self {
+        Self(r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0)
+    }
+
+    /// Converts the color to 8-bit channels, clamping anything outside of `[0.0, 1.0]`. This is
+    /// how colors are written to image files.
+    pub fn to_rgb8(&self) -> (u8, u8, u8) {
+        let channel = |c: f64| (256.0 * c.clamp(0.0, 0.999)) as u8;
+
+        (channel(self.r()), channel(self.g()), channel(self.b()))
+    }
+
+    /// Checks whether every channel is within `eps` of the other color's.
+    pub fn approx_eq(&self, other: &Color, eps: f64) -> bool {
+        (self.r() - other.r()).abs() <= eps
+            && (self.g() - other.g()).abs() <= eps
+            && (self.b() - other.b()).abs() <= eps
+    }
+
+    /// Clamps each channel to `[0.0, 1.0]`.
+    pub fn clamp(&self) -> Color {
+        Self(
+            self.r().clamp(0.0, 1.0),
+            self.g().clamp(0.0, 1.0),
+            self.b().clamp(0.0, 1.0),
+        )
+    }
+
+    /// Rec. 709 relative luminance (luma) of the color, i.e. how bright it appears.
+    pub fn luminance(&self) -> f64 {
+        0.2126 * self.r() + 0.7152 * self.g() + 0.0722 * self.b()
+    }
 }
 
 impl ops::Add for Color {
@@ -133,10 +200,64 @@ mod color_tests {
         assert_eq!(f * c, Color(0.4, 0.6, 0.8));
     }
 
+    #[test]
+    fn colors_from_hex_codes() {
+        assert_eq!(Color::from_hex(0xFF0000), Color::red());
+        assert_eq!(Color::from_hex(0x00FF00), Color::green());
+        assert_eq!(Color::from_hex(0xFF8800), Color::from_rgb8(255, 136, 0));
+        assert_eq!(Color::from_hex(0xFF8800).to_rgb8(), (255, 136, 0));
+    }
+
+    #[test]
+    fn rgb8_round_trips() {
+        for rgb in [(0, 0, 0), (255, 255, 255), (12, 128, 254), (1, 100, 200)] {
+            assert_eq!(Color::from_rgb8(rgb.0, rgb.1, rgb.2).to_rgb8(), rgb);
+        }
+    }
+
+    #[test]
+    fn to_rgb8_clamps_out_of_range_channels() {
+        assert_eq!(Color(1.5, -0.5, 0.5).to_rgb8(), (255, 0, 128));
+    }
+
+    #[test]
+    fn approximately_equal_colors() {
+        let c = Color(0.1, 0.2, 0.3);
+
+        assert!(c.approx_eq(&Color(0.1 + 1e-7, 0.2, 0.3 - 1e-7), 1e-6));
+        assert!(!c.approx_eq(&Color(0.1, 0.25, 0.3), 1e-6));
+    }
+
+    #[test]
+    fn clamping_an_over_bright_color() {
+        let c = Color(1.5, -0.2, 0.5);
+        assert_eq!(c.clamp(), Color(1.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn luminance_of_primary_colors() {
+        assert_eq!(Color::red().luminance(), 0.2126);
+        assert_eq!(Color::green().luminance(), 0.7152);
+        assert_eq!(Color::blue().luminance(), 0.0722);
+        assert!((Color::white().luminance() - 1.0).abs() < 1e-10);
+    }
+
     #[test]
     fn color_hadamard_product() {
         let c1 = Color(1.0, 0.2, 0.4);
         let c2 = Color(0.9, 1.0, 0.1);
         assert!((c1 * c2 - Color(0.9, 0.2, 0.04)) < Color(1e-6, 1e-6, 1e-6));
     }
+
+    #[test]
+    fn color_temperatures_go_from_warm_to_neutral() {
+        let daylight = Color::from_kelvin(6500.0);
+        let candle = Color::from_kelvin(2000.0);
+
+        assert!(daylight.approx_eq(&Color::white(), 0.05));
+        assert_eq!(candle.r(), 1.0);
+        assert!(candle.r() > candle.g() && candle.g() > candle.b());
+        assert!(candle.b() < 0.2);
+        assert_eq!(Color::from_kelvin(100.0), Color::from_kelvin(1000.0));
+    }
 }